@@ -1,6 +1,8 @@
 use iced::widget::{container, text};
-use iced::{Element, Length, Point, Sandbox, Settings};
-use iced_node_editor::{connection, graph_container, node, Matrix};
+use iced::{keyboard, Element, Length, Point, Sandbox, Settings};
+use iced_node_editor::{
+    connection, graph_container, node, node_palette, Marquee, Matrix, PaletteEntry, Selection,
+};
 
 pub fn main() -> iced::Result {
     // To resize the the resulting canvas for web: https://github.com/iced-rs/iced/issues/1265
@@ -45,13 +47,26 @@ struct Example {
     matrix: Matrix,
     nodes: Vec<NodeState>,
     connections: Vec<(usize, usize)>,
+    selection: Selection,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 enum Message {
     ScaleChanged(f32, f32, f32),
     TranslationChanged(f32, f32),
     MoveNode(usize, f32, f32),
+    CreateNode(String, Point),
+    SelectionChanged(Selection),
+}
+
+// The nodes that can be added with the palette (double-click the canvas or press Tab)
+fn palette_entries() -> Vec<PaletteEntry> {
+    vec![
+        PaletteEntry::new("iced", "Iced"),
+        PaletteEntry::new("node", "Node").keywords(["vertex"]),
+        PaletteEntry::new("editor", "Editor"),
+        PaletteEntry::new("graph", "Graph Container").keywords(["canvas"]),
+    ]
 }
 
 impl Sandbox for Example {
@@ -75,6 +90,7 @@ impl Sandbox for Example {
                 },
             ],
             connections: vec![(0, 1), (1, 2)],
+            selection: Selection::new(),
         }
     }
 
@@ -102,10 +118,19 @@ impl Sandbox for Example {
                     self.nodes[i].position.y + y / self.matrix.get_scale(),
                 );
             }
+            Message::CreateNode(id, position) => {
+                if let Some(entry) = palette_entries().into_iter().find(|t| t.id == id) {
+                    self.nodes.push(NodeState {
+                        position,
+                        text: entry.name,
+                    });
+                }
+            }
+            Message::SelectionChanged(selection) => self.selection = selection,
         }
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         let mut graph_content = Vec::new();
 
         for (i, n) in self.nodes.iter().enumerate() {
@@ -140,7 +165,15 @@ impl Sandbox for Example {
         container(
             graph_container(graph_content)
                 .on_translate(|p| Message::TranslationChanged(p.0, p.1))
+                // Flicking the canvas keeps it gliding for a moment
+                .kinetic_panning(0.95)
                 .on_scale(Message::ScaleChanged)
+                .node_palette(node_palette(palette_entries(), Message::CreateNode))
+                // Click nodes to select them, Shift + click to select several, or draw a lasso
+                // around them with Ctrl + drag
+                .selection(&self.selection)
+                .on_selection_change(Message::SelectionChanged)
+                .marquee(Marquee::Lasso, keyboard::Modifiers::CTRL)
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .matrix(self.matrix),
@@ -0,0 +1,108 @@
+use iced::widget::{button, column, row, text};
+use iced::{Background, Color, Element, Length, Point, Sandbox, Settings, Vector};
+use iced_node_editor::styles::node::Appearance;
+use iced_node_editor::{node, BadgePosition, EditorController, EditorEvent, Socket, SocketRole};
+
+pub fn main() -> iced::Result {
+    Example::run(Settings::default())
+}
+
+struct Example {
+    editor: EditorController<String>,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Editor(EditorEvent),
+    AddNode,
+    RemoveSelected,
+    SelectDownstream,
+    DuplicateSelected,
+}
+
+impl Sandbox for Example {
+    type Message = Message;
+
+    fn new() -> Self {
+        let mut editor = EditorController::new();
+        editor
+            .graph_mut()
+            .add_node(Point::new(50.0, 50.0), String::from("Hello"));
+        editor
+            .graph_mut()
+            .add_node(Point::new(300.0, 150.0), String::from("World"));
+
+        Example { editor }
+    }
+
+    fn title(&self) -> String {
+        String::from("Iced Node Editor - Controller Example")
+    }
+
+    fn theme(&self) -> iced::Theme {
+        iced::Theme::Dark
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            // Panning, zooming, moving and selecting nodes, and connecting sockets are all taken
+            // care of by the controller
+            Message::Editor(event) => self.editor.update(event),
+            Message::AddNode => {
+                let count = self.editor.graph().nodes().len();
+                self.editor.graph_mut().add_node(
+                    Point::new(50.0 + count as f32 * 20.0, 250.0),
+                    format!("Node #{count}"),
+                );
+            }
+            Message::RemoveSelected => {
+                self.editor.remove_selected();
+            }
+            Message::SelectDownstream => self.editor.select_downstream(),
+            Message::DuplicateSelected => self.editor.duplicate_selected(Vector::new(20.0, 20.0)),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let graph = self.editor.view(
+            |_, name, selected| {
+                let node = node(text(name))
+                    .padding(10.0)
+                    .width(Length::Fixed(150.0))
+                    .sockets(vec![
+                        Socket::new(SocketRole::In, text("in")),
+                        Socket::new(SocketRole::Out, text("out")),
+                    ]);
+
+                if selected {
+                    // Marks the selected nodes with a dot on their top right corner as well
+                    node.badge(BadgePosition::TopRight, text("●").size(12))
+                        .appearance(Appearance {
+                            background: Some(Background::Color(Color::from_rgb8(0x2b, 0x2d, 0x31))),
+                            border_radius: 5.0,
+                            border_width: 2.0,
+                            border_color: Color::from_rgb8(0x4c, 0x9a, 0xff),
+                            ..Appearance::default()
+                        })
+                } else {
+                    node
+                }
+            },
+            Message::Editor,
+        );
+
+        column![
+            row![
+                button("Add node").on_press(Message::AddNode),
+                button("Remove selected").on_press(Message::RemoveSelected),
+                button("Select downstream").on_press(Message::SelectDownstream),
+                button("Duplicate selected").on_press(Message::DuplicateSelected),
+            ]
+            .spacing(10),
+            graph.width(Length::Fill).height(Length::Fill),
+        ]
+        .spacing(10)
+        .padding(10)
+        .into()
+    }
+}
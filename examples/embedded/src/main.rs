@@ -0,0 +1,161 @@
+use iced::widget::{column, container, scrollable, text};
+use iced::{keyboard, Element, Length, Point, Sandbox, Settings};
+use iced_node_editor::{connection, graph_container, node, Bindings, Matrix};
+
+pub fn main() -> iced::Result {
+    // To resize the the resulting canvas for web: https://github.com/iced-rs/iced/issues/1265
+    #[cfg(target_arch = "wasm32")]
+    {
+        let window = web_sys::window().unwrap();
+        let (width, height) = (
+            (window.inner_width().unwrap().as_f64().unwrap()) as u32,
+            (window.inner_height().unwrap().as_f64().unwrap()) as u32,
+        );
+
+        Example::run(Settings {
+            window: iced::window::Settings {
+                size: (width, height),
+                ..Default::default()
+            },
+            ..Default::default()
+        })?;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    Example::run(Settings {
+        window: iced::window::Settings {
+            size: iced::Size {
+                width: 800.0,
+                height: 600.0,
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    })?;
+
+    Ok(())
+}
+
+struct NodeState {
+    position: Point,
+    text: String,
+}
+
+struct Example {
+    matrix: Matrix,
+    nodes: Vec<NodeState>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Message {
+    ScaleChanged(f32, f32, f32),
+    TranslationChanged(f32, f32),
+    MoveNode(usize, Point),
+}
+
+const NODE_WIDTH: f32 = 150.0;
+const NODE_HEIGHT: f32 = 60.0;
+
+impl Sandbox for Example {
+    type Message = Message;
+
+    fn new() -> Self {
+        Example {
+            matrix: Matrix::identity(),
+            nodes: vec![
+                NodeState {
+                    position: Point::new(50.0, 50.0),
+                    text: String::from("Scroll"),
+                },
+                NodeState {
+                    position: Point::new(300.0, 150.0),
+                    text: String::from("Ctrl + Scroll"),
+                },
+            ],
+        }
+    }
+
+    fn title(&self) -> String {
+        String::from("Iced Graph Editor - Embedded Example")
+    }
+
+    fn theme(&self) -> iced::Theme {
+        iced::Theme::Dark
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::ScaleChanged(x, y, scale) => {
+                self.matrix = self
+                    .matrix
+                    .translate(-x, -y)
+                    .scale(if scale > 0.0 { 1.2 } else { 1.0 / 1.2 })
+                    .translate(x, y);
+            }
+            Message::TranslationChanged(x, y) => self.matrix = self.matrix.translate(x, y),
+            Message::MoveNode(i, position) => self.nodes[i].position = position,
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let mut graph_content = Vec::new();
+
+        for (i, n) in self.nodes.iter().enumerate() {
+            graph_content.push(
+                node(text(&n.text))
+                    .center_x()
+                    .center_y()
+                    // Reports the new graph-space position, so it can be stored as is
+                    .on_move(move |position| Message::MoveNode(i, position))
+                    .width(Length::Fixed(NODE_WIDTH))
+                    .height(Length::Fixed(NODE_HEIGHT))
+                    .position(n.position)
+                    .into(),
+            );
+        }
+
+        let (from, to) = (&self.nodes[0], &self.nodes[1]);
+        graph_content.push(
+            connection(
+                Point::new(
+                    from.position.x + NODE_WIDTH,
+                    from.position.y + NODE_HEIGHT / 2.0,
+                ),
+                Point::new(to.position.x, to.position.y + NODE_HEIGHT / 2.0),
+            )
+            .into(),
+        );
+
+        // The graph only zooms while Ctrl is held. Otherwise, the wheel scrolls the page, even
+        // while the cursor is over the graph.
+        let graph = graph_container(graph_content)
+            .on_translate(|p| Message::TranslationChanged(p.0, p.1))
+            .on_scale(Message::ScaleChanged)
+            .bindings(Bindings::zoom_with(keyboard::Modifiers::CTRL))
+            .width(Length::Fill)
+            .height(Length::Fixed(400.0))
+            .matrix(self.matrix);
+
+        let filler = |title: &str| {
+            column![
+                text(title).size(24),
+                text(
+                    "The graph editor is just another widget, so it can be placed anywhere in \
+                     the layout of an application. Scroll down to find it, or scroll past it."
+                ),
+            ]
+            .spacing(10)
+            .height(Length::Fixed(300.0))
+        };
+
+        scrollable(
+            container(
+                column![filler("Above the graph"), graph, filler("Below the graph")].spacing(20),
+            )
+            .padding(20),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
@@ -1,10 +1,10 @@
 use iced::widget::{button, container, text};
 use iced::{Color, Element, Length, Padding, Point, Sandbox, Settings};
 use iced_node_editor::{
-    graph_container, node, Connection, Endpoint, GraphNodeElement, Link, LogicalEndpoint, Matrix,
-    Socket, SocketRole, SocketSide,
+    graph_container, nodes_from_iter, Connection, Endpoint, GraphNodeElement, Link,
+    LogicalEndpoint, Matrix, NodeTemplate, Socket, SocketRole, SocketRowStyle, SocketSide,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub fn main() -> iced::Result {
     // To resize the the resulting canvas for web: https://github.com/iced-rs/iced/issues/1265
@@ -45,6 +45,7 @@ struct NodeState {
     text: String,
     button: bool,
     sockets: (Vec<SocketType>, Vec<SocketType>),
+    bypassed: bool,
 }
 
 // Define some types that sockets may have.
@@ -72,6 +73,9 @@ struct Example {
     // in the value type.
     connections: HashMap<(usize, usize), (usize, usize)>,
 
+    // The connections with a breakpoint, keyed like `connections`
+    breakpoints: HashSet<(usize, usize)>,
+
     // Our own representation of the “dangling connection” — the connection that follows the user's
     // mouse pointer in the process of connecting two sockets with each other.
     // It is divided into two parts:
@@ -89,9 +93,12 @@ enum Message {
     TranslationChanged(f32, f32),
     MoveNode(usize, f32, f32),
     Connect(Link),
-    Disconnect(LogicalEndpoint, Point),
+    Disconnect(Point, Option<Link>),
     Dangling(Option<(LogicalEndpoint, Link)>),
     ButtonPressed,
+    AddInput(usize),
+    ToggleBreakpoint(Link, bool),
+    Bypass(usize, bool),
 }
 
 impl Sandbox for Example {
@@ -111,6 +118,7 @@ impl Sandbox for Example {
                     text: String::from("Iced"),
                     button: false,
                     sockets: (vec![], vec![SocketType::BlueSquare, SocketType::RedCircle]),
+                    bypassed: false,
                 },
                 // Node #1
                 NodeState {
@@ -125,6 +133,7 @@ impl Sandbox for Example {
                             SocketType::Button,
                         ],
                     ),
+                    bypassed: false,
                 },
                 // Node #2
                 NodeState {
@@ -132,9 +141,11 @@ impl Sandbox for Example {
                     text: String::from("Editor"),
                     button: true,
                     sockets: (vec![SocketType::BlueSquare, SocketType::RedCircle], vec![]),
+                    bypassed: false,
                 },
             ],
             connections,
+            breakpoints: HashSet::new(),
             dangling_source: None,
             dangling_connection: None,
         }
@@ -177,37 +188,31 @@ impl Sandbox for Example {
                     (start.node_index, start.socket_index),
                 );
             }
-            Message::Disconnect(endpoint, new_dangling_end_position) => {
-                // Remove the connection that ends in the socket, if it exists
-                if let Some((start_node_index, start_socket_index)) = self
-                    .connections
-                    .remove(&(endpoint.node_index, endpoint.socket_index))
-                {
-                    // If there was a connection, turn it into a dangling one, such that the user
-                    // may connect it to some other socket instead. First, set the source of the
-                    // new dangling connection
-                    let new_dangling_source = LogicalEndpoint {
-                        node_index: start_node_index,
-                        role: SocketRole::Out,
-                        socket_index: start_socket_index,
-                    };
-                    self.dangling_source = Some(new_dangling_source);
-
-                    // Construct a link for the dangling connection.
-                    //
-                    // This is not necessary just for correct behaviour.
-                    // The node editor would emit the `Dangling` event with the full `Link`
-                    // as soon as the mouse is moved anyway.
-                    // However, if we do not do this, no dangling connection will be drawn until
-                    // the mouse is moved. To be able to avoid this slight jank, the library
-                    // provides us with a destination point to construct a new dangling connection
-                    // for ourselves.
-                    self.dangling_connection = Some(Link::from_unordered(
-                        Endpoint::Socket(new_dangling_source),
-                        Endpoint::Absolute(new_dangling_end_position),
-                    ));
-                }
+            Message::Disconnect(new_dangling_end_position, Some(link)) => {
+                // Remove the connection that was grabbed by its end
+                let (start, end) = link.unwrap_sockets();
+                self.connections.remove(&(end.node_index, end.socket_index));
+
+                // Turn it into a dangling one, such that the user may connect it to some other
+                // socket instead. First, set the source of the new dangling connection
+                self.dangling_source = Some(*start);
+
+                // Construct a link for the dangling connection.
+                //
+                // This is not necessary just for correct behaviour.
+                // The node editor would emit the `Dangling` event with the full `Link`
+                // as soon as the mouse is moved anyway.
+                // However, if we do not do this, no dangling connection will be drawn until
+                // the mouse is moved. To be able to avoid this slight jank, the library
+                // provides us with a destination point to construct a new dangling connection
+                // for ourselves.
+                self.dangling_connection = Some(Link::from_unordered(
+                    Endpoint::Socket(*start),
+                    Endpoint::Absolute(new_dangling_end_position),
+                ));
             }
+            // Nothing to disconnect
+            Message::Disconnect(_, None) => {}
             Message::Dangling(Some((source, link))) => {
                 // The dangling connection is updated, perhaps because the user moved their mouse
                 self.dangling_source = Some(source);
@@ -220,46 +225,69 @@ impl Sandbox for Example {
                 self.dangling_connection = None;
             }
             Message::ButtonPressed => println!("Button was pressed."),
+            Message::AddInput(i) => self.nodes[i].sockets.0.push(SocketType::RedCircle),
+            Message::ToggleBreakpoint(link, set) => {
+                let (_, end) = link.unwrap_sockets();
+                let key = (end.node_index, end.socket_index);
+                if set {
+                    self.breakpoints.insert(key);
+                } else {
+                    self.breakpoints.remove(&key);
+                }
+            }
+            Message::Bypass(i, bypassed) => self.nodes[i].bypassed = bypassed,
         }
     }
 
-    fn view(&self) -> Element<Message> {
-        let mut graph_content: Vec<GraphNodeElement<Message, _, _>> = vec![];
-
-        // Convert our own node representations into widgets
-        for (i, n) in self.nodes.iter().enumerate() {
-            // Create sockets from our lists of `SocketType`s
-            let (in_sockets, out_sockets) = &n.sockets;
-            let mut node_sockets = vec![];
-            for (role, sockets) in [(SocketRole::In, in_sockets), (SocketRole::Out, out_sockets)] {
-                for socket_type in sockets {
-                    // Call our own utility function to create the socket
-                    let new_socket = make_socket(role, socket_type);
-                    node_sockets.push(new_socket);
+    fn view(&self) -> Element<'_, Message> {
+        // All our nodes share these settings, so they are only written down once
+        let template = NodeTemplate::new(|node| {
+            node.padding(10.0)
+                .center_x()
+                .center_y()
+                .width(Length::Fixed(200.0))
+                // The height is left to `Length::Shrink`, so the nodes grow with their sockets
+                .min_socket_row_height(24.0)
+        });
+
+        // Convert our own node representations into widgets. `i` is the index of the node in the
+        // graph, which is what connections refer to.
+        let mut graph_content: Vec<GraphNodeElement<Message, _, _>> =
+            nodes_from_iter(&self.nodes, |i, n| {
+                // Create sockets from our lists of `SocketType`s
+                let (in_sockets, out_sockets) = &n.sockets;
+                let mut node_sockets = vec![];
+                for (role, sockets) in
+                    [(SocketRole::In, in_sockets), (SocketRole::Out, out_sockets)]
+                {
+                    for socket_type in sockets {
+                        // Call our own utility function to create the socket
+                        let new_socket = make_socket(role, socket_type);
+                        node_sockets.push(new_socket);
+                    }
                 }
-            }
 
-            let node = if n.button {
-                node(iced::widget::column![
-                    text(&n.text),
-                    button("Button").on_press(Message::ButtonPressed)
-                ])
-            } else {
-                node(text(&n.text))
-            };
+                let node = if n.button {
+                    template.instantiate(
+                        n.position,
+                        iced::widget::column![
+                            text(&n.text),
+                            button("Button").on_press(Message::ButtonPressed)
+                        ],
+                    )
+                } else {
+                    template.instantiate(n.position, text(&n.text))
+                };
 
-            graph_content.push(
-                node.padding(Padding::from(10.0))
-                    .sockets(node_sockets)
-                    .center_x()
-                    .center_y()
+                // The sockets differ between our nodes, so they are not part of the template
+                node.sockets(node_sockets)
                     .on_translate(move |p| Message::MoveNode(i, p.0, p.1))
-                    .width(Length::Fixed(200.0))
-                    .height(Length::Fixed(75.0))
-                    .position(n.position)
-                    .into(),
-            );
-        }
+                    // Shows a "+" button below the sockets to add more inputs
+                    .on_add_socket(SocketRole::In, move || Message::AddInput(i))
+                    // Press M over a node to bypass it
+                    .bypassed(n.bypassed)
+                    .on_bypass_toggle(move |bypassed| Message::Bypass(i, bypassed))
+            });
 
         // Convert our own `HashMap` representation of connections into the one used by the library.
         // Here it is important that this happens *after* the nodes have been added.
@@ -281,6 +309,11 @@ impl Sandbox for Example {
                         socket_index: *end_socket_index,
                     }),
                 )
+                // Click the ring in the middle of a connection to set a breakpoint on it
+                .breakpoint(
+                    self.breakpoints
+                        .contains(&(*end_node_index, *end_socket_index)),
+                )
                 .into(),
             );
         }
@@ -293,11 +326,18 @@ impl Sandbox for Example {
         container(
             graph_container(graph_content)
                 .dangling_source(self.dangling_source)
+                .trace_connections(true)
                 .on_translate(|p| Message::TranslationChanged(p.0, p.1))
                 .on_scale(Message::ScaleChanged)
                 .on_connect(Message::Connect)
-                .on_disconnect(Message::Disconnect)
+                .on_disconnect(|_, position, link| Message::Disconnect(position, link))
                 .on_dangling(Message::Dangling)
+                .on_breakpoint_toggle(Message::ToggleBreakpoint)
+                .auto_pan(40.0, 600.0)
+                .clamp_to_content(200.0)
+                .keyboard_navigation(true)
+                .acyclic(true)
+                .min_socket_scale(0.3)
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .matrix(self.matrix),
@@ -334,7 +374,7 @@ fn make_socket<'a>(
 
     // The style of the blob is not determined by a style sheet, but by properties of the `Socket`
     // itself.
-    let (blob_border_radius, blob_color, content) = match socket_type {
+    let (blob_border_radius, blob_color, content): (_, _, Element<_>) = match socket_type {
         SocketType::BlueSquare => (
             0.0,
             Color::from_rgb(0.0, 0.1, 0.8),
@@ -352,18 +392,23 @@ fn make_socket<'a>(
         ),
     };
 
-    Socket {
-        role,
-        blob_side,
-        content_alignment,
-
-        blob_radius: BLOB_RADIUS,
-        blob_border_radius,
-        blob_color,
-        content, // Arbitrary widgets can be used here.
-
-        min_height: 0.0,
-        max_height: f32::INFINITY,
-        blob_border_color: None, // If `None`, the one from the style sheet will be used.
-    }
+    // `Socket::new` picks defaults for everything that is not set explicitly: the blob is round
+    // and gray, and it is placed on the left side for inputs and on the right side for outputs.
+    // The blob border color comes from the style sheet, and an element like an icon could be
+    // drawn instead of the colored blob with `.blob(...)`.
+    Socket::new(role, content) // Arbitrary widgets can be used as the content.
+        .blob_side(blob_side)
+        .content_alignment(content_alignment)
+        .blob_radius(BLOB_RADIUS)
+        .blob_border_radius(blob_border_radius)
+        .blob_color(blob_color)
+        .hit_radius(BLOB_RADIUS * 2.0) // Makes the small blobs easier to grab.
+        // Highlights the row under the cursor, which makes it easier to see which socket a
+        // connection would be made to.
+        .row_style(SocketRowStyle {
+            hovered_background: Some(Color::from_rgba(0.5, 0.5, 0.5, 0.15)),
+            border_radius: 3.0,
+            padding: Padding::from([0.0, 4.0]),
+            ..SocketRowStyle::default()
+        })
 }
@@ -0,0 +1,226 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use iced::widget::{checkbox, column, row, text};
+use iced::{
+    executor, window, Application, Command, Element, Length, Point, Settings, Subscription, Theme,
+};
+use iced_node_editor::{
+    node, Bindings, EditorController, EditorEvent, Endpoint, Link, Socket, SocketRole,
+};
+
+const DEFAULT_NODES: usize = 500;
+const DEFAULT_LINKS: usize = 1000;
+
+// Nodes are placed on a grid with this many columns
+const COLUMNS: usize = 40;
+const NODE_WIDTH: f32 = 150.0;
+
+pub fn main() -> iced::Result {
+    Example::run(Settings {
+        window: iced::window::Settings {
+            size: iced::Size {
+                width: 1200.0,
+                height: 800.0,
+            },
+            ..Default::default()
+        },
+        ..Settings::with_flags(Config::from_env())
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Config {
+    nodes: usize,
+    links: usize,
+}
+
+impl Config {
+    // `stress_example [nodes] [links]`; missing arguments are taken from the `STRESS_NODES` and
+    // `STRESS_LINKS` environment variables, and then from the defaults
+    fn from_env() -> Self {
+        let mut args = std::env::args().skip(1);
+        let mut value = |variable: &str, default: usize| {
+            args.next()
+                .or_else(|| std::env::var(variable).ok())
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default)
+        };
+
+        Config {
+            nodes: value("STRESS_NODES", DEFAULT_NODES).max(1),
+            links: value("STRESS_LINKS", DEFAULT_LINKS),
+        }
+    }
+}
+
+struct Example {
+    config: Config,
+    editor: EditorController<String>,
+    // When the recent frames were drawn, to count the frames per second
+    frames: VecDeque<Instant>,
+    start: Instant,
+    auto_pan: bool,
+    auto_zoom: bool,
+    auto_drag: bool,
+    bundling: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Editor(EditorEvent),
+    Frame(Instant),
+    AutoPan(bool),
+    AutoZoom(bool),
+    AutoDrag(bool),
+    Bundling(bool),
+}
+
+// The same graph for the same configuration: links are chosen by a fixed pseudo-random sequence
+fn generate(config: Config) -> EditorController<String> {
+    let mut editor = EditorController::new();
+    let graph = editor.graph_mut();
+    for index in 0..config.nodes {
+        graph.add_node(grid_position(index), format!("Node #{index}"));
+    }
+
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed as usize % config.nodes
+    };
+    for _ in 0..config.links {
+        let (from, to) = (next(), next());
+        graph.add_link(Link::from_unordered(
+            Endpoint::socket(from, SocketRole::Out, 0),
+            Endpoint::socket(to, SocketRole::In, 0),
+        ));
+    }
+
+    editor
+}
+
+fn grid_position(index: usize) -> Point {
+    Point::new(
+        (index % COLUMNS) as f32 * 220.0,
+        (index / COLUMNS) as f32 * 120.0,
+    )
+}
+
+impl Application for Example {
+    type Executor = executor::Default;
+    type Message = Message;
+    type Theme = Theme;
+    type Flags = Config;
+
+    fn new(config: Config) -> (Self, Command<Message>) {
+        let example = Example {
+            config,
+            editor: generate(config),
+            frames: VecDeque::new(),
+            start: Instant::now(),
+            auto_pan: false,
+            auto_zoom: false,
+            auto_drag: false,
+            bundling: false,
+        };
+
+        (example, Command::none())
+    }
+
+    fn title(&self) -> String {
+        String::from("Iced Node Editor - Stress Example")
+    }
+
+    fn theme(&self) -> Theme {
+        Theme::Dark
+    }
+
+    fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::Editor(event) => self.editor.update(event),
+            Message::Frame(now) => {
+                self.frames.push_back(now);
+                while let Some(first) = self.frames.front() {
+                    if now.duration_since(*first) <= Duration::from_secs(1) {
+                        break;
+                    }
+                    self.frames.pop_front();
+                }
+
+                let t = now.duration_since(self.start).as_secs_f32();
+                if self.auto_pan {
+                    self.editor
+                        .update(EditorEvent::Translated(t.cos() * 4.0, t.sin() * 4.0));
+                }
+                if self.auto_zoom {
+                    // Alternates between zooming in and out around the middle of the window
+                    let direction = (t * 0.5).sin();
+                    self.editor
+                        .update(EditorEvent::Scaled(600.0, 400.0, direction));
+                }
+                if self.auto_drag {
+                    let origin = grid_position(0);
+                    let position = Point::new(
+                        origin.x + (t * 2.0).cos() * 100.0,
+                        origin.y + (t * 2.0).sin() * 100.0,
+                    );
+                    self.editor.graph_mut().move_node(0, position);
+                }
+            }
+            Message::AutoPan(enabled) => self.auto_pan = enabled,
+            Message::AutoZoom(enabled) => self.auto_zoom = enabled,
+            Message::AutoDrag(enabled) => self.auto_drag = enabled,
+            Message::Bundling(enabled) => self.bundling = enabled,
+        }
+
+        Command::none()
+    }
+
+    // Asks for every frame, so that the frame rate is measured even while nothing moves
+    fn subscription(&self) -> Subscription<Message> {
+        window::frames().map(Message::Frame)
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let graph = self.editor.view(
+            |_, name, _| {
+                node(text(name))
+                    .padding(10.0)
+                    .width(Length::Fixed(NODE_WIDTH))
+                    .sockets(vec![
+                        Socket::new(SocketRole::In, text("in")),
+                        Socket::new(SocketRole::Out, text("out")),
+                    ])
+            },
+            Message::Editor,
+        );
+
+        column![
+            row![
+                text(format!(
+                    "{} nodes, {} links: {} FPS",
+                    self.config.nodes,
+                    self.config.links,
+                    self.frames.len()
+                )),
+                checkbox("Pan", self.auto_pan).on_toggle(Message::AutoPan),
+                checkbox("Zoom", self.auto_zoom).on_toggle(Message::AutoZoom),
+                checkbox("Drag", self.auto_drag).on_toggle(Message::AutoDrag),
+                checkbox("Bundle edges", self.bundling).on_toggle(Message::Bundling),
+            ]
+            .spacing(20),
+            // The wheel pans around the large graph, Ctrl + wheel zooms
+            graph
+                .bindings(Bindings::editor())
+                .edge_bundling(if self.bundling { 0.8 } else { 0.0 })
+                .width(Length::Fill)
+                .height(Length::Fill),
+        ]
+        .spacing(10)
+        .padding(10)
+        .into()
+    }
+}
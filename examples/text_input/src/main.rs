@@ -0,0 +1,169 @@
+use iced::widget::{column, container, text, text_input};
+use iced::{Element, Length, Point, Sandbox, Settings};
+use iced_node_editor::{connection, graph_container, node, Matrix};
+
+pub fn main() -> iced::Result {
+    // To resize the the resulting canvas for web: https://github.com/iced-rs/iced/issues/1265
+    #[cfg(target_arch = "wasm32")]
+    {
+        let window = web_sys::window().unwrap();
+        let (width, height) = (
+            (window.inner_width().unwrap().as_f64().unwrap()) as u32,
+            (window.inner_height().unwrap().as_f64().unwrap()) as u32,
+        );
+
+        Example::run(Settings {
+            window: iced::window::Settings {
+                size: (width, height),
+                ..Default::default()
+            },
+            ..Default::default()
+        })?;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    Example::run(Settings {
+        window: iced::window::Settings {
+            size: iced::Size {
+                width: 800.0,
+                height: 600.0,
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    })?;
+
+    Ok(())
+}
+
+struct NodeState {
+    position: Point,
+    title: String,
+}
+
+struct Example {
+    matrix: Matrix,
+    nodes: Vec<NodeState>,
+    connections: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    ScaleChanged(f32, f32, f32),
+    TranslationChanged(f32, f32),
+    MoveNode(usize, f32, f32),
+    TitleChanged(usize, String),
+}
+
+impl Sandbox for Example {
+    type Message = Message;
+
+    fn new() -> Self {
+        Example {
+            matrix: Matrix::identity(),
+            nodes: vec![
+                NodeState {
+                    position: Point::new(0.0, 0.0),
+                    title: String::from("Iced"),
+                },
+                NodeState {
+                    position: Point::new(250.0, 250.0),
+                    title: String::from("Node"),
+                },
+                NodeState {
+                    position: Point::new(500.0, 250.0),
+                    title: String::from("Editor"),
+                },
+            ],
+            connections: vec![(0, 1), (1, 2)],
+        }
+    }
+
+    fn title(&self) -> String {
+        String::from("Iced Graph Editor - Text Input Example")
+    }
+
+    fn theme(&self) -> iced::Theme {
+        iced::Theme::Dark
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::ScaleChanged(x, y, scale) => {
+                self.matrix = self
+                    .matrix
+                    .translate(-x, -y)
+                    .scale(if scale > 0.0 { 1.2 } else { 1.0 / 1.2 })
+                    .translate(x, y);
+            }
+            Message::TranslationChanged(x, y) => self.matrix = self.matrix.translate(x, y),
+            Message::MoveNode(i, x, y) => {
+                self.nodes[i].position = Point::new(
+                    self.nodes[i].position.x + x / self.matrix.get_scale(),
+                    self.nodes[i].position.y + y / self.matrix.get_scale(),
+                );
+            }
+            Message::TitleChanged(i, title) => self.nodes[i].title = title,
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let mut graph_content = Vec::new();
+
+        for (i, n) in self.nodes.iter().enumerate() {
+            // The title can be edited in place; dragging the rest of the node moves it
+            graph_content.push(
+                node(
+                    column![
+                        text_input("Title", &n.title)
+                            .on_input(move |title| Message::TitleChanged(i, title))
+                            .width(Length::Fixed(150.0)),
+                        text(format!("{} characters", n.title.chars().count())).size(12),
+                    ]
+                    .spacing(5),
+                )
+                .padding(10.0)
+                // Shown below the node, which grows to make room for it
+                .error(
+                    n.title
+                        .trim()
+                        .is_empty()
+                        .then(|| String::from("The title must not be empty")),
+                )
+                .on_translate(move |p| Message::MoveNode(i, p.0, p.1))
+                .width(Length::Fixed(200.0))
+                .height(Length::Fixed(75.0))
+                .position(n.position)
+                .into(),
+            );
+        }
+
+        for (from_index, to_index) in self.connections.iter() {
+            graph_content.push(
+                connection(
+                    Point::new(
+                        self.nodes[*from_index].position.x + 200.0,
+                        self.nodes[*from_index].position.y + 37.5,
+                    ),
+                    Point::new(
+                        self.nodes[*to_index].position.x,
+                        self.nodes[*to_index].position.y + 37.5,
+                    ),
+                )
+                .into(),
+            );
+        }
+
+        container(
+            graph_container(graph_content)
+                .on_translate(|p| Message::TranslationChanged(p.0, p.1))
+                .on_scale(Message::ScaleChanged)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .matrix(self.matrix),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+}
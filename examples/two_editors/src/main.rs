@@ -0,0 +1,212 @@
+use iced::widget::{column, container, row, text};
+use iced::{Element, Length, Point, Sandbox, Settings};
+use iced_node_editor::{
+    graph_container, node, nodes_from_iter, Connection, Endpoint, GraphNodeElement, Link,
+    LogicalEndpoint, Matrix, Socket, SocketRole,
+};
+
+pub fn main() -> iced::Result {
+    // To resize the the resulting canvas for web: https://github.com/iced-rs/iced/issues/1265
+    #[cfg(target_arch = "wasm32")]
+    {
+        let window = web_sys::window().unwrap();
+        let (width, height) = (
+            (window.inner_width().unwrap().as_f64().unwrap()) as u32,
+            (window.inner_height().unwrap().as_f64().unwrap()) as u32,
+        );
+
+        Example::run(Settings {
+            window: iced::window::Settings {
+                size: (width, height),
+                ..Default::default()
+            },
+            ..Default::default()
+        })?;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    Example::run(Settings {
+        window: iced::window::Settings {
+            size: iced::Size {
+                width: 1200.0,
+                height: 600.0,
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    })?;
+
+    Ok(())
+}
+
+struct NodeState {
+    position: Point,
+    text: String,
+}
+
+// Everything one editor needs. The two editors of this example share nothing, so every one of
+// them can be panned, zoomed and edited on its own.
+struct Editor {
+    title: String,
+    matrix: Matrix,
+    nodes: Vec<NodeState>,
+    connections: Vec<Link>,
+    dangling_source: Option<LogicalEndpoint>,
+    dangling_connection: Option<Link>,
+}
+
+struct Example {
+    editors: Vec<Editor>,
+}
+
+#[derive(Debug, Clone)]
+enum EditorMessage {
+    ScaleChanged(f32, f32, f32),
+    TranslationChanged(f32, f32),
+    MoveNode(usize, f32, f32),
+    Connect(Link),
+    Disconnect(Option<Link>),
+    Dangling(Option<(LogicalEndpoint, Link)>),
+}
+
+// Messages of an editor are tagged with its index, so they end up with the right one
+#[derive(Debug, Clone)]
+struct Message(usize, EditorMessage);
+
+impl Editor {
+    fn new(title: &str, names: &[&str]) -> Self {
+        Editor {
+            title: String::from(title),
+            matrix: Matrix::identity(),
+            nodes: names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| NodeState {
+                    position: Point::new(50.0 + i as f32 * 200.0, 50.0 + i as f32 * 100.0),
+                    text: String::from(*name),
+                })
+                .collect(),
+            connections: vec![],
+            dangling_source: None,
+            dangling_connection: None,
+        }
+    }
+
+    fn update(&mut self, message: EditorMessage) {
+        match message {
+            EditorMessage::ScaleChanged(x, y, scale) => {
+                self.matrix = self
+                    .matrix
+                    .translate(-x, -y)
+                    .scale(if scale > 0.0 { 1.05 } else { 1.0 / 1.05 })
+                    .translate(x, y);
+            }
+            EditorMessage::TranslationChanged(x, y) => {
+                self.matrix = self.matrix.translate(x, y);
+            }
+            EditorMessage::MoveNode(i, x, y) => {
+                self.nodes[i].position = Point::new(
+                    self.nodes[i].position.x + x / self.matrix.get_scale(),
+                    self.nodes[i].position.y + y / self.matrix.get_scale(),
+                );
+            }
+            EditorMessage::Connect(link) => self.connections.push(link),
+            EditorMessage::Disconnect(Some(link)) => {
+                self.connections.retain(|connection| *connection != link);
+            }
+            EditorMessage::Disconnect(None) => {}
+            EditorMessage::Dangling(Some((source, link))) => {
+                self.dangling_source = Some(source);
+                self.dangling_connection = Some(link);
+            }
+            EditorMessage::Dangling(None) => {
+                self.dangling_source = None;
+                self.dangling_connection = None;
+            }
+        }
+    }
+
+    fn view(&self) -> Element<'_, EditorMessage> {
+        let mut graph_content: Vec<GraphNodeElement<EditorMessage, _, _>> =
+            nodes_from_iter(&self.nodes, |i, n| {
+                node(text(&n.text))
+                    .padding(10.0)
+                    .center_x()
+                    .center_y()
+                    .width(Length::Fixed(120.0))
+                    .sockets(vec![
+                        Socket::new(SocketRole::In, text("in")),
+                        Socket::new(SocketRole::Out, text("out")),
+                    ])
+                    .position(n.position)
+                    .on_translate(move |p| EditorMessage::MoveNode(i, p.0, p.1))
+            });
+
+        for link in &self.connections {
+            graph_content.push(Connection::new(link.clone()).into());
+        }
+        if let Some(link) = &self.dangling_connection {
+            graph_content.push(Connection::new(link.clone()).into());
+        }
+
+        column![
+            text(&self.title).size(20),
+            graph_container(graph_content)
+                .dangling_source(self.dangling_source)
+                .on_translate(|p| EditorMessage::TranslationChanged(p.0, p.1))
+                .on_scale(EditorMessage::ScaleChanged)
+                .on_connect(EditorMessage::Connect)
+                .on_disconnect(|_, _, link| EditorMessage::Disconnect(link))
+                .on_dangling(EditorMessage::Dangling)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .matrix(self.matrix),
+        ]
+        .spacing(10)
+        .into()
+    }
+}
+
+impl Sandbox for Example {
+    type Message = Message;
+
+    fn new() -> Self {
+        let mut left = Editor::new("Left", &["Source", "Filter", "Sink"]);
+        left.connections.push(Link::from_unordered(
+            Endpoint::socket(0, SocketRole::Out, 0),
+            Endpoint::socket(1, SocketRole::In, 0),
+        ));
+        let right = Editor::new("Right", &["Input", "Output"]);
+
+        Example {
+            editors: vec![left, right],
+        }
+    }
+
+    fn title(&self) -> String {
+        String::from("Iced Node Editor - Two Editors Example")
+    }
+
+    fn theme(&self) -> iced::Theme {
+        iced::Theme::Dark
+    }
+
+    fn update(&mut self, Message(index, message): Message) {
+        self.editors[index].update(message);
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let editors = self.editors.iter().enumerate().map(|(index, editor)| {
+            container(editor.view().map(move |message| Message(index, message)))
+                .width(Length::FillPortion(1))
+                .height(Length::Fill)
+                .into()
+        });
+
+        container(row(editors).spacing(20))
+            .padding(20)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
@@ -0,0 +1,166 @@
+// Baseline for the performance of the graph container: laying out graphs of various sizes, and
+// finding the socket under the cursor while it moves over them.
+//
+//     cargo bench -p iced_node_editor
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::widget::Tree;
+use iced::advanced::{clipboard, Shell};
+use iced::widget::text;
+use iced::{mouse, Element, Event, Font, Length, Pixels, Point, Rectangle, Renderer, Size, Theme};
+use iced_node_editor::{
+    graph_container, node, Endpoint, GraphState, Link, Matrix, Socket, SocketRole,
+};
+
+const SIZES: [usize; 3] = [100, 1_000, 5_000];
+const VIEWPORT: Size = Size::new(1920.0, 1080.0);
+
+// Nodes are placed on a grid with this many columns
+const COLUMNS: usize = 50;
+const NODE_SPACING_X: f32 = 200.0;
+const NODE_SPACING_Y: f32 = 120.0;
+
+// A graph with the given number of nodes, each with one input and one output socket, and twice
+// as many links between them. The links are chosen by a fixed pseudo-random sequence, so every
+// run measures the same graph.
+fn graph_state(nodes: usize) -> GraphState<String> {
+    let mut graph = GraphState::new();
+    for index in 0..nodes {
+        let position = Point::new(
+            (index % COLUMNS) as f32 * NODE_SPACING_X,
+            (index / COLUMNS) as f32 * NODE_SPACING_Y,
+        );
+        graph.add_node(position, format!("Node {index}"));
+    }
+
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed as usize % nodes
+    };
+    for _ in 0..nodes * 2 {
+        let (from, to) = (next(), next());
+        graph.add_link(Link::from_unordered(
+            Endpoint::socket(from, SocketRole::Out, 0),
+            Endpoint::socket(to, SocketRole::In, 0),
+        ));
+    }
+
+    graph
+}
+
+fn view(graph: &GraphState<String>, matrix: Matrix) -> Element<'_, (), Theme, Renderer> {
+    let content = graph.elements(|_, name| {
+        node(text(name))
+            .padding(10.0)
+            .width(Length::Fixed(150.0))
+            .sockets(vec![
+                Socket::new(SocketRole::In, text("in")),
+                Socket::new(SocketRole::Out, text("out")),
+            ])
+    });
+
+    graph_container(content)
+        .matrix(matrix)
+        .on_socket_hover(|_| ())
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+// The software renderer, which does not need a window. Text is measured just like in an
+// application.
+fn renderer() -> Renderer {
+    Renderer::TinySkia(iced_tiny_skia::Renderer::new(
+        iced_tiny_skia::Backend::new(),
+        Font::default(),
+        Pixels(16.0),
+    ))
+}
+
+fn limits() -> layout::Limits {
+    layout::Limits::new(Size::ZERO, VIEWPORT)
+}
+
+fn layout(c: &mut Criterion) {
+    let renderer = renderer();
+    let mut group = c.benchmark_group("layout");
+    for nodes in SIZES {
+        let graph = graph_state(nodes);
+
+        // Nothing changes between layouts, as while the application is idle
+        let element = view(&graph, Matrix::identity());
+        let mut tree = Tree::new(&element);
+        group.bench_with_input(BenchmarkId::new("unchanged", nodes), &nodes, |b, _| {
+            b.iter(|| element.as_widget().layout(&mut tree, &renderer, &limits()))
+        });
+
+        // The scale changes on every layout, as while zooming
+        let zoomed = [
+            view(&graph, Matrix::identity()),
+            view(&graph, Matrix::identity().scale(1.1)),
+        ];
+        let mut tree = Tree::new(&zoomed[0]);
+        let mut frame = 0;
+        group.bench_with_input(BenchmarkId::new("zooming", nodes), &nodes, |b, _| {
+            b.iter(|| {
+                frame += 1;
+                zoomed[frame % 2]
+                    .as_widget()
+                    .layout(&mut tree, &renderer, &limits())
+            })
+        });
+    }
+    group.finish();
+}
+
+fn socket_hit_testing(c: &mut Criterion) {
+    let renderer = renderer();
+    let mut group = c.benchmark_group("socket_hover");
+    for nodes in SIZES {
+        let graph = graph_state(nodes);
+        let mut element = view(&graph, Matrix::identity());
+        let mut tree = Tree::new(&element);
+        let node = element.as_widget().layout(&mut tree, &renderer, &limits());
+        let bounds = Rectangle::with_size(VIEWPORT);
+
+        // Sweeps the cursor along the left and right edges of the nodes, where the sockets are
+        let positions: Vec<Point> = (0..1_000)
+            .map(|i| {
+                let column = (i % 10) as f32;
+                let x = column * NODE_SPACING_X + if i % 2 == 0 { 0.0 } else { 150.0 };
+                let y = (i / 10) as f32 * 11.0 % VIEWPORT.height;
+                Point::new(x, y)
+            })
+            .collect();
+
+        let mut messages = Vec::new();
+        let mut index = 0;
+        group.bench_with_input(BenchmarkId::from_parameter(nodes), &nodes, |b, _| {
+            b.iter(|| {
+                index = (index + 1) % positions.len();
+                let position = positions[index];
+                let mut shell = Shell::new(&mut messages);
+                let status = element.as_widget_mut().on_event(
+                    &mut tree,
+                    Event::Mouse(mouse::Event::CursorMoved { position }),
+                    Layout::new(&node),
+                    mouse::Cursor::Available(position),
+                    &renderer,
+                    &mut clipboard::Null,
+                    &mut shell,
+                    &bounds,
+                );
+                messages.clear();
+                status
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, layout, socket_hit_testing);
+criterion_main!(benches);
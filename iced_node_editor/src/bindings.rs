@@ -0,0 +1,127 @@
+use iced::keyboard::Modifiers;
+
+// What the mouse wheel does over a graph container
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WheelAction {
+    // Zooms by vertical scrolling. Horizontal scrolling, which only touchpads and some mice
+    // produce, is ignored.
+    #[default]
+    Zoom,
+    // Pans along both axes, following the scroll delta (vertical for most mice, both for
+    // touchpads)
+    Pan,
+    // Pans sideways, turning vertical scrolling into horizontal movement
+    PanHorizontal,
+    // Leaves the event to the parent widgets, e.g. to scroll the page the graph is placed in
+    Ignore,
+}
+
+// Maps held modifiers to what the mouse wheel does, see `GraphContainer::bindings`. The first
+// binding whose modifiers are all held applies, so more specific combinations have to be added
+// first. If none of them is held, the default action applies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bindings {
+    wheel: Vec<(Modifiers, WheelAction)>,
+    default_wheel: WheelAction,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bindings {
+    // The wheel always zooms
+    pub fn new() -> Self {
+        Bindings {
+            wheel: Vec::new(),
+            default_wheel: WheelAction::Zoom,
+        }
+    }
+
+    // The bindings of most 2D editors: the wheel pans vertically, Shift + wheel pans
+    // horizontally, and Ctrl (Cmd on macOS) + wheel zooms
+    pub fn editor() -> Self {
+        Bindings::new()
+            .wheel(Modifiers::COMMAND, WheelAction::Zoom)
+            .wheel(Modifiers::SHIFT, WheelAction::PanHorizontal)
+            .default_wheel(WheelAction::Pan)
+    }
+
+    // The wheel only zooms while the modifiers are held, and is left to the parent widgets
+    // otherwise, so that the graph can be placed in a scrollable without every scroll over it
+    // zooming the graph
+    pub fn zoom_with(modifiers: Modifiers) -> Self {
+        Bindings::new()
+            .wheel(modifiers, WheelAction::Zoom)
+            .default_wheel(WheelAction::Ignore)
+    }
+
+    pub fn wheel(mut self, modifiers: Modifiers, action: WheelAction) -> Self {
+        self.wheel.push((modifiers, action));
+        self
+    }
+
+    // What the wheel does while none of the modifiers of the other bindings are held
+    pub fn default_wheel(mut self, action: WheelAction) -> Self {
+        self.default_wheel = action;
+        self
+    }
+
+    pub fn wheel_action(&self, held: Modifiers) -> WheelAction {
+        self.wheel
+            .iter()
+            .find(|(modifiers, _)| held.contains(*modifiers))
+            .map_or(self.default_wheel, |(_, action)| *action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wheel_action_follows_the_held_modifiers() {
+        let bindings = Bindings::editor();
+
+        assert_eq!(bindings.wheel_action(Modifiers::empty()), WheelAction::Pan);
+        assert_eq!(bindings.wheel_action(Modifiers::COMMAND), WheelAction::Zoom);
+        assert_eq!(
+            bindings.wheel_action(Modifiers::SHIFT),
+            WheelAction::PanHorizontal
+        );
+        // Other modifiers held along with a binding do not keep it from applying
+        assert_eq!(
+            bindings.wheel_action(Modifiers::SHIFT | Modifiers::ALT),
+            WheelAction::PanHorizontal
+        );
+        assert_eq!(bindings.wheel_action(Modifiers::ALT), WheelAction::Pan);
+
+        assert_eq!(
+            Bindings::new().wheel_action(Modifiers::SHIFT),
+            WheelAction::Zoom
+        );
+    }
+
+    #[test]
+    fn first_matching_binding_wins() {
+        let bindings = Bindings::new()
+            .wheel(Modifiers::SHIFT | Modifiers::ALT, WheelAction::Ignore)
+            .wheel(Modifiers::SHIFT, WheelAction::Pan);
+
+        assert_eq!(
+            bindings.wheel_action(Modifiers::SHIFT | Modifiers::ALT),
+            WheelAction::Ignore
+        );
+        assert_eq!(bindings.wheel_action(Modifiers::SHIFT), WheelAction::Pan);
+
+        let bindings = Bindings::new()
+            .wheel(Modifiers::SHIFT, WheelAction::Pan)
+            .wheel(Modifiers::SHIFT | Modifiers::ALT, WheelAction::Ignore);
+        assert_eq!(
+            bindings.wheel_action(Modifiers::SHIFT | Modifiers::ALT),
+            WheelAction::Pan
+        );
+    }
+}
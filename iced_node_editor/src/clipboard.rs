@@ -0,0 +1,113 @@
+use iced::{Point, Vector};
+
+use crate::Link;
+
+// A copied selection of nodes, independent of where the nodes were located and of their
+// indices in the graph. `N` is whatever the application needs to recreate a node.
+#[derive(Debug, Clone)]
+pub struct SubgraphClipboard<N> {
+    // Node data with positions relative to the centroid of the copied nodes
+    pub nodes: Vec<(Vector, N)>,
+    // Links between the copied nodes, with node indices referring to positions in `nodes`
+    pub links: Vec<Link>,
+}
+
+impl<N> SubgraphClipboard<N> {
+    // Copies the nodes with the given indices. Only links whose endpoints are both attached to
+    // copied nodes are kept; links to nodes outside of the selection or to absolute positions
+    // are dropped.
+    pub fn copy(
+        selection: &[usize],
+        links: &[Link],
+        position: impl Fn(usize) -> Point,
+        data: impl Fn(usize) -> N,
+    ) -> Self {
+        let centroid = centroid(selection.iter().map(|index| position(*index)));
+
+        let nodes = selection
+            .iter()
+            .map(|index| (position(*index) - centroid, data(*index)))
+            .collect();
+
+        let links = internal_links(selection, links, 0);
+
+        Self { nodes, links }
+    }
+
+    // Re-anchors the copied nodes such that their centroid is at `target`. The pasted nodes are
+    // assumed to be appended to the graph, with the first one getting the index
+    // `first_node_index`; the returned links are remapped accordingly.
+    pub fn paste(&self, target: Point, first_node_index: usize) -> (Vec<(Point, &N)>, Vec<Link>) {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|(offset, data)| (target + *offset, data))
+            .collect();
+
+        let links = self
+            .links
+            .iter()
+            .map(|link| link.map_node_indices(|index| index + first_node_index))
+            .collect();
+
+        (nodes, links)
+    }
+}
+
+// Duplicates the nodes with the given indices in place, e.g. for "duplicate selection" (Ctrl+D):
+// returns the positions, moved by `offset`, and data of the copies, along with copies of the links
+// between the duplicated nodes. The copies are assumed to be appended to the graph in the order of
+// `selection`, with the first one getting the index `first_node_index`; the links are remapped
+// accordingly, so the copies are wired among themselves just like the originals. Links to nodes
+// outside of the selection or to absolute positions are not duplicated.
+pub fn duplicate<N>(
+    selection: &[usize],
+    links: &[Link],
+    offset: Vector,
+    first_node_index: usize,
+    position: impl Fn(usize) -> Point,
+    data: impl Fn(usize) -> N,
+) -> (Vec<(Point, N)>, Vec<Link>) {
+    let nodes = selection
+        .iter()
+        .map(|index| (position(*index) + offset, data(*index)))
+        .collect();
+
+    (nodes, internal_links(selection, links, first_node_index))
+}
+
+// The links between the selected nodes, with each node index replaced by the position of the node
+// in `selection` plus `first_node_index`
+fn internal_links(selection: &[usize], links: &[Link], first_node_index: usize) -> Vec<Link> {
+    let remap = |index: usize| {
+        selection
+            .iter()
+            .position(|selected| *selected == index)
+            .map(|position| position + first_node_index)
+    };
+
+    links
+        .iter()
+        .filter_map(|link| {
+            let start = remap(link.start().node_index()?)?;
+            let end = remap(link.end().node_index()?)?;
+
+            Some(Link::new(
+                link.start().map_node_index(|_| start),
+                link.end().map_node_index(|_| end),
+            ))
+        })
+        .collect()
+}
+
+fn centroid(points: impl Iterator<Item = Point>) -> Point {
+    let (sum, count) = points.fold((Vector::new(0.0, 0.0), 0), |(sum, count), point| {
+        (sum + Vector::new(point.x, point.y), count + 1)
+    });
+
+    if count == 0 {
+        Point::ORIGIN
+    } else {
+        Point::new(sum.x / count as f32, sum.y / count as f32)
+    }
+}
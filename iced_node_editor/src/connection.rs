@@ -1,28 +1,217 @@
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
 use iced::advanced::graphics::mesh::{Indexed, SolidVertex2D};
-use iced::advanced::renderer;
-use iced::advanced::widget::Tree;
-use iced::{advanced::Widget, Length, Point, Size, Vector};
+use iced::advanced::widget::{tree, Tree};
+use iced::advanced::{renderer, text};
+use iced::time::Instant;
+use iced::{
+    advanced::Widget, alignment, Background, Border, Color, Length, Pixels, Point, Rectangle, Size,
+    Vector,
+};
 
+use crate::size_mode::SizeMode;
 use crate::{
     mesh_renderer::MeshRenderer,
     node_element::{GraphNodeElement, ScalableWidget},
+    routing,
     styles::connection::StyleSheet,
-    SocketRole,
+    SocketRole, SocketSide,
 };
 
+// Width of connections created with `Connection::new`
+pub(crate) const DEFAULT_WIDTH: f32 = 1.2;
+
 pub struct Connection<Message, Theme>
 where
     Theme: StyleSheet,
 {
     link: Link,
-    width: f32,
+    width: SizeMode,
     number_of_segments: usize,
+    quality: Option<Quality>,
     style: Theme::Style,
+    label: Option<String>,
+    label_size: Option<f32>,
+    label_offset: Vector,
+    flow: Option<FlowAnimation>,
+    time: Option<f32>,
+    tangent: Option<Tangent>,
+    routing: Routing,
+    exit_side: Option<SocketSide>,
+    entry_side: Option<SocketSide>,
+    breakpoint: Option<bool>,
 
     phantom_message: std::marker::PhantomData<Message>,
-    spline: Mutex<Vec<Vector>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlowAnimation {
+    // Speed of the moving dots in graph units per second
+    pub speed: f32,
+    pub direction: FlowDirection,
+    // Distance between two consecutive dots in graph units
+    pub spacing: f32,
+    pub dot_radius: f32,
+}
+
+impl Default for FlowAnimation {
+    fn default() -> Self {
+        Self {
+            speed: 40.0,
+            direction: FlowDirection::Forward,
+            spacing: 20.0,
+            dot_radius: 2.5,
+        }
+    }
+}
+
+// Shapes a connection as a cubic bezier curve, whose control points lie on the tangents at its
+// endpoints. Links whose end lies behind their start (e.g. an output connected to an input to its
+// left) get longer tangents, so that they bow outward instead of cutting back through the nodes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tangent {
+    // Length of the tangents in graph units
+    pub strength: f32,
+    // The direction in which the connection leaves its start and enters its end. If `None`, it
+    // is determined by the sides of the sockets, and horizontal for other endpoints.
+    pub direction: Option<Vector>,
+}
+
+impl Default for Tangent {
+    fn default() -> Self {
+        Self {
+            strength: 50.0,
+            direction: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Routing {
+    // The connection runs directly from its start to its end
+    #[default]
+    Direct,
+    // Like `Direct`, but links whose end lies behind their start, or that start and end at the
+    // same node, are routed around the nodes they connect, keeping `clearance` (in graph units)
+    // away from them
+    Loopback {
+        clearance: f32,
+    },
+    // Routes the connection around all nodes in its way, keeping `clearance` away from them. The
+    // route is searched on a grid with cells of `cell_size` (both in graph units); coarser grids
+    // are faster, but find fewer ways through narrow gaps. If no route is found, the connection
+    // is drawn directly.
+    AvoidNodes {
+        clearance: f32,
+        cell_size: f32,
+    },
+    // Draws the connection with horizontal and vertical segments only, like a wire in a circuit
+    // diagram. Connections whose middle segments would run along the same line are moved apart
+    // by `channel_spacing` (in graph units), so that they can still be told apart.
+    Orthogonal {
+        channel_spacing: f32,
+    },
+}
+
+// How finely connections are tessellated, see `Connection::quality`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quality {
+    Fast,
+    #[default]
+    Balanced,
+    High,
+}
+
+// Number of segments of the curve that is used to estimate the length of a connection
+const QUALITY_PROBE_SEGMENTS: usize = 8;
+
+// How far along the way to the other ends of their links the connections of a bundle meet
+const BUNDLE_TRUNK: f32 = 0.35;
+// How far (in graph units) bundled connections run straight out of their sockets
+const BUNDLE_LEAD: f32 = 20.0;
+// How far (in graph units) orthogonal connections run out of their sockets before turning back
+const ORTHOGONAL_LEAD: f32 = 20.0;
+// Radius of the breakpoint glyph in graph units
+const BREAKPOINT_RADIUS: f32 = 6.0;
+// Border width of the glyph of a breakpoint that is not set, in screen pixels
+const BREAKPOINT_RING_WIDTH: f32 = 1.5;
+// While zooming within one of these fractions of a doubling of the scale, the geometry of a
+// connection is scaled instead of computed again
+const ZOOM_BUCKETS_PER_OCTAVE: f32 = 8.0;
+// The stroke of a connection is scaled along with its curve while the scale stays within this
+// fraction of the scale it was tessellated at, and tessellated again beyond that, so that line
+// widths given in screen pixels do not drift while zooming
+const STROKE_SCALE_TOLERANCE: f32 = 0.01;
+
+impl Quality {
+    // The number of segments for a curve of the given length in screen pixels
+    fn number_of_segments(self, length: f32) -> usize {
+        // Pixels per segment, and bounds for the number of segments
+        let (pixels, min, max) = match self {
+            Quality::Fast => (24.0, 4, 32),
+            Quality::Balanced => (10.0, 8, 128),
+            Quality::High => (4.0, 16, 512),
+        };
+        ((length / pixels).ceil() as usize).clamp(min, max)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FlowDirection {
+    // From the output socket to the input socket
+    #[default]
+    Forward,
+    Backward,
+}
+
+// Kept in the widget tree rather than in the connection itself, as the connection is rebuilt on
+// every view while its layout is reused
+#[derive(Default)]
+struct ConnectionLayoutState {
+    spline: Vec<Vector>,
+    scale: f32,
+    // The layout bounds, and the tessellated curve relative to them
+    bounds: Rectangle,
+    vertices: Vec<Vector>,
+    indices: Vec<u32>,
+    // The scale the curve was last tessellated at
+    stroke_scale: f32,
+    // The inputs the geometry was computed from, if it can be reused while they stay the same
+    key: Option<GeometryKey>,
+    // When the flow animation started running on its own, and how far it has come since
+    animation_start: Option<Instant>,
+    animation_time: f32,
+}
+
+// Lengths are rounded to pixels at the scale of the zoom bucket, so that the key stays the same
+// while zooming within the bucket
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GeometryKey {
+    from: Vector,
+    to: Vector,
+    from_direction: Vector,
+    to_direction: Vector,
+    zoom_bucket: i32,
+    margin: f32,
+    line_width: f32,
+    number_of_segments: usize,
+    quality: Option<Quality>,
+    tangent: Option<Tangent>,
+    routing: Routing,
+    // Loopback routes go around the nodes at the ends
+    end_nodes: (Option<Rectangle>, Option<Rectangle>),
+    // Bundled routes depend on the other links of the sockets
+    bundle: (f32, [Option<Vector>; 2]),
+    channel: Option<f32>,
+    // Routes around nodes depend on all of them
+    obstacles: Option<u64>,
+}
+
+// The zoom bucket a scale falls into, and the scale in the middle of it
+fn zoom_bucket(scale: f32) -> (i32, f32) {
+    let bucket = (scale.log2() * ZOOM_BUCKETS_PER_OCTAVE).round();
+    (bucket as i32, (bucket / ZOOM_BUCKETS_PER_OCTAVE).exp2())
 }
 
 impl<Message, Theme> Connection<Message, Theme>
@@ -31,10 +220,20 @@ where
 {
     pub fn new(link: Link) -> Self {
         Connection {
-            spline: Mutex::new(Vec::new()),
             link,
-            width: 1.2,
+            width: SizeMode::Screen(DEFAULT_WIDTH),
             number_of_segments: 20,
+            quality: None,
+            label: None,
+            label_size: None,
+            label_offset: Vector::new(0.0, 0.0),
+            flow: None,
+            time: None,
+            tangent: None,
+            routing: Routing::default(),
+            exit_side: None,
+            entry_side: None,
+            breakpoint: None,
             phantom_message: std::marker::PhantomData,
             style: Default::default(),
         }
@@ -44,8 +243,8 @@ where
         Self::new(Link::from_unordered(first, second))
     }
 
-    pub fn width(mut self, width: f32) -> Self {
-        self.width = width;
+    pub fn width(mut self, width: impl Into<SizeMode>) -> Self {
+        self.width = width.into();
         self
     }
 
@@ -54,8 +253,306 @@ where
         self
     }
 
-    pub fn style(mut self, style: Theme::Style) -> Self {
-        self.style = style;
+    // Chooses the number of segments from the length of the curve on screen instead of using
+    // `number_of_segments`, so that curves stay smooth when zoomed in and cheap when zoomed out
+    pub fn quality(mut self, quality: Quality) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    pub fn style(mut self, style: impl Into<Theme::Style>) -> Self {
+        self.style = style.into();
+        self
+    }
+
+    // The curve was tessellated during layout, so only the color and the translation are applied
+    fn mesh(
+        layout_state: &ConnectionLayoutState,
+        color: iced::Color,
+        translation: Vector,
+    ) -> Indexed<SolidVertex2D> {
+        let color = iced::advanced::graphics::color::pack(color);
+        Indexed {
+            vertices: layout_state
+                .vertices
+                .iter()
+                .map(|p| SolidVertex2D {
+                    position: [p.x + translation.x, p.y + translation.y],
+                    color,
+                })
+                .collect(),
+            indices: layout_state.indices.clone(),
+        }
+    }
+
+    // Tessellates the curve, given in layout coordinates, at the given scale
+    fn stroke(&self, state: &mut ConnectionLayoutState, spline: &[Vector], scale: f32) {
+        // The bounds cover everything that is drawn along the curve, on all sides, so that the
+        // connection is not culled while any part of it is visible
+        let bounds = bounds_for_vectors(spline).expand(self.margin(scale));
+        let spline: Vec<Vector> = spline
+            .iter()
+            .map(|p| Vector::new(p.x - bounds.x, p.y - bounds.y))
+            .collect();
+        let (vertices, indices) = line_to_polygon(&spline, self.width.resolve(scale) / 2.0);
+
+        state.spline = spline;
+        state.scale = scale;
+        state.bounds = bounds;
+        state.vertices = vertices;
+        state.indices = indices;
+        state.stroke_scale = scale;
+    }
+
+    // Samples the curve between the resolved endpoints, following the routing
+    #[allow(clippy::too_many_arguments)]
+    fn curve(
+        &self,
+        from: Vector,
+        to: Vector,
+        from_direction: Vector,
+        to_direction: Vector,
+        scale: f32,
+        socket_state: &super::node_element::SocketLayoutState,
+        channel: Option<f32>,
+    ) -> Vec<Vector> {
+        let routed = match self.routing {
+            Routing::Loopback { clearance } => {
+                let node_bounds = |endpoint: &Endpoint| {
+                    endpoint
+                        .node_index()
+                        .and_then(|node_index| socket_state.nodes.get(node_index).copied())
+                };
+                let same_node = self.link.start.node_index().is_some()
+                    && self.link.start.node_index() == self.link.end.node_index();
+                loopback_waypoints(
+                    from,
+                    to,
+                    from_direction,
+                    clearance.max(1.0) * scale,
+                    node_bounds(&self.link.start),
+                    node_bounds(&self.link.end),
+                    same_node,
+                )
+            }
+            Routing::AvoidNodes {
+                clearance,
+                cell_size,
+            } => {
+                let clearance = clearance * scale;
+                let lead_from = from + normalize_vector(from_direction) * clearance;
+                let lead_to = to - normalize_vector(to_direction) * clearance;
+                let obstacles: Vec<Rectangle> = socket_state
+                    .nodes
+                    .iter()
+                    .map(|node| node.expand(clearance / 2.0))
+                    .collect();
+
+                routing::find_path(lead_from, lead_to, &obstacles, cell_size * scale).map(
+                    |corners| {
+                        let mut waypoints = vec![from, lead_from];
+                        waypoints.extend(corners);
+                        waypoints.extend([lead_to, to]);
+                        waypoints
+                    },
+                )
+            }
+            Routing::Direct => {
+                self.bundled_waypoints(from, to, from_direction, to_direction, scale, socket_state)
+            }
+            // The corners are kept sharp
+            Routing::Orthogonal { .. } => {
+                return orthogonal_route(
+                    from,
+                    to,
+                    from_direction,
+                    to_direction,
+                    ORTHOGONAL_LEAD * scale,
+                    |_, position, _| channel.unwrap_or(position),
+                );
+            }
+        };
+
+        let curve = |number_of_segments: usize| match (&routed, self.tangent) {
+            (Some(waypoints), _) => smooth_polyline(waypoints, number_of_segments),
+            (None, Some(tangent)) => {
+                let from_direction = normalize_vector(from_direction);
+                let to_direction = normalize_vector(to_direction);
+                let behind = (-dot_vector(to - from, from_direction)).max(0.0);
+                let strength = tangent.strength * scale + behind / 2.0;
+                generate_bezier(
+                    from,
+                    from + from_direction * strength,
+                    to - to_direction * strength,
+                    to,
+                    number_of_segments,
+                )
+            }
+            (None, None) => generate_spline(
+                from,
+                from_direction,
+                1.0,
+                to,
+                to_direction,
+                number_of_segments,
+                1.0_f32,
+            ),
+        };
+
+        match self.quality {
+            Some(quality) => {
+                // A coarse version of the curve is enough to estimate its length
+                let coarse = curve(QUALITY_PROBE_SEGMENTS);
+                let length: f32 = coarse
+                    .windows(2)
+                    .map(|segment| {
+                        let delta = segment[1] - segment[0];
+                        dot_vector(delta, delta).sqrt()
+                    })
+                    .sum();
+                curve(quality.number_of_segments(length))
+            }
+            None => curve(self.number_of_segments),
+        }
+    }
+
+    // The average positions of the other ends of the links at the start and the end socket, if
+    // the connection is bundled with those links, see `GraphContainer::edge_bundling`
+    fn bundle_targets(
+        &self,
+        socket_state: &super::node_element::SocketLayoutState,
+    ) -> [Option<Vector>; 2] {
+        if socket_state.bundle_strength <= 0.0 || self.routing != Routing::Direct {
+            return [None, None];
+        }
+
+        [&self.link.start, &self.link.end].map(|endpoint| {
+            socket_state
+                .logical_endpoint(endpoint)
+                .and_then(|socket| socket_state.bundle_targets.get(&socket).copied())
+        })
+    }
+
+    // A route that leaves the start socket together with the other links of the socket, and
+    // likewise enters the end socket, or `None` if neither socket has other links
+    fn bundled_waypoints(
+        &self,
+        from: Vector,
+        to: Vector,
+        from_direction: Vector,
+        to_direction: Vector,
+        scale: f32,
+        socket_state: &super::node_element::SocketLayoutState,
+    ) -> Option<Vec<Vector>> {
+        let [start_target, end_target] = self.bundle_targets(socket_state);
+        if start_target.is_none() && end_target.is_none() {
+            return None;
+        }
+
+        // Moves the point the connection would pass anyway towards the trunk of the bundle
+        let strength = socket_state.bundle_strength;
+        let trunk = |end: Vector, other: Vector, target: Option<Vector>| {
+            let natural = end + (other - end) * BUNDLE_TRUNK;
+            target.map(|target| {
+                let trunk = end + (target - end) * BUNDLE_TRUNK;
+                natural + (trunk - natural) * strength
+            })
+        };
+
+        let lead = BUNDLE_LEAD * scale;
+        let mut waypoints = vec![from, from + normalize_vector(from_direction) * lead];
+        waypoints.extend(trunk(from, to, start_target));
+        waypoints.extend(trunk(to, from, end_target));
+        waypoints.extend([to - normalize_vector(to_direction) * lead, to]);
+        Some(waypoints)
+    }
+
+    // How far the drawn connection extends beyond its curve: half the line width, or the radius
+    // of the flow dots if they are larger
+    fn margin(&self, scale: f32) -> f32 {
+        let line = self.width.resolve(scale) / 2.0;
+        let line = match &self.flow {
+            Some(flow) => line.max(flow.dot_radius * scale),
+            None => line,
+        };
+        match self.breakpoint {
+            Some(_) => line.max(BREAKPOINT_RADIUS * scale),
+            None => line,
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    // Text size of the label in graph units; it is scaled along with the graph.
+    // If `None`, the renderer's default text size is used.
+    pub fn label_size(mut self, label_size: impl Into<Pixels>) -> Self {
+        self.label_size = Some(label_size.into().0);
+        self
+    }
+
+    pub fn flow(mut self, flow: FlowAnimation) -> Self {
+        self.flow = Some(flow);
+        self
+    }
+
+    // The current animation time in seconds. Without it, the flow animation runs on its own,
+    // redrawing the connection only while it is visible; setting it lets the application drive
+    // (or pause) the animation instead.
+    pub fn time(mut self, time: f32) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    // Draws the connection as a bezier curve with the given tangents, instead of the default
+    // spline that runs almost straight between the endpoints
+    pub fn tangent(mut self, tangent: Tangent) -> Self {
+        self.tangent = Some(tangent);
+        self
+    }
+
+    pub fn routing(mut self, routing: Routing) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    // Leaves the start socket towards the given side, instead of the side its blob is on, e.g.
+    // so that a link back to an earlier node visibly turns around right away. Takes precedence
+    // over the direction of the `tangent`.
+    pub fn exit_side(mut self, side: SocketSide) -> Self {
+        self.exit_side = Some(side);
+        self
+    }
+
+    // Enters the end socket from the given side, instead of the side its blob is on
+    pub fn entry_side(mut self, side: SocketSide) -> Self {
+        self.entry_side = Some(side);
+        self
+    }
+
+    // Shows a breakpoint glyph at the midpoint of the connection: a filled dot if the breakpoint
+    // is set, and a ring if it is not. Clicking the glyph produces the
+    // `on_breakpoint_toggle` message of the graph container. A label is drawn on top of it.
+    pub fn breakpoint(mut self, set: bool) -> Self {
+        self.breakpoint = Some(set);
+        self
+    }
+
+    // Where the breakpoint glyph is drawn, relative to the top left corner of the layout bounds
+    fn breakpoint_center(&self, layout_state: &ConnectionLayoutState) -> Option<Vector> {
+        self.breakpoint?;
+        layout_state
+            .spline
+            .get(layout_state.spline.len() / 2)
+            .copied()
+    }
+
+    // Offset of the label from the curve midpoint, in graph units.
+    // Note that the label is always drawn horizontally, as iced cannot rotate text.
+    pub fn label_offset(mut self, label_offset: Vector) -> Self {
+        self.label_offset = label_offset;
         self
     }
 }
@@ -67,55 +564,289 @@ where
     Connection::between(Endpoint::Absolute(from), Endpoint::Absolute(to))
 }
 
-impl<Message, Theme, Renderer> ScalableWidget<Message, Renderer> for Connection<Message, Theme>
+impl<Message, Theme, Renderer> ScalableWidget<Message, Renderer, Theme>
+    for Connection<Message, Theme>
 where
     Theme: StyleSheet,
     Renderer: renderer::Renderer,
 {
     fn layout(
         &self,
-        _tree: &mut Tree,
+        tree: &mut Tree,
         _renderer: &Renderer,
         _limits: &iced::advanced::layout::Limits,
         scale: f32,
         socket_state: &mut super::node_element::SocketLayoutState,
     ) -> iced::advanced::layout::Node {
-        // Set the flag that we've started to process connections;
-        // this will cause a panic if there are further nodes
-        socket_state.done = true;
-
-        let spline = generate_spline(
-            self.link.start.resolve(scale, socket_state),
-            1.0,
-            self.link.end.resolve(scale, socket_state),
-            self.number_of_segments,
-            1.0_f32,
-        );
+        // Set the flag that we've started to process connections
+        if !socket_state.done {
+            socket_state.done = true;
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            for node in &socket_state.nodes {
+                [node.x, node.y, node.width, node.height]
+                    .map(f32::to_bits)
+                    .hash(&mut hasher);
+            }
+            socket_state.obstacles_hash = hasher.finish();
+        }
 
-        let spline_bounds = bounds_for_vectors(&spline);
+        // Links leave nodes on the right and enter them on the left
+        let from = self
+            .link
+            .start
+            .resolve(scale, socket_state, RelativeAnchor::CENTER_RIGHT);
+        let to = self
+            .link
+            .end
+            .resolve(scale, socket_state, RelativeAnchor::CENTER_LEFT);
+        let (Some(from), Some(to)) = (from, to) else {
+            // The link refers to a node that is not part of the graph, or the connection comes
+            // before the nodes; it is left out rather than drawn to some arbitrary point
+            debug_assert!(false, "connection {:?} refers to a missing node", self.link);
+            let self_state = tree.state.downcast_mut::<ConnectionLayoutState>();
+            *self_state = ConnectionLayoutState {
+                animation_start: self_state.animation_start,
+                animation_time: self_state.animation_time,
+                ..ConnectionLayoutState::default()
+            };
+            return iced::advanced::layout::Node::new(Size::ZERO);
+        };
+        let tangent_direction = self.tangent.and_then(|tangent| tangent.direction);
+        let from_direction = self
+            .exit_side
+            .map(|side| side.outward_direction())
+            .or(tangent_direction)
+            .or_else(|| self.link.start.direction(socket_state))
+            .unwrap_or(Vector::new(1.0, 0.0));
+        // The curve enters the end socket opposite to the direction it would leave it in
+        let to_direction = self
+            .entry_side
+            .map(|side| side.outward_direction() * -1.0)
+            .or(tangent_direction)
+            .or_else(|| {
+                self.link
+                    .end
+                    .direction(socket_state)
+                    .map(|direction| direction * -1.0)
+            })
+            .unwrap_or(Vector::new(1.0, 0.0));
 
-        let spline = spline
-            .iter()
-            .map(|p| Vector::new(p.x - spline_bounds.x, p.y - spline_bounds.y))
-            .collect();
+        // Orthogonal connections reserve their channel on every layout, even if their geometry is
+        // reused, so that the channels of the following connections stay the same
+        let mut channel = None;
+        if let Routing::Orthogonal { channel_spacing } = self.routing {
+            orthogonal_route(
+                from,
+                to,
+                from_direction,
+                to_direction,
+                ORTHOGONAL_LEAD * scale,
+                |vertical, position, span| {
+                    let position = socket_state.channels.claim(
+                        vertical,
+                        position,
+                        span,
+                        channel_spacing * scale,
+                    );
+                    channel = Some(position);
+                    position
+                },
+            );
+        }
+
+        // Panning does not move the endpoints, as they are resolved before the translation of
+        // the graph is applied, so the geometry of most connections can be reused. Zooming within
+        // a bucket only scales the curve, see `STROKE_SCALE_TOLERANCE`.
+        let (bucket, bucket_scale) = zoom_bucket(scale);
+        let round = |length: f32| (length * bucket_scale / scale).round();
+        let round_vector = |vector: Vector| Vector::new(round(vector.x), round(vector.y));
+        let node_bounds = |endpoint: &Endpoint| {
+            endpoint
+                .node_index()
+                .and_then(|node_index| socket_state.nodes.get(node_index))
+                .map(|node| {
+                    Rectangle::new(
+                        Point::new(round(node.x), round(node.y)),
+                        Size::new(round(node.width), round(node.height)),
+                    )
+                })
+        };
+        let key = GeometryKey {
+            from: round_vector(from),
+            to: round_vector(to),
+            from_direction,
+            to_direction,
+            zoom_bucket: bucket,
+            margin: self.margin(bucket_scale),
+            line_width: self.width.resolve(bucket_scale),
+            number_of_segments: self.number_of_segments,
+            quality: self.quality,
+            tangent: self.tangent,
+            routing: self.routing,
+            end_nodes: (node_bounds(&self.link.start), node_bounds(&self.link.end)),
+            bundle: (
+                socket_state.bundle_strength,
+                self.bundle_targets(socket_state)
+                    .map(|target| target.map(round_vector)),
+            ),
+            channel: channel.map(round),
+            obstacles: matches!(self.routing, Routing::AvoidNodes { .. })
+                .then_some(socket_state.obstacles_hash),
+        };
+
+        let self_state = tree.state.downcast_mut::<ConnectionLayoutState>();
+        if self_state.key == Some(key) && self_state.scale != scale {
+            let ratio = scale / self_state.scale;
+            if (scale / self_state.stroke_scale - 1.0).abs() <= STROKE_SCALE_TOLERANCE {
+                for point in self_state
+                    .spline
+                    .iter_mut()
+                    .chain(self_state.vertices.iter_mut())
+                {
+                    *point = *point * ratio;
+                }
+                let bounds = self_state.bounds;
+                self_state.bounds = Rectangle::new(
+                    Point::new(bounds.x * ratio, bounds.y * ratio),
+                    Size::new(bounds.width * ratio, bounds.height * ratio),
+                );
+                self_state.scale = scale;
+            } else {
+                let origin = Vector::new(self_state.bounds.x, self_state.bounds.y);
+                let spline: Vec<Vector> = self_state
+                    .spline
+                    .iter()
+                    .map(|point| (*point + origin) * ratio)
+                    .collect();
+                self.stroke(self_state, &spline, scale);
+            }
+        } else if self_state.key != Some(key) {
+            let spline = self.curve(
+                from,
+                to,
+                from_direction,
+                to_direction,
+                scale,
+                socket_state,
+                channel,
+            );
+
+            self_state.key = Some(key);
+            self.stroke(self_state, &spline, scale);
+        }
+
+        let bounds = self_state.bounds;
+        let node =
+            iced::advanced::layout::Node::new(Size::new(bounds.width.ceil(), bounds.height.ceil()));
+
+        node.translate(Vector::new(bounds.x, bounds.y))
+    }
 
-        let node = iced::advanced::layout::Node::new(Size::new(
-            (spline_bounds.width + self.width).ceil(),
-            (spline_bounds.height + self.width).ceil(),
-        ));
+    fn link(&self) -> Option<&Link> {
+        Some(&self.link)
+    }
+
+    fn is_visible(&self, tree: &Tree, rectangle: Rectangle) -> bool {
+        // The label is drawn around the midpoint, possibly beyond the bounds
+        if self.label.is_some() {
+            return true;
+        }
+
+        // A long connection can have bounds that cover the visible area while the curve itself
+        // passes by it
+        let layout_state = tree.state.downcast_ref::<ConnectionLayoutState>();
+        let rectangle = rectangle.expand(self.margin(layout_state.scale));
+        layout_state
+            .spline
+            .windows(2)
+            .any(|segment| segment_intersects_rectangle(segment[0], segment[1], rectangle))
+    }
+
+    fn animate(&self, tree: &mut Tree, now: Instant) -> bool {
+        if self.flow.is_none() || self.time.is_some() {
+            return false;
+        }
+
+        let state = tree.state.downcast_mut::<ConnectionLayoutState>();
+        let start = *state.animation_start.get_or_insert(now);
+        state.animation_time = now.saturating_duration_since(start).as_secs_f32();
+        true
+    }
+
+    fn halo_mesh(
+        &self,
+        tree: &Tree,
+        extra: f32,
+        color: Color,
+        translation: Vector,
+    ) -> Option<Indexed<SolidVertex2D>> {
+        let layout_state = tree.state.downcast_ref::<ConnectionLayoutState>();
+        if layout_state.spline.len() < 2 {
+            return None;
+        }
+
+        let width = self.width.resolve(layout_state.scale) / 2.0 + extra;
+        let (vertices, indices) = line_to_polygon(&layout_state.spline, width);
+        let color = iced::advanced::graphics::color::pack(color);
+        Some(Indexed {
+            vertices: vertices
+                .iter()
+                .map(|p| SolidVertex2D {
+                    position: [p.x + translation.x, p.y + translation.y],
+                    color,
+                })
+                .collect(),
+            indices,
+        })
+    }
+
+    fn breakpoint_at(&self, tree: &Tree, point: Vector) -> Option<bool> {
+        let set = self.breakpoint?;
+        let layout_state = tree.state.downcast_ref::<ConnectionLayoutState>();
+        let offset = point - self.breakpoint_center(layout_state)?;
+        let radius = BREAKPOINT_RADIUS * layout_state.scale;
+        (offset.x * offset.x + offset.y * offset.y <= radius * radius).then_some(set)
+    }
 
-        let mut self_state = self.spline.lock().expect("Could not lock mutex");
-        *self_state = spline;
+    fn distance_to(&self, tree: &Tree, point: Vector) -> Option<f32> {
+        tree.state
+            .downcast_ref::<ConnectionLayoutState>()
+            .spline
+            .windows(2)
+            .map(|segment| distance_to_segment(point, segment[0], segment[1]))
+            .min_by(f32::total_cmp)
+    }
+
+    fn batched_mesh(
+        &self,
+        tree: &Tree,
+        theme: &Theme,
+        translation: Vector,
+    ) -> Option<Indexed<SolidVertex2D>> {
+        // Labels, flow animations and breakpoints are not part of the mesh
+        if self.label.is_some() || self.flow.is_some() || self.breakpoint.is_some() {
+            return None;
+        }
 
-        node.translate(Vector::new(spline_bounds.x, spline_bounds.y))
+        let color = theme.appearance(&self.style).color.unwrap();
+        let layout_state = tree.state.downcast_ref::<ConnectionLayoutState>();
+        Some(Self::mesh(layout_state, color, translation))
     }
 }
 
 impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer> for Connection<Message, Theme>
 where
     Theme: StyleSheet,
-    Renderer: renderer::Renderer + MeshRenderer,
+    Renderer: renderer::Renderer + text::Renderer + MeshRenderer,
 {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<ConnectionLayoutState>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(ConnectionLayoutState::default())
+    }
+
     fn layout(
         &self,
         _tree: &mut Tree,
@@ -127,34 +858,82 @@ where
 
     fn draw(
         &self,
-        _tree: &iced::advanced::widget::Tree,
+        tree: &iced::advanced::widget::Tree,
         renderer: &mut Renderer,
         theme: &Theme,
         _renderer_style: &renderer::Style,
         layout: iced::advanced::Layout<'_>,
         _cursor: iced::mouse::Cursor,
-        _viewport: &iced::Rectangle,
+        viewport: &iced::Rectangle,
     ) {
         let bounds = layout.bounds();
         let style = theme.appearance(&self.style);
 
-        let spline = self.spline.lock().unwrap();
-        let (vertices, indices) = line_to_polygon(&spline, self.width / 2.0);
-
-        let buffers = Indexed {
-            vertices: vertices
-                .iter()
-                .map(|p| SolidVertex2D {
-                    position: [p.x, p.y],
-                    color: iced::advanced::graphics::color::pack(style.color.unwrap()),
-                })
-                .collect(),
-            indices,
-        };
+        let layout_state = tree.state.downcast_ref::<ConnectionLayoutState>();
+        let spline = &layout_state.spline;
+        let buffers = Self::mesh(layout_state, style.color.unwrap(), Vector::new(0.0, 0.0));
 
         renderer.with_translation(Vector::new(bounds.x, bounds.y), |renderer| {
             renderer.draw_buffers(buffers);
         });
+
+        if let Some(flow) = &self.flow {
+            draw_flow(
+                renderer,
+                flow,
+                self.time.unwrap_or(layout_state.animation_time),
+                spline,
+                layout_state.scale,
+                Vector::new(bounds.x, bounds.y),
+                style.color.unwrap(),
+            );
+        }
+
+        if let (Some(set), Some(center)) = (self.breakpoint, self.breakpoint_center(layout_state)) {
+            let radius = BREAKPOINT_RADIUS * layout_state.scale;
+            let color = style.breakpoint_color.unwrap_or(style.color.unwrap());
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle::new(
+                        Point::new(bounds.x + center.x - radius, bounds.y + center.y - radius),
+                        Size::new(radius * 2.0, radius * 2.0),
+                    ),
+                    border: Border {
+                        color,
+                        width: BREAKPOINT_RING_WIDTH,
+                        radius: radius.into(),
+                    },
+                    ..renderer::Quad::default()
+                },
+                Background::Color(if set { color } else { Color::TRANSPARENT }),
+            );
+        }
+
+        if let Some(label) = &self.label {
+            let scale = layout_state.scale;
+            let midpoint = spline[spline.len() / 2];
+            let position = Point::new(bounds.x, bounds.y) + midpoint + self.label_offset * scale;
+            let size = self
+                .label_size
+                .map(Pixels)
+                .unwrap_or_else(|| renderer.default_size());
+
+            renderer.fill_text(
+                text::Text {
+                    content: label,
+                    bounds: Size::INFINITY,
+                    size: size * scale,
+                    line_height: text::LineHeight::default(),
+                    font: renderer.default_font(),
+                    horizontal_alignment: alignment::Horizontal::Center,
+                    vertical_alignment: alignment::Vertical::Center,
+                    shaping: text::Shaping::Basic,
+                },
+                position,
+                style.color.unwrap(),
+                *viewport,
+            );
+        }
     }
 
     fn size(&self) -> Size<Length> {
@@ -167,14 +946,15 @@ impl<'a, Message, Theme, Renderer> From<Connection<Message, Theme>>
 where
     Message: 'a,
     Theme: StyleSheet + 'a,
-    Renderer: renderer::Renderer + MeshRenderer + 'a,
+    Renderer: renderer::Renderer + text::Renderer + MeshRenderer + 'a,
 {
     fn from(node: Connection<Message, Theme>) -> Self {
         Self::new(node)
     }
 }
 
-#[derive(Debug, Clone)]
+// Links compare and hash by their endpoints, so they can be kept in a `HashSet`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Link {
     start: Endpoint,
     end: Endpoint,
@@ -195,13 +975,17 @@ impl Link {
 
     pub fn from_unordered(e1: Endpoint, e2: Endpoint) -> Self {
         match e1 {
-            Endpoint::Absolute(_) => match e2 {
-                Endpoint::Absolute(_) => Self::new(e1, e2),
-                Endpoint::Socket(l2) => match l2.role {
-                    SocketRole::In => Self::new(e1, e2),
-                    SocketRole::Out => Self::new(e2, e1),
-                },
-            },
+            Endpoint::Absolute(_) | Endpoint::NodeAnchor(..) | Endpoint::NamedSocket(..) => {
+                match e2 {
+                    Endpoint::Absolute(_)
+                    | Endpoint::NodeAnchor(..)
+                    | Endpoint::NamedSocket(..) => Self::new(e1, e2),
+                    Endpoint::Socket(l2) => match l2.role {
+                        SocketRole::In => Self::new(e1, e2),
+                        SocketRole::Out => Self::new(e2, e1),
+                    },
+                }
+            }
             Endpoint::Socket(l1) => {
                 if let Endpoint::Socket(l2) = e2 {
                     if l1.role == l2.role {
@@ -234,12 +1018,142 @@ impl Link {
 
         panic!("tried to call unwrap_sockets() on a Link containing absolute endpoints");
     }
+
+    // Both endpoints, if both of them are sockets
+    pub fn sockets(&self) -> Option<(LogicalEndpoint, LogicalEndpoint)> {
+        match (&self.start, &self.end) {
+            (Endpoint::Socket(start), Endpoint::Socket(end)) => Some((*start, *end)),
+            _ => None,
+        }
+    }
+
+    // The endpoint at the other end of the link, or `None` if `endpoint` is not one of its ends
+    pub fn other_end(&self, endpoint: &Endpoint) -> Option<&Endpoint> {
+        if *endpoint == self.start {
+            Some(&self.end)
+        } else if *endpoint == self.end {
+            Some(&self.start)
+        } else {
+            None
+        }
+    }
+
+    // Whether either end of the link is attached to the node with the given index
+    pub fn involves_node(&self, node_index: usize) -> bool {
+        self.start.node_index() == Some(node_index) || self.end.node_index() == Some(node_index)
+    }
+
+    // Returns a copy of this link with the node indices of both ends replaced by `f(node_index)`
+    pub fn map_node_indices(&self, f: impl Fn(usize) -> usize) -> Self {
+        Self {
+            start: self.start.map_node_index(&f),
+            end: self.end.map_node_index(&f),
+        }
+    }
+
+    // Returns the link as it is after the node with the given index was removed from the graph,
+    // i.e. with the indices of all following nodes shifted down by one. Returns `None` if the
+    // link is attached to the removed node.
+    pub fn after_node_removed(&self, removed: usize) -> Option<Self> {
+        if self.involves_node(removed) {
+            return None;
+        }
+
+        Some(self.map_node_indices(|index| if index > removed { index - 1 } else { index }))
+    }
+
+    // Returns a copy of this link with every end attached to the node `from` attached to the
+    // node `to` instead, e.g. when a node is replaced by another one
+    pub fn retarget(&self, from: usize, to: usize) -> Self {
+        self.map_node_indices(|index| if index == from { to } else { index })
+    }
 }
 
+// Endpoints compare and hash the coordinates of absolute endpoints and node anchors by their bits
+// (with -0.0 taken as 0.0), so that equality is reflexive even for NaN
 #[derive(Debug, Clone)]
 pub enum Endpoint {
     Absolute(Point),
     Socket(LogicalEndpoint),
+    // A point on the bounds of the node with the given index
+    NodeAnchor(usize, RelativeAnchor),
+    // The socket with the given `Socket::name` on the node with the given index, so that links
+    // survive reordering the sockets of a node. It is resolved during layout; as the role of the
+    // socket is only known then, `Link::from_unordered` treats it like an absolute endpoint. A
+    // name that the node does not declare leaves the link dangling at the edge of the node.
+    NamedSocket(usize, String),
+}
+
+// A position relative to the bounds of a node, as fractions of its width and height
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelativeAnchor {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl RelativeAnchor {
+    pub const TOP_LEFT: Self = Self::new(0.0, 0.0);
+    pub const TOP_CENTER: Self = Self::new(0.5, 0.0);
+    pub const TOP_RIGHT: Self = Self::new(1.0, 0.0);
+    pub const CENTER_LEFT: Self = Self::new(0.0, 0.5);
+    pub const CENTER: Self = Self::new(0.5, 0.5);
+    pub const CENTER_RIGHT: Self = Self::new(1.0, 0.5);
+    pub const BOTTOM_LEFT: Self = Self::new(0.0, 1.0);
+    pub const BOTTOM_CENTER: Self = Self::new(0.5, 1.0);
+    pub const BOTTOM_RIGHT: Self = Self::new(1.0, 1.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+// Adding zero turns -0.0 into 0.0
+fn f32_bits(value: f32) -> u32 {
+    (value + 0.0).to_bits()
+}
+
+impl PartialEq for Endpoint {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Endpoint::Absolute(a), Endpoint::Absolute(b)) => {
+                f32_bits(a.x) == f32_bits(b.x) && f32_bits(a.y) == f32_bits(b.y)
+            }
+            (Endpoint::Socket(a), Endpoint::Socket(b)) => a == b,
+            (Endpoint::NodeAnchor(a_index, a), Endpoint::NodeAnchor(b_index, b)) => {
+                a_index == b_index
+                    && f32_bits(a.x) == f32_bits(b.x)
+                    && f32_bits(a.y) == f32_bits(b.y)
+            }
+            (Endpoint::NamedSocket(a_index, a), Endpoint::NamedSocket(b_index, b)) => {
+                a_index == b_index && a == b
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Endpoint {}
+
+impl Hash for Endpoint {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Endpoint::Absolute(point) => {
+                f32_bits(point.x).hash(state);
+                f32_bits(point.y).hash(state);
+            }
+            Endpoint::Socket(logical) => logical.hash(state),
+            Endpoint::NodeAnchor(node_index, anchor) => {
+                node_index.hash(state);
+                f32_bits(anchor.x).hash(state);
+                f32_bits(anchor.y).hash(state);
+            }
+            Endpoint::NamedSocket(node_index, name) => {
+                node_index.hash(state);
+                name.hash(state);
+            }
+        }
+    }
 }
 
 impl Endpoint {
@@ -259,35 +1173,190 @@ impl Endpoint {
         Self::socket(node_index, SocketRole::In, socket_index)
     }
 
-    fn resolve(&self, scale: f32, socket_state: &super::node_element::SocketLayoutState) -> Vector {
+    pub fn node_anchor(node_index: usize, anchor: RelativeAnchor) -> Self {
+        Endpoint::NodeAnchor(node_index, anchor)
+    }
+
+    pub fn named_socket(node_index: usize, name: impl Into<String>) -> Self {
+        Endpoint::NamedSocket(node_index, name.into())
+    }
+
+    // The index of the node this endpoint is attached to, if any
+    pub fn node_index(&self) -> Option<usize> {
+        match self {
+            Endpoint::Absolute(_) => None,
+            Endpoint::Socket(logical) => Some(logical.node_index),
+            Endpoint::NodeAnchor(node_index, _) | Endpoint::NamedSocket(node_index, _) => {
+                Some(*node_index)
+            }
+        }
+    }
+
+    // Returns a copy of this endpoint with its node index replaced by `f(node_index)`
+    pub fn map_node_index(&self, f: impl FnOnce(usize) -> usize) -> Self {
+        match self {
+            Endpoint::Absolute(point) => Endpoint::Absolute(*point),
+            Endpoint::Socket(logical) => Endpoint::Socket(LogicalEndpoint {
+                node_index: f(logical.node_index),
+                ..*logical
+            }),
+            Endpoint::NodeAnchor(node_index, anchor) => {
+                Endpoint::NodeAnchor(f(*node_index), *anchor)
+            }
+            Endpoint::NamedSocket(node_index, name) => {
+                Endpoint::NamedSocket(f(*node_index), name.clone())
+            }
+        }
+    }
+
+    // The direction in which a connection leaves this endpoint, if it is a socket
+    fn direction(&self, socket_state: &super::node_element::SocketLayoutState) -> Option<Vector> {
+        match self {
+            Endpoint::Socket(logical) => {
+                let node_sides = match logical.role {
+                    SocketRole::In => &socket_state.input_sides,
+                    SocketRole::Out => &socket_state.output_sides,
+                };
+
+                node_sides
+                    .get(logical.node_index)
+                    .and_then(|sides| sides.get(logical.socket_index))
+                    .map(|side| side.outward_direction())
+            }
+            Endpoint::NamedSocket(node_index, name) => {
+                Endpoint::Socket(resolve_name(*node_index, name, socket_state)?)
+                    .direction(socket_state)
+            }
+            Endpoint::Absolute(_) | Endpoint::NodeAnchor(..) => None,
+        }
+    }
+
+    // A socket that has not been laid out (such as a named socket that its node does not
+    // declare, or an index beyond the sockets of the node) leaves the link dangling at the
+    // `unresolved` anchor of the node instead. A node that has not been laid out cannot be
+    // resolved at all.
+    fn resolve(
+        &self,
+        scale: f32,
+        socket_state: &super::node_element::SocketLayoutState,
+        unresolved: RelativeAnchor,
+    ) -> Option<Vector> {
         match self {
-            Endpoint::Absolute(point) => Vector::new(point.x * scale, point.y * scale),
+            Endpoint::Absolute(point) => Some(Vector::new(point.x * scale, point.y * scale)),
+            Endpoint::NodeAnchor(node_index, anchor) => {
+                socket_state.nodes.get(*node_index).map(|rect| {
+                    Vector::new(
+                        rect.x + rect.width * anchor.x,
+                        rect.y + rect.height * anchor.y,
+                    )
+                })
+            }
             Endpoint::Socket(logical) => {
                 let node_sockets = match logical.role {
                     SocketRole::In => &socket_state.inputs,
                     SocketRole::Out => &socket_state.outputs,
                 };
 
-                match node_sockets.get(logical.node_index) {
-                    Some(sockets) => match sockets.get(logical.socket_index) {
-                        Some(rect) => Vector::new(rect.center_x(), rect.center_y()),
-                        None => panic!("socket index {} out of bounds for role {:?} of node {}; only found {} socket(s)", logical.socket_index, logical.role, logical.node_index, sockets.len())
-                    }
-                    None => panic!("node index {} out of bounds for role {:?}; only found {} node(s)", logical.node_index, logical.role, node_sockets.len())
+                match node_sockets
+                    .get(logical.node_index)
+                    .and_then(|sockets| sockets.get(logical.socket_index))
+                {
+                    Some(rect) => Some(Vector::new(rect.center_x(), rect.center_y())),
+                    None => Endpoint::NodeAnchor(logical.node_index, unresolved).resolve(
+                        scale,
+                        socket_state,
+                        unresolved,
+                    ),
+                }
+            }
+            Endpoint::NamedSocket(node_index, name) => {
+                match resolve_name(*node_index, name, socket_state) {
+                    Some(logical) => Endpoint::Socket(logical),
+                    None => Endpoint::NodeAnchor(*node_index, unresolved),
                 }
+                .resolve(scale, socket_state, unresolved)
             }
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+fn resolve_name(
+    node_index: usize,
+    name: &str,
+    socket_state: &super::node_element::SocketLayoutState,
+) -> Option<LogicalEndpoint> {
+    socket_state.named_socket(node_index, name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct LogicalEndpoint {
     pub node_index: usize,
     pub role: SocketRole,
     pub socket_index: usize,
 }
 
-fn line_to_polygon(points: &[Vector], width: f32) -> (Vec<Vector>, Vec<u32>) {
+fn draw_flow<Renderer>(
+    renderer: &mut Renderer,
+    flow: &FlowAnimation,
+    time: f32,
+    spline: &[Vector],
+    scale: f32,
+    translation: Vector,
+    color: iced::Color,
+) where
+    Renderer: renderer::Renderer,
+{
+    let spacing = flow.spacing * scale;
+    if spacing < 1.0 || spline.len() < 2 {
+        return;
+    }
+
+    let radius = flow.dot_radius * scale;
+    let phase = (time * flow.speed * scale).rem_euclid(spacing);
+
+    let points: Vec<Vector> = match flow.direction {
+        FlowDirection::Forward => spline.to_vec(),
+        FlowDirection::Backward => spline.iter().rev().copied().collect(),
+    };
+
+    // Walk along the polyline and place a dot every `spacing` units, starting at `phase`
+    let mut next_dot = phase;
+    let mut travelled = 0.0;
+    for segment in points.windows(2) {
+        let delta = segment[1] - segment[0];
+        let length = (delta.x * delta.x + delta.y * delta.y).sqrt();
+
+        while next_dot <= travelled + length {
+            let t = if length > 0.0 {
+                (next_dot - travelled) / length
+            } else {
+                0.0
+            };
+            let center = segment[0] + delta * t + translation;
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle::new(
+                        Point::new(center.x - radius, center.y - radius),
+                        Size::new(radius * 2.0, radius * 2.0),
+                    ),
+                    border: Border {
+                        radius: radius.into(),
+                        ..Border::default()
+                    },
+                    ..renderer::Quad::default()
+                },
+                Background::Color(color),
+            );
+
+            next_dot += spacing;
+        }
+
+        travelled += length;
+    }
+}
+
+pub(crate) fn line_to_polygon(points: &[Vector], width: f32) -> (Vec<Vector>, Vec<u32>) {
     let mut result = Vec::new();
     let mut indices = Vec::new();
 
@@ -329,10 +1398,12 @@ fn dot_vector(vector: Vector, other: Vector) -> f32 {
     vector.x * other.x + vector.y * other.y
 }
 
-fn generate_spline(
+pub(crate) fn generate_spline(
     from: Vector,
+    from_direction: Vector,
     control_scale: f32,
     to: Vector,
+    to_direction: Vector,
     number_of_segments: usize,
     alpha: f32,
 ) -> Vec<Vector> {
@@ -341,10 +1412,10 @@ fn generate_spline(
     for i in 0..number_of_segments {
         let t = i as f32 / (number_of_segments - 1) as f32;
         let p = catmull_rom(
-            Vector::new(from.x - control_scale, from.y),
+            from - from_direction * control_scale,
             from,
             to,
-            Vector::new(to.x + control_scale, to.y),
+            to + to_direction * control_scale,
             t,
             alpha,
         );
@@ -354,6 +1425,190 @@ fn generate_spline(
     spline
 }
 
+// The corners of a path with horizontal and vertical segments only, leaving `from` and entering
+// `to` in the given directions. The middle segment runs along a channel, whose position is
+// passed to `channel` as a suggestion, along with whether the segment is vertical and the span
+// it covers; the position returned is used instead.
+fn orthogonal_route(
+    from: Vector,
+    to: Vector,
+    from_direction: Vector,
+    to_direction: Vector,
+    lead: f32,
+    channel: impl FnOnce(bool, f32, (f32, f32)) -> f32,
+) -> Vec<Vector> {
+    // Work in a frame where the start socket faces along the x axis
+    let transposed = from_direction.y.abs() > from_direction.x.abs();
+    let frame = |vector: Vector| {
+        if transposed {
+            Vector::new(vector.y, vector.x)
+        } else {
+            vector
+        }
+    };
+    let (from_direction, to_direction) = (frame(from_direction), frame(to_direction));
+    let (start, end) = (frame(from), frame(to));
+
+    // A socket on a side of the other axis is reached with a single corner
+    if to_direction.y.abs() > to_direction.x.abs() {
+        return vec![from, frame(Vector::new(end.x, start.y)), to];
+    }
+
+    let span = |a: f32, b: f32| (a.min(b), a.max(b));
+    let out = from_direction.x.signum();
+    let into = to_direction.x.signum();
+    let lead_out = start.x + out * lead;
+    let lead_in = end.x - into * lead;
+    let corners = if out == into && (lead_in - lead_out) * out >= 0.0 {
+        // Across between the sockets
+        let x = channel(!transposed, (start.x + end.x) / 2.0, span(start.y, end.y));
+        vec![Vector::new(x, start.y), Vector::new(x, end.y)]
+    } else {
+        // Out of the start socket, back along a channel, and into the end socket
+        let y = channel(transposed, (start.y + end.y) / 2.0, span(lead_out, lead_in));
+        vec![
+            Vector::new(lead_out, start.y),
+            Vector::new(lead_out, y),
+            Vector::new(lead_in, y),
+            Vector::new(lead_in, end.y),
+        ]
+    };
+
+    let mut route = vec![from];
+    route.extend(corners.into_iter().map(frame));
+    route.push(to);
+    route
+}
+
+// The corners of a path leaving `from` in `direction` and entering `to` in the same direction,
+// going around the start and end nodes. Returns `None` if the end lies far enough in front of
+// the start that the link can be drawn directly.
+fn loopback_waypoints(
+    from: Vector,
+    to: Vector,
+    direction: Vector,
+    clearance: f32,
+    start_node: Option<Rectangle>,
+    end_node: Option<Rectangle>,
+    same_node: bool,
+) -> Option<Vec<Vector>> {
+    // Work in a frame where `u` points along the direction and `v` is perpendicular to it
+    let u = normalize_vector(direction);
+    let v = Vector::new(-u.y, u.x);
+    let (from_u, from_v) = (dot_vector(from, u), dot_vector(from, v));
+    let (to_u, to_v) = (dot_vector(to, u), dot_vector(to, v));
+
+    if !same_node && to_u - from_u >= clearance * 2.0 {
+        return None;
+    }
+
+    // The extent of a node perpendicular to the direction
+    let extent = |node: Option<Rectangle>, fallback: f32| match node {
+        Some(rect) => [
+            Vector::new(rect.x, rect.y),
+            Vector::new(rect.x + rect.width, rect.y),
+            Vector::new(rect.x, rect.y + rect.height),
+            Vector::new(rect.x + rect.width, rect.y + rect.height),
+        ]
+        .iter()
+        .map(|corner| dot_vector(*corner, v))
+        .fold((fallback, fallback), |(min, max), value| {
+            (min.min(value), max.max(value))
+        }),
+        None => (fallback, fallback),
+    };
+    let (start_min, start_max) = extent(start_node, from_v);
+    let (end_min, end_max) = extent(end_node, to_v);
+
+    // Pass between the nodes if there is room, otherwise around both of them on the side that is
+    // closer to the endpoints
+    let pass_v = if start_max + clearance < end_min - clearance {
+        (start_max + end_min) / 2.0
+    } else if end_max + clearance < start_min - clearance {
+        (end_max + start_min) / 2.0
+    } else {
+        let min = start_min.min(end_min) - clearance;
+        let max = start_max.max(end_max) + clearance;
+        let middle = (from_v + to_v) / 2.0;
+        if middle - min < max - middle {
+            min
+        } else {
+            max
+        }
+    };
+
+    let point = |along: f32, across: f32| u * along + v * across;
+    let out_u = from_u + clearance;
+    let in_u = to_u - clearance;
+    Some(vec![
+        point(from_u, from_v),
+        point(out_u, from_v),
+        point(out_u, pass_v),
+        point(in_u, pass_v),
+        point(in_u, to_v),
+        point(to_u, to_v),
+    ])
+}
+
+// A smooth curve through all of the given points
+fn smooth_polyline(points: &[Vector], number_of_segments: usize) -> Vec<Vector> {
+    // Catmull-Rom splines cannot handle coincident points
+    let mut points = points.to_vec();
+    points.dedup_by(|a, b| {
+        let d = *a - *b;
+        dot_vector(d, d) < 0.25
+    });
+
+    if points.len() < 2 {
+        return points;
+    }
+
+    let last = points.len() - 1;
+    let segments_per_span = (number_of_segments / last).max(4);
+    let mut spline = Vec::new();
+
+    for i in 0..last {
+        // Mirror the neighbouring points at the ends
+        let p0 = if i == 0 {
+            points[0] * 2.0 - points[1]
+        } else {
+            points[i - 1]
+        };
+        let p3 = if i + 1 == last {
+            points[last] * 2.0 - points[last - 1]
+        } else {
+            points[i + 2]
+        };
+
+        // Every span starts where the previous one ended
+        let first_segment = if i == 0 { 0 } else { 1 };
+        for segment in first_segment..=segments_per_span {
+            let t = segment as f32 / segments_per_span as f32;
+            spline.push(catmull_rom(p0, points[i], points[i + 1], p3, t, 0.5));
+        }
+    }
+
+    spline
+}
+
+fn generate_bezier(
+    p0: Vector,
+    p1: Vector,
+    p2: Vector,
+    p3: Vector,
+    number_of_segments: usize,
+) -> Vec<Vector> {
+    // Both ends are always part of the curve, so it is at least a straight line
+    let number_of_segments = number_of_segments.max(2);
+    (0..number_of_segments)
+        .map(|i| {
+            let t = i as f32 / (number_of_segments - 1) as f32;
+            let u = 1.0 - t;
+            p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+        })
+        .collect()
+}
+
 // Code taken and adapted from https://en.wikipedia.org/wiki/Centripetal_Catmull%E2%80%93Rom_spline
 fn get_t(t: f32, alpha: f32, p0: Vector, p1: Vector) -> f32 {
     let d = p1 - p0;
@@ -376,6 +1631,49 @@ fn catmull_rom(p0: Vector, p1: Vector, p2: Vector, p3: Vector, t: f32, alpha: f3
     b1 * ((t2 - t) / (t2 - t1)) + b2 * ((t - t1) / (t2 - t1))
 }
 
+fn distance_to_segment(point: Vector, start: Vector, end: Vector) -> f32 {
+    let segment = end - start;
+    let length_squared = dot_vector(segment, segment);
+    let t = if length_squared > 0.0 {
+        (dot_vector(point - start, segment) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let offset = point - (start + segment * t);
+    dot_vector(offset, offset).sqrt()
+}
+
+// Whether any part of the segment lies within the rectangle, by clipping the segment against
+// every edge of the rectangle in turn (Liang-Barsky)
+fn segment_intersects_rectangle(start: Vector, end: Vector, rectangle: Rectangle) -> bool {
+    let delta = end - start;
+    let (mut enter, mut exit) = (0.0_f32, 1.0_f32);
+
+    for (p, q) in [
+        (-delta.x, start.x - rectangle.x),
+        (delta.x, rectangle.x + rectangle.width - start.x),
+        (-delta.y, start.y - rectangle.y),
+        (delta.y, rectangle.y + rectangle.height - start.y),
+    ] {
+        if p == 0.0 {
+            // Parallel to this edge, and outside of it
+            if q < 0.0 {
+                return false;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                enter = enter.max(t);
+            } else {
+                exit = exit.min(t);
+            }
+        }
+    }
+
+    enter <= exit
+}
+
 fn bounds_for_vectors(points: &[Vector]) -> iced::Rectangle {
     let mut min_x = points[0].x;
     let mut min_y = points[0].y;
@@ -404,3 +1702,130 @@ fn bounds_for_vectors(points: &[Vector]) -> iced::Rectangle {
         height: max_y - min_y,
     }
 }
+
+// For every socket with several links, the average position of the other ends of its links, see
+// `GraphContainer::edge_bundling`. Links with an end that is not a socket are left out.
+pub(crate) fn bundle_targets<'a>(
+    links: impl IntoIterator<Item = &'a Link>,
+    scale: f32,
+    socket_state: &super::node_element::SocketLayoutState,
+) -> HashMap<LogicalEndpoint, Vector> {
+    let mut sums: HashMap<LogicalEndpoint, (Vector, usize)> = HashMap::new();
+    for link in links {
+        let (Some(start), Some(end)) = (
+            socket_state.logical_endpoint(&link.start),
+            socket_state.logical_endpoint(&link.end),
+        ) else {
+            continue;
+        };
+
+        let (Some(from), Some(to)) = (
+            link.start
+                .resolve(scale, socket_state, RelativeAnchor::CENTER_RIGHT),
+            link.end
+                .resolve(scale, socket_state, RelativeAnchor::CENTER_LEFT),
+        ) else {
+            continue;
+        };
+        for (socket, other) in [(start, to), (end, from)] {
+            let (sum, count) = sums.entry(socket).or_insert((Vector::ZERO, 0));
+            *sum = *sum + other;
+            *count += 1;
+        }
+    }
+
+    sums.into_iter()
+        .filter(|(_, (_, count))| *count > 1)
+        .map(|(socket, (sum, count))| (socket, sum * (1.0 / count as f32)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RIGHT: Vector = Vector::new(1.0, 0.0);
+    const DOWN: Vector = Vector::new(0.0, 1.0);
+
+    #[test]
+    fn orthogonal_route_crosses_between_facing_sockets() {
+        let (from, to) = (Vector::new(0.0, 0.0), Vector::new(100.0, 50.0));
+
+        let route = orthogonal_route(from, to, RIGHT, RIGHT, 10.0, |vertical, position, span| {
+            assert!(vertical);
+            assert_eq!((position, span), (50.0, (0.0, 50.0)));
+            60.0
+        });
+
+        assert_eq!(
+            route,
+            vec![from, Vector::new(60.0, 0.0), Vector::new(60.0, 50.0), to]
+        );
+    }
+
+    #[test]
+    fn orthogonal_route_leads_back_around() {
+        let (from, to) = (Vector::new(0.0, 0.0), Vector::new(-100.0, 50.0));
+
+        let route = orthogonal_route(from, to, RIGHT, RIGHT, 10.0, |vertical, position, span| {
+            assert!(!vertical);
+            assert_eq!((position, span), (25.0, (-110.0, 10.0)));
+            position
+        });
+
+        assert_eq!(
+            route,
+            vec![
+                from,
+                Vector::new(10.0, 0.0),
+                Vector::new(10.0, 25.0),
+                Vector::new(-110.0, 25.0),
+                Vector::new(-110.0, 50.0),
+                to,
+            ]
+        );
+    }
+
+    #[test]
+    fn orthogonal_route_turns_once_between_the_axes() {
+        let (from, to) = (Vector::new(0.0, 0.0), Vector::new(100.0, 50.0));
+
+        let route = orthogonal_route(from, to, RIGHT, DOWN, 10.0, |_, _, _| {
+            panic!("a single corner needs no channel")
+        });
+        assert_eq!(route, vec![from, Vector::new(100.0, 0.0), to]);
+
+        // Sockets on the top and bottom are routed the same way, with the axes swapped
+        let route = orthogonal_route(from, to, DOWN, DOWN, 10.0, |vertical, position, span| {
+            assert!(!vertical);
+            assert_eq!((position, span), (25.0, (0.0, 100.0)));
+            position
+        });
+        assert_eq!(
+            route,
+            vec![from, Vector::new(0.0, 25.0), Vector::new(100.0, 25.0), to]
+        );
+    }
+
+    #[test]
+    fn segment_intersects_rectangle_it_crosses_or_touches() {
+        let rectangle = Rectangle::new(Point::new(10.0, 10.0), iced::Size::new(20.0, 20.0));
+        let hits = |start: (f32, f32), end: (f32, f32)| {
+            segment_intersects_rectangle(
+                Vector::new(start.0, start.1),
+                Vector::new(end.0, end.1),
+                rectangle,
+            )
+        };
+
+        assert!(hits((0.0, 0.0), (40.0, 40.0)));
+        assert!(hits((15.0, 15.0), (20.0, 25.0)));
+        assert!(hits((0.0, 20.0), (10.0, 20.0)));
+        assert!(hits((20.0, 20.0), (20.0, 20.0)));
+
+        assert!(!hits((0.0, 0.0), (5.0, 5.0)));
+        assert!(!hits((0.0, 25.0), (15.0, 40.0)));
+        assert!(!hits((0.0, 5.0), (40.0, 5.0)));
+        assert!(!hits((35.0, 0.0), (35.0, 40.0)));
+    }
+}
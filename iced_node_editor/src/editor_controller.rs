@@ -0,0 +1,246 @@
+use std::rc::Rc;
+
+use iced::advanced::{renderer, text};
+use iced::{Point, Vector};
+
+use crate::{
+    clipboard::duplicate,
+    graph_container::GraphContainer,
+    mesh_renderer::MeshRenderer,
+    styles::{connection, graph_container, node},
+    Connection, Endpoint, GraphState, Link, LogicalEndpoint, Node, Selection,
+};
+
+// Zoom factor of one step of the mouse wheel
+const ZOOM_STEP: f32 = 1.05;
+
+// A ready-made editor for applications that do not need to handle every interaction themselves.
+// The controller owns the graph (node positions, links and viewport), the selection and the
+// connection that is being dragged. Its `view` builds a graph container whose events are all
+// turned into `EditorEvent`s, which are then passed back to `update`.
+//
+// Applications that need more control can still build the graph container by hand; the
+// controller only uses its public API.
+#[derive(Debug, Clone)]
+pub struct EditorController<N> {
+    graph: GraphState<N>,
+    // Only ever holds nodes
+    selection: Selection,
+    dangling_source: Option<LogicalEndpoint>,
+    dangling_connection: Option<Link>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditorEvent {
+    Scaled(f32, f32, f32),
+    Translated(f32, f32),
+    // A node was dragged by the given screen-space distance
+    NodeMoved(usize, f32, f32),
+    Connected(Link),
+    // A link was grabbed by its end at the given graph-space position
+    Disconnected(Point, Option<Link>),
+    Dangling(Option<(LogicalEndpoint, Link)>),
+    CanvasClicked(Point),
+}
+
+impl<N> Default for EditorController<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> EditorController<N> {
+    pub fn new() -> Self {
+        Self::from_graph(GraphState::new())
+    }
+
+    pub fn from_graph(graph: GraphState<N>) -> Self {
+        Self {
+            graph,
+            selection: Selection::new(),
+            dangling_source: None,
+            dangling_connection: None,
+        }
+    }
+
+    pub fn graph(&self) -> &GraphState<N> {
+        &self.graph
+    }
+
+    // Removing nodes this way keeps the selection as it is, so it may refer to other nodes
+    // afterwards; `remove_selected` takes care of that.
+    pub fn graph_mut(&mut self) -> &mut GraphState<N> {
+        &mut self.graph
+    }
+
+    // The indices of the selected nodes, in the order they were selected
+    pub fn selection(&self) -> &[usize] {
+        self.selection.nodes()
+    }
+
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selection.contains_node(index)
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.selection.select_node(index);
+    }
+
+    pub fn deselect(&mut self, index: usize) {
+        self.selection.deselect_node(index);
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection.clear();
+    }
+
+    // Adds every node downstream of the selected ones to the selection
+    pub fn select_downstream(&mut self) {
+        self.selection = std::mem::take(&mut self.selection).with_downstream(self.graph.links());
+    }
+
+    // Adds every node upstream of the selected ones to the selection
+    pub fn select_upstream(&mut self) {
+        self.selection = std::mem::take(&mut self.selection).with_upstream(self.graph.links());
+    }
+
+    // Removes the selected nodes along with their links, returning their data
+    pub fn remove_selected(&mut self) -> Vec<N> {
+        let mut selection = std::mem::take(&mut self.selection).nodes().to_vec();
+        // Removing from the back keeps the indices of the remaining selected nodes intact
+        selection.sort_unstable_by(|a, b| b.cmp(a));
+        selection
+            .into_iter()
+            .map(|index| self.graph.remove_node(index))
+            .collect()
+    }
+
+    pub fn update(&mut self, event: EditorEvent) {
+        match event {
+            EditorEvent::Scaled(x, y, scale) => {
+                let matrix = self
+                    .graph
+                    .matrix()
+                    .translate(-x, -y)
+                    .scale(if scale > 0.0 {
+                        ZOOM_STEP
+                    } else {
+                        1.0 / ZOOM_STEP
+                    })
+                    .translate(x, y);
+                self.graph.set_matrix(matrix);
+            }
+            EditorEvent::Translated(x, y) => {
+                let matrix = self.graph.matrix().translate(x, y);
+                self.graph.set_matrix(matrix);
+            }
+            EditorEvent::NodeMoved(index, x, y) => {
+                // Dragging a selected node moves the whole selection, dragging any other node
+                // selects it instead
+                if !self.is_selected(index) {
+                    self.selection = Selection::new().with_node(index);
+                }
+                let scale = self.graph.matrix().get_scale();
+                for selected in self.selection.nodes() {
+                    self.graph.translate_node(*selected, x / scale, y / scale);
+                }
+            }
+            EditorEvent::Connected(link) => self.graph.add_link(link),
+            EditorEvent::Disconnected(position, Some(link)) => {
+                self.graph.remove_link(&link);
+
+                // Keep the link attached to the cursor until it is dropped somewhere else
+                let (start, _) = link.unwrap_sockets();
+                self.dangling_source = Some(*start);
+                self.dangling_connection = Some(Link::from_unordered(
+                    Endpoint::Socket(*start),
+                    Endpoint::Absolute(position),
+                ));
+            }
+            EditorEvent::Disconnected(_, None) => {}
+            EditorEvent::Dangling(Some((source, link))) => {
+                self.dangling_source = Some(source);
+                self.dangling_connection = Some(link);
+            }
+            EditorEvent::Dangling(None) => {
+                self.dangling_source = None;
+                self.dangling_connection = None;
+            }
+            EditorEvent::CanvasClicked(_) => self.clear_selection(),
+        }
+    }
+
+    // Builds the graph container. `view_node` creates the widget of a node from its index, its
+    // data and whether it is selected; its position is filled in by the controller. All events
+    // of the container are turned into messages by `on_event`. The container can be configured
+    // further, as long as the callbacks set here are left alone.
+    pub fn view<'a, Message, Theme, Renderer>(
+        &'a self,
+        view_node: impl Fn(usize, &'a N, bool) -> Node<'a, Message, Theme, Renderer>,
+        on_event: impl Fn(EditorEvent) -> Message + 'a,
+    ) -> GraphContainer<'a, Message, Theme, Renderer>
+    where
+        Message: 'a,
+        Theme: graph_container::StyleSheet + node::StyleSheet + connection::StyleSheet + 'a,
+        Renderer: renderer::Renderer + text::Renderer + MeshRenderer + 'a,
+    {
+        let on_event: Rc<dyn Fn(EditorEvent) -> Message + 'a> = Rc::new(on_event);
+
+        let mut content = self.graph.elements(|index, data| {
+            let on_event = on_event.clone();
+            view_node(index, data, self.is_selected(index))
+                .on_translate(move |(x, y)| on_event(EditorEvent::NodeMoved(index, x, y)))
+        });
+        if let Some(link) = &self.dangling_connection {
+            content.push(Connection::new(link.clone()).into());
+        }
+
+        let on_scale = on_event.clone();
+        let on_disconnect = on_event.clone();
+        GraphContainer::new(content)
+            .matrix(self.graph.matrix())
+            .dangling_source(self.dangling_source)
+            .on_translate(forward(&on_event, |(x, y)| EditorEvent::Translated(x, y)))
+            .on_scale(move |x, y, scale| on_scale(EditorEvent::Scaled(x, y, scale)))
+            .on_connect(forward(&on_event, EditorEvent::Connected))
+            .on_disconnect(move |_, position, link| {
+                on_disconnect(EditorEvent::Disconnected(position, link))
+            })
+            .on_dangling(forward(&on_event, EditorEvent::Dangling))
+            .on_canvas_click(forward(&on_event, EditorEvent::CanvasClicked))
+    }
+}
+
+impl<N: Clone> EditorController<N> {
+    // Adds copies of the selected nodes, moved by `offset`, along with copies of the links
+    // between them, and selects the copies instead of the originals. Nested graphs are not
+    // copied.
+    pub fn duplicate_selected(&mut self, offset: Vector) {
+        let (nodes, links) = duplicate(
+            self.selection.nodes(),
+            self.graph.links(),
+            offset,
+            self.graph.nodes().len(),
+            |index| self.graph.nodes()[index].position,
+            |index| self.graph.nodes()[index].data.clone(),
+        );
+
+        self.selection = Selection::new();
+        for (position, data) in nodes {
+            let index = self.graph.add_node(position, data);
+            self.selection.select_node(index);
+        }
+        for link in links {
+            self.graph.add_link(link);
+        }
+    }
+}
+
+// Turns the values passed to a callback of the graph container into messages
+fn forward<'a, T, Message: 'a>(
+    on_event: &Rc<dyn Fn(EditorEvent) -> Message + 'a>,
+    f: impl Fn(T) -> EditorEvent + 'a,
+) -> impl Fn(T) -> Message + 'a {
+    let on_event = on_event.clone();
+    move |value| on_event(f(value))
+}
@@ -0,0 +1,357 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashSet};
+use std::fmt;
+
+use crate::Link;
+
+// Queries on the structure of a graph, given by its links. Every link leads from the node of its
+// start to the node of its end; links with an end that is not attached to a node (e.g. an
+// absolute endpoint) are ignored, as are repeated links between the same two nodes.
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Adjacency {
+    successors: BTreeMap<usize, Vec<usize>>,
+    predecessors: BTreeMap<usize, Vec<usize>>,
+}
+
+// A cycle that prevents a topological order. The nodes are listed in the direction of the
+// links, and the last one links back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub nodes: Vec<usize>,
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the graph contains a cycle through the nodes")?;
+        for node in &self.nodes {
+            write!(f, " {}", node)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Cycle {}
+
+impl Adjacency {
+    pub fn new<'a>(links: impl IntoIterator<Item = &'a Link>) -> Self {
+        let mut adjacency = Self::default();
+        for link in links {
+            let (Some(from), Some(to)) = (link.start().node_index(), link.end().node_index())
+            else {
+                continue;
+            };
+
+            let successors = adjacency.successors.entry(from).or_default();
+            if !successors.contains(&to) {
+                successors.push(to);
+                adjacency.predecessors.entry(to).or_default().push(from);
+            }
+        }
+
+        adjacency
+    }
+
+    // The nodes that links from the node lead to, in the order of the links
+    pub fn successors(&self, node: usize) -> &[usize] {
+        self.successors.get(&node).map_or(&[], Vec::as_slice)
+    }
+
+    // The nodes that links to the node come from, in the order of the links
+    pub fn predecessors(&self, node: usize) -> &[usize] {
+        self.predecessors.get(&node).map_or(&[], Vec::as_slice)
+    }
+
+    // The nodes that are reachable from the node by following links, in ascending order. The
+    // node itself is only included if it is part of a cycle.
+    pub fn downstream(&self, node: usize) -> Vec<usize> {
+        collect_reachable(node, |node| self.successors(node))
+    }
+
+    // The nodes from which the node is reachable by following links, in ascending order. The
+    // node itself is only included if it is part of a cycle.
+    pub fn upstream(&self, node: usize) -> Vec<usize> {
+        collect_reachable(node, |node| self.predecessors(node))
+    }
+
+    // Whether following links leads from the node `from` to the node `to`. A node always
+    // reaches itself.
+    pub fn reaches(&self, from: usize, to: usize) -> bool {
+        from == to || reachable(from, |node| self.successors(node)).contains(&to)
+    }
+
+    // Orders the nodes `0..node_count` such that every node comes after all of its predecessors,
+    // e.g. to evaluate a dataflow graph. Nodes that do not depend on each other keep the order
+    // of their indices. Links to nodes outside of the range are ignored.
+    pub fn topological_sort(&self, node_count: usize) -> Result<Vec<usize>, Cycle> {
+        let in_range = |nodes: &[usize]| {
+            nodes
+                .iter()
+                .copied()
+                .filter(|node| *node < node_count)
+                .collect::<Vec<_>>()
+        };
+
+        let mut in_degrees: Vec<usize> = (0..node_count)
+            .map(|node| in_range(self.predecessors(node)).len())
+            .collect();
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..node_count)
+            .filter(|node| in_degrees[*node] == 0)
+            .map(Reverse)
+            .collect();
+
+        let mut order = Vec::with_capacity(node_count);
+        while let Some(Reverse(node)) = ready.pop() {
+            order.push(node);
+            for successor in in_range(self.successors(node)) {
+                in_degrees[successor] -= 1;
+                if in_degrees[successor] == 0 {
+                    ready.push(Reverse(successor));
+                }
+            }
+        }
+
+        if order.len() == node_count {
+            return Ok(order);
+        }
+
+        // Every node that is left has a predecessor that is left as well, so walking backwards
+        // from any of them eventually runs into a node that was already visited
+        let mut path = vec![in_degrees.iter().position(|degree| *degree > 0).unwrap()];
+        loop {
+            let node = *path.last().unwrap();
+            let predecessor = in_range(self.predecessors(node))
+                .into_iter()
+                .find(|predecessor| in_degrees[*predecessor] > 0)
+                .unwrap();
+
+            if let Some(start) = path.iter().position(|visited| *visited == predecessor) {
+                let mut nodes = path.split_off(start);
+                nodes.reverse();
+                return Err(Cycle { nodes });
+            }
+            path.push(predecessor);
+        }
+    }
+}
+
+pub fn adjacency(links: &[Link]) -> Adjacency {
+    Adjacency::new(links)
+}
+
+pub fn topological_sort(links: &[Link], node_count: usize) -> Result<Vec<usize>, Cycle> {
+    Adjacency::new(links).topological_sort(node_count)
+}
+
+pub fn downstream(links: &[Link], node: usize) -> Vec<usize> {
+    Adjacency::new(links).downstream(node)
+}
+
+pub fn upstream(links: &[Link], node: usize) -> Vec<usize> {
+    Adjacency::new(links).upstream(node)
+}
+
+pub fn reaches(links: &[Link], from: usize, to: usize) -> bool {
+    Adjacency::new(links).reaches(from, to)
+}
+
+fn reachable<'a>(start: usize, next: impl Fn(usize) -> &'a [usize]) -> HashSet<usize> {
+    let mut visited = HashSet::new();
+    let mut stack = next(start).to_vec();
+    while let Some(node) = stack.pop() {
+        if visited.insert(node) {
+            stack.extend_from_slice(next(node));
+        }
+    }
+
+    visited
+}
+
+fn collect_reachable<'a>(start: usize, next: impl Fn(usize) -> &'a [usize]) -> Vec<usize> {
+    let mut nodes: Vec<usize> = reachable(start, next).into_iter().collect();
+    nodes.sort_unstable();
+    nodes
+}
+
+// Keeps track of the nodes that changed since the graph was last evaluated, and determines
+// which nodes have to be evaluated again because of them: the changed nodes and everything
+// downstream of them, each one after all of its predecessors.
+//
+// Changes to the links are detected by `set_links`: the nodes at the end of added or removed
+// links are marked as changed. Removing a node shifts the indices of the following ones, which
+// is not detected; call `mark_all_dirty` in that case.
+#[derive(Debug, Clone)]
+pub struct DirtyTracker {
+    links: HashSet<Link>,
+    adjacency: Adjacency,
+    // The position of every node in a topological order
+    ranks: Vec<usize>,
+    dirty: HashSet<usize>,
+}
+
+impl DirtyTracker {
+    // Creates a tracker for the nodes `0..node_count`, all of which are dirty at first
+    pub fn new(links: &[Link], node_count: usize) -> Result<Self, Cycle> {
+        let adjacency = Adjacency::new(links);
+        let ranks = ranks(&adjacency.topological_sort(node_count)?);
+
+        Ok(Self {
+            links: links.iter().cloned().collect(),
+            adjacency,
+            dirty: (0..node_count).collect(),
+            ranks,
+        })
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.ranks.len()
+    }
+
+    // Replaces the links and the number of nodes. New nodes and the nodes at the end of added or
+    // removed links are marked as dirty. If the new links contain a cycle, the tracker is left
+    // unchanged.
+    pub fn set_links(&mut self, links: &[Link], node_count: usize) -> Result<(), Cycle> {
+        let adjacency = Adjacency::new(links);
+        let ranks = ranks(&adjacency.topological_sort(node_count)?);
+
+        let links: HashSet<Link> = links.iter().cloned().collect();
+        let changed: Vec<usize> = links
+            .symmetric_difference(&self.links)
+            .filter_map(|link| link.end().node_index())
+            .collect();
+        self.dirty.extend(changed);
+        self.dirty.extend(self.ranks.len()..node_count);
+        self.dirty.retain(|node| *node < node_count);
+
+        self.links = links;
+        self.adjacency = adjacency;
+        self.ranks = ranks;
+        Ok(())
+    }
+
+    // Marks a node as changed, e.g. because one of its parameters was edited
+    pub fn mark_dirty(&mut self, node: usize) {
+        if node < self.node_count() {
+            self.dirty.insert(node);
+        }
+    }
+
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty.extend(0..self.node_count());
+    }
+
+    // Whether the node itself changed. Nodes that only need to be evaluated again because
+    // something upstream changed are not dirty themselves.
+    pub fn is_dirty(&self, node: usize) -> bool {
+        self.dirty.contains(&node)
+    }
+
+    // The nodes that have to be evaluated again, in an order in which every node comes after
+    // all of its predecessors
+    pub fn evaluation_order(&self) -> Vec<usize> {
+        let mut nodes: HashSet<usize> = self.dirty.clone();
+        for node in &self.dirty {
+            nodes.extend(self.adjacency.downstream(*node));
+        }
+
+        let mut nodes: Vec<usize> = nodes
+            .into_iter()
+            .filter(|node| *node < self.node_count())
+            .collect();
+        nodes.sort_unstable_by_key(|node| self.ranks[*node]);
+        nodes
+    }
+
+    // Returns the evaluation order and marks all nodes as clean, once they have been evaluated
+    pub fn take_evaluation_order(&mut self) -> Vec<usize> {
+        let order = self.evaluation_order();
+        self.dirty.clear();
+        order
+    }
+}
+
+// The position of every node in the order
+fn ranks(order: &[usize]) -> Vec<usize> {
+    let mut ranks = vec![0; order.len()];
+    for (rank, node) in order.iter().enumerate() {
+        ranks[*node] = rank;
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Endpoint, LogicalEndpoint, SocketRole};
+
+    // A link from the first output of `from` to the first input of `to`
+    fn link(from: usize, to: usize) -> Link {
+        let socket = |node_index, role| {
+            Endpoint::Socket(LogicalEndpoint {
+                node_index,
+                role,
+                socket_index: 0,
+            })
+        };
+        Link::new(socket(from, SocketRole::Out), socket(to, SocketRole::In))
+    }
+
+    #[test]
+    fn topological_sort_puts_predecessors_first() {
+        let links = [link(3, 1), link(1, 0), link(2, 0)];
+        assert_eq!(topological_sort(&links, 4), Ok(vec![2, 3, 1, 0]));
+
+        // Independent nodes keep the order of their indices, and links to nodes out of range are
+        // left out
+        assert_eq!(topological_sort(&[link(1, 5)], 3), Ok(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn topological_sort_reports_a_cycle() {
+        let links = [link(0, 1), link(1, 2), link(2, 3), link(3, 1)];
+        let cycle = topological_sort(&links, 4).unwrap_err();
+
+        // The cycle may start at any of its nodes, but has to follow the links
+        assert_eq!(cycle.nodes.len(), 3);
+        for (i, node) in cycle.nodes.iter().enumerate() {
+            let next = cycle.nodes[(i + 1) % cycle.nodes.len()];
+            assert!(links.contains(&link(*node, next)));
+        }
+
+        assert_eq!(
+            topological_sort(&[link(2, 2)], 3),
+            Err(Cycle { nodes: vec![2] })
+        );
+    }
+
+    #[test]
+    fn dirty_tracker_evaluates_changes_downstream() {
+        let mut tracker = DirtyTracker::new(&[link(2, 1), link(1, 0)], 4).unwrap();
+        assert_eq!(tracker.take_evaluation_order(), vec![2, 1, 0, 3]);
+        assert_eq!(tracker.evaluation_order(), Vec::<usize>::new());
+
+        tracker.mark_dirty(1);
+        assert!(tracker.is_dirty(1));
+        assert!(!tracker.is_dirty(0));
+        assert_eq!(tracker.take_evaluation_order(), vec![1, 0]);
+
+        // Adding a link marks its end, and so does removing it
+        tracker
+            .set_links(&[link(2, 1), link(1, 0), link(3, 0)], 4)
+            .unwrap();
+        assert_eq!(tracker.take_evaluation_order(), vec![0]);
+        tracker.set_links(&[link(2, 1), link(1, 0)], 5).unwrap();
+        assert_eq!(tracker.take_evaluation_order(), vec![0, 4]);
+    }
+
+    #[test]
+    fn dirty_tracker_keeps_its_links_on_a_cycle() {
+        let mut tracker = DirtyTracker::new(&[link(0, 1)], 2).unwrap();
+        tracker.take_evaluation_order();
+
+        assert!(tracker.set_links(&[link(0, 1), link(1, 0)], 2).is_err());
+        assert_eq!(tracker.evaluation_order(), Vec::<usize>::new());
+        tracker.mark_dirty(0);
+        assert_eq!(tracker.evaluation_order(), vec![0, 1]);
+    }
+}
@@ -1,20 +1,30 @@
 use iced::{
     advanced::{
-        layout, renderer,
+        graphics::mesh::{Indexed, SolidVertex2D},
+        layout, overlay, renderer, text,
         widget::{self, Operation},
         Clipboard, Layout, Shell, Widget,
     },
-    event, mouse, Background, Border, Color, Element, Event, Length, Point, Rectangle, Size,
-    Vector,
+    event, keyboard, mouse,
+    time::{Duration, Instant},
+    window, Background, Border, Color, Element, Event, Length, Point, Rectangle, Size, Vector,
 };
 use std::collections::VecDeque;
-use std::sync::Mutex;
-
-use crate::connection::LogicalEndpoint;
-use crate::node_element::SocketLayoutState;
+use std::rc::Rc;
+
+use crate::connection::{bundle_targets, line_to_polygon, LogicalEndpoint};
+use crate::graph::Adjacency;
+use crate::lane::{draw_lanes, Lane};
+use crate::mesh_renderer::MeshRenderer;
+use crate::node_element::{snap_to_pixel, SocketLayoutState, SocketPosition};
+use crate::node_palette::{NodePalette, PaletteOverlay, PaletteState};
+use crate::operation::GraphViewport;
+use crate::selection::{Marquee, Selectable, Selection};
 use crate::{
-    matrix::Matrix,
-    styles::graph_container::{Appearance, StyleSheet},
+    bindings::{Bindings, WheelAction},
+    matrix::{Matrix, SyncedMatrix, ViewportChange},
+    size_mode::SizeMode,
+    styles::graph_container::{Appearance, GridStyle, StyleSheet},
     Endpoint, GraphNodeElement, Link, SocketRole,
 };
 
@@ -33,18 +43,244 @@ where
     on_translate: Option<Box<dyn Fn((f32, f32)) -> Message + 'a>>,
     on_scale: Option<Box<dyn Fn(f32, f32, f32) -> Message + 'a>>,
     on_connect: Option<Box<dyn Fn(Link) -> Message + 'a>>,
-    on_disconnect: Option<Box<dyn Fn(LogicalEndpoint, Point) -> Message + 'a>>,
+    on_rejected_connection: Option<Box<dyn Fn(Link, RejectionReason) -> Message + 'a>>,
+    on_disconnect: Option<Box<dyn Fn(LogicalEndpoint, Point, Option<Link>) -> Message + 'a>>,
     on_dangling: Option<Box<dyn Fn(Option<(LogicalEndpoint, Link)>) -> Message + 'a>>,
+    on_socket_layout: Option<Box<dyn Fn(Vec<SocketPosition>) -> Message + 'a>>,
+    on_graph_bounds: Option<Box<dyn Fn(Option<Rectangle>) -> Message + 'a>>,
+    on_node_measured: Option<Box<dyn Fn(usize, Size) -> Message + 'a>>,
+    on_socket_hover: Option<Box<dyn Fn(Option<LogicalEndpoint>) -> Message + 'a>>,
+    on_detach: Option<Box<dyn Fn(Link, Option<Link>) -> Message + 'a>>,
+    on_connection_split: Option<Box<dyn Fn(Link, Point) -> Message + 'a>>,
+    on_canvas_click: Option<Box<dyn Fn(Point) -> Message + 'a>>,
+    on_canvas_double_click: Option<Box<dyn Fn(Point) -> Message + 'a>>,
+    on_connection_double_click: Option<Box<dyn Fn(Link, Point) -> Message + 'a>>,
+    on_breakpoint_toggle: Option<Box<dyn Fn(Link, bool) -> Message + 'a>>,
+    on_selection_change: Option<Box<dyn Fn(Selection) -> Message + 'a>>,
+    selection: Option<&'a Selection>,
+    marquee: Option<(Marquee, keyboard::Modifiers)>,
+    on_canvas_cell_click: Option<(f32, Box<dyn Fn((i32, i32)) -> Message + 'a>)>,
+    on_drop: Option<Box<dyn Fn(Point) -> Message + 'a>>,
+    on_enter_subgraph: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+    node_palette: Option<NodePalette<'a, Message>>,
     dangling_source: Option<LogicalEndpoint>,
+    interaction: Interaction,
+    batch_connections: bool,
+    keyboard_navigation: bool,
+    acyclic: bool,
+    pick_nearest_link: bool,
+    trace_connections: bool,
+    edge_bundling: f32,
+    grid_style: Option<GridStyle>,
+    scale_factor: f32,
+    outline_width: SizeMode,
+    min_socket_scale: f32,
+    hide_small_sockets: bool,
+    bindings: Bindings,
+    lanes: Vec<Lane>,
+    auto_pan: Option<AutoPan>,
+    pan_limit: Option<PanLimit>,
+    kinetic_friction: Option<f32>,
 
     phantom_message: std::marker::PhantomData<Message>,
-    socket_state: Mutex<SocketLayoutState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interaction {
+    // Nodes and connections can be edited, and the viewport can be moved
+    #[default]
+    Full,
+    // Only the viewport can be moved (panned and zoomed); no events reach the nodes
+    PanZoomOnly,
+    // The graph is displayed without any interaction at all
+    None,
+}
+
+// Why a connection was refused, see `GraphContainer::on_rejected_connection`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectionReason {
+    // Both sockets belong to the same node
+    SameNode,
+    // The connection would close a cycle, see `GraphContainer::acyclic`
+    Cycle,
+}
+
+// The state of a graph container in the widget tree. The layout of the sockets is kept apart from
+// the rest, so that both can be borrowed at the same time. Layout and event handling get mutable
+// access to the tree, so neither needs a lock (which could be poisoned by a panic, and is of no
+// use in iced's single-threaded widget model, e.g. on wasm) or any other interior mutability.
+struct TreeState {
+    interaction: GraphContainerState,
+    sockets: SocketLayoutState,
 }
 
 struct GraphContainerState {
-    drag_start_position: Option<Point>,
+    gesture: Gesture,
+    last_socket_positions: Vec<SocketPosition>,
+    last_graph_bounds: Option<Rectangle>,
+    last_node_sizes: Vec<Size>,
+    modifiers: keyboard::Modifiers,
+    hovered_socket: Option<LogicalEndpoint>,
+    // The connection under the cursor, with `trace_connections`
+    traced_link: Option<Link>,
+    // The socket focused with the keyboard, and the source of a connection started with Enter
+    focused_socket: Option<LogicalEndpoint>,
+    keyboard_source: Option<LogicalEndpoint>,
+    // Whether the last press was on the editor. Keys are only handled while the editor has focus
+    // or the cursor is over it, so they can still be used by the rest of the application.
+    has_focus: bool,
+    // The socket the connection being made may not be attached to. Finding cycles walks the whole
+    // graph, so this is only updated when the cursor or the keyboard focus moves, or the links
+    // change.
+    rejected_socket: Option<LogicalEndpoint>,
+    last_click: Option<(Instant, Point)>,
+    // The last press on a node with a subgraph, to detect double clicks
+    last_subgraph_press: Option<(Instant, usize)>,
+    // The last click on a connection, to detect double clicks
+    last_connection_click: Option<(Instant, Link)>,
+    last_auto_pan: Option<Instant>,
+    // While panning, the smoothed speed of the cursor in pixels per second, and when it last
+    // moved; after a fast pan, the viewport keeps moving with `kinetic_panning`
+    pan_velocity: Vector,
+    last_pan_move: Option<Instant>,
+    inertia: Option<Inertia>,
+    palette: Option<PaletteState>,
+}
+
+// The mouse gesture in progress. Every gesture starts with a press of the left button and ends
+// with its release.
+#[derive(Debug, Clone, PartialEq, Default)]
+enum Gesture {
+    #[default]
+    Idle,
+    // Positions are absolute
+    PanningViewport {
+        last_position: Point,
+        press_position: Point,
+    },
+    // A node captured the press and moves itself
+    DraggingNode,
+    // Started on an input socket with several links ending in it, with `pick_nearest_link`. The
+    // link nearest to the cursor is grabbed once it moved far enough to tell them apart.
+    PickingLink {
+        socket: LogicalEndpoint,
+        press_position: Point,
+    },
+    // Started on a connection, with `on_connection_split` or `on_connection_double_click`. The
+    // press position is absolute.
+    BendingConnection {
+        link: Link,
+        press_position: Point,
+    },
+    // Started on a socket. With detaching enabled, the link that was picked up is hidden until
+    // the connection is dropped.
+    DraggingConnection {
+        detached_link: Option<Link>,
+    },
+    // Started on the empty canvas, with `marquee`. The path is absolute and starts at the press
+    // position; a box only keeps its last point in addition.
+    Selecting {
+        marquee: Marquee,
+        path: Vec<Point>,
+        add: bool,
+    },
+}
+
+impl GraphContainerState {
+    fn detached_link(&self) -> Option<&Link> {
+        match &self.gesture {
+            Gesture::DraggingConnection { detached_link } => detached_link.as_ref(),
+            _ => None,
+        }
+    }
+
+    // The socket the detached link starts at, which may be referred to by name
+    fn detached_source(&self, socket_state: &SocketLayoutState) -> Option<LogicalEndpoint> {
+        self.detached_link()
+            .and_then(|link| socket_state.logical_endpoint(link.start()))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PanLimit {
+    Bounds(Rectangle),
+    Content { padding: f32 },
+}
+
+// The viewport moving on by itself after a fast pan, see `GraphContainer::kinetic_panning`
+#[derive(Debug, Clone, Copy)]
+struct Inertia {
+    // In pixels per second
+    velocity: Vector,
+    last_frame: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AutoPan {
+    margin: f32,
+    speed: f32,
 }
 
+impl AutoPan {
+    // The translation of the viewport over `dt` seconds. The closer the cursor is to an edge (or
+    // the further beyond it), the faster the viewport moves, up to `speed` pixels per second.
+    fn translation(&self, bounds: Rectangle, cursor_position: Point, dt: f32) -> Vector {
+        let axis = |position: f32, start: f32, end: f32| {
+            if position < start + self.margin {
+                ((start + self.margin - position) / self.margin).min(1.0)
+            } else if position > end - self.margin {
+                -((position - end + self.margin) / self.margin).min(1.0)
+            } else {
+                0.0
+            }
+        };
+
+        Vector::new(
+            axis(cursor_position.x, bounds.x, bounds.x + bounds.width),
+            axis(cursor_position.y, bounds.y, bounds.y + bounds.height),
+        ) * (self.speed * dt)
+    }
+}
+
+// A press and release on empty canvas counts as a click if the cursor moved less than this
+const CLICK_DISTANCE: f32 = 3.0;
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+// Auto-panning assumes this frame time when it starts, and never pans for longer than
+// `MAX_AUTO_PAN_FRAME` at once, so that the viewport does not jump after a stall
+const AUTO_PAN_FIRST_FRAME: Duration = Duration::from_millis(16);
+const MAX_AUTO_PAN_FRAME: Duration = Duration::from_millis(100);
+// Space between a socket blob and the keyboard focus ring around it, and its thickness
+const FOCUS_RING_GAP: f32 = 3.0;
+const FOCUS_RING_WIDTH: f32 = 2.0;
+// How far the cursor has to move away from an input socket before `pick_nearest_link` decides
+// which of its links was meant
+const LINK_PICK_DISTANCE: f32 = 12.0;
+// How close to a connection a press has to be to grab it, in screen pixels
+const CONNECTION_HIT_DISTANCE: f32 = 5.0;
+// Radius of the dot that shows where a bent connection would be split
+const SPLIT_PREVIEW_RADIUS: f32 = 4.0;
+// Node size changes up to this many graph units are not reported by `on_node_measured`
+const NODE_SIZE_TOLERANCE: f32 = 0.5;
+// How far the viewport pans per line of mouse wheel scrolling
+const WHEEL_LINE_HEIGHT: f32 = 40.0;
+// Opacity of the inside of a box marquee, relative to the selection color
+const MARQUEE_FILL_ALPHA: f32 = 0.15;
+// Minimum distance between two points of a lasso, in screen pixels
+const LASSO_POINT_SPACING: f32 = 4.0;
+// Guidelines closer than this on screen are left out, and so are the dots and crosses of a grid
+// whose intersections are closer than the marker spacing, in pixels
+const MIN_GUIDELINE_SPACING: f32 = 5.0;
+const MIN_GRID_MARKER_SPACING: f32 = 16.0;
+// Kinetic panning starts if the canvas is released while it moves faster than this, and stops
+// once the viewport has slowed down below the stop speed, both in pixels per second
+const KINETIC_START_SPEED: f32 = 300.0;
+const KINETIC_STOP_SPEED: f32 = 20.0;
+// Kinetic panning only starts if the cursor moved this shortly before the release, so that
+// holding still before releasing leaves the viewport where it is
+const KINETIC_RELEASE_WINDOW: Duration = Duration::from_millis(50);
+// How much the latest cursor movement counts towards the panning speed, against earlier ones
+const KINETIC_VELOCITY_WEIGHT: f32 = 0.6;
+
 impl<'a, Message, Theme, Renderer> GraphContainer<'a, Message, Theme, Renderer>
 where
     Theme: StyleSheet,
@@ -55,8 +291,26 @@ where
             on_translate: None,
             on_scale: None,
             on_connect: None,
+            on_rejected_connection: None,
             on_disconnect: None,
             on_dangling: None,
+            on_socket_layout: None,
+            on_graph_bounds: None,
+            on_node_measured: None,
+            on_socket_hover: None,
+            on_detach: None,
+            on_connection_split: None,
+            on_canvas_click: None,
+            on_canvas_double_click: None,
+            on_connection_double_click: None,
+            on_breakpoint_toggle: None,
+            on_selection_change: None,
+            selection: None,
+            marquee: None,
+            on_canvas_cell_click: None,
+            on_drop: None,
+            on_enter_subgraph: None,
+            node_palette: None,
             matrix: Matrix::identity(),
             width: Length::Shrink,
             height: Length::Shrink,
@@ -65,13 +319,25 @@ where
             style: Default::default(),
             content,
             dangling_source: None,
+            interaction: Interaction::default(),
+            batch_connections: false,
+            keyboard_navigation: false,
+            acyclic: false,
+            pick_nearest_link: false,
+            trace_connections: false,
+            edge_bundling: 0.0,
+            grid_style: None,
+            scale_factor: 1.0,
+            outline_width: SizeMode::Screen(FOCUS_RING_WIDTH),
+            min_socket_scale: 0.0,
+            hide_small_sockets: false,
+            bindings: Bindings::new(),
+            lanes: vec![],
+            auto_pan: None,
+            pan_limit: None,
+            kinetic_friction: None,
 
             phantom_message: std::marker::PhantomData,
-            socket_state: Mutex::new(SocketLayoutState {
-                inputs: vec![],
-                outputs: vec![],
-                done: false,
-            }),
         }
     }
 
@@ -99,9 +365,22 @@ where
         self
     }
 
+    // Called when a connection is dropped on (or, with the keyboard, made to) a socket it may not
+    // be attached to
+    pub fn on_rejected_connection<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Link, RejectionReason) -> Message,
+    {
+        self.on_rejected_connection = Some(Box::new(f));
+        self
+    }
+
+    // Called when dragging starts from an input socket, with the socket, the graph-space cursor
+    // position and the link that should be removed: the topmost of the links ending in the
+    // socket (the last one in the content), or `None` if there is none.
     pub fn on_disconnect<F>(mut self, f: F) -> Self
     where
-        F: 'a + Fn(LogicalEndpoint, Point) -> Message,
+        F: 'a + Fn(LogicalEndpoint, Point, Option<Link>) -> Message,
     {
         self.on_disconnect = Some(Box::new(f));
         self
@@ -115,11 +394,179 @@ where
         self
     }
 
+    // Called with the positions, blobs, hit areas and sides of all sockets in graph space
+    // whenever they change, e.g. for a widget that is drawn on top of the sockets.
+    // `SocketPosition::to_screen` converts them into absolute coordinates.
+    pub fn on_socket_layout<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Vec<SocketPosition>) -> Message,
+    {
+        self.on_socket_layout = Some(Box::new(f));
+        self
+    }
+
+    // Called with the union of the bounds of all nodes in graph space (or `None` if the graph is
+    // empty) whenever it changes, e.g. to fit the graph into the view or to clamp panning
+    pub fn on_graph_bounds<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Option<Rectangle>) -> Message,
+    {
+        self.on_graph_bounds = Some(Box::new(f));
+        self
+    }
+
+    // Called with the index and the graph-space size of a node whenever its size after layout
+    // changes, including when it is laid out for the first time. Useful for nodes that shrink to
+    // their content, e.g. to persist their sizes or to run an automatic layout.
+    pub fn on_node_measured<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(usize, Size) -> Message,
+    {
+        self.on_node_measured = Some(Box::new(f));
+        self
+    }
+
+    // Called with the socket under the cursor whenever it changes, and with `None` when the
+    // cursor leaves it, e.g. to show a tooltip or details about the port
+    pub fn on_socket_hover<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Option<LogicalEndpoint>) -> Message,
+    {
+        self.on_socket_hover = Some(Box::new(f));
+        self
+    }
+
+    // Enables Blender-style detaching: dragging from an input socket that already has a link
+    // picks up that link as a dangling connection, instead of emitting `on_disconnect`. The
+    // detached connection is hidden while dragging, but nothing is emitted until the button
+    // is released; then the original link is reported together with the link that should
+    // replace it, which is `None` if it was not dropped onto a compatible socket.
+    pub fn on_detach<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Link, Option<Link>) -> Message,
+    {
+        self.on_detach = Some(Box::new(f));
+        self
+    }
+
+    // Called when a connection is dragged somewhere by its middle and released, or when it is
+    // double-clicked (unless `on_connection_double_click` is set), with the graph-space position
+    // of the release or the click. This is where a reroute node would be inserted, with the link
+    // replaced by one into and one out of it.
+    pub fn on_connection_split<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Link, Point) -> Message,
+    {
+        self.on_connection_split = Some(Box::new(f));
+        self
+    }
+
+    // Called with the link and the graph-space position of a double click on a connection, e.g.
+    // to insert a node there and splice the link through it. Without `on_connection_split`,
+    // dragging a connection pans the viewport as usual.
+    pub fn on_connection_double_click<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Link, Point) -> Message,
+    {
+        self.on_connection_double_click = Some(Box::new(f));
+        self
+    }
+
+    // Called when the breakpoint glyph of a connection (see `Connection::breakpoint`) is
+    // clicked, with the link and whether the breakpoint should now be set
+    pub fn on_breakpoint_toggle<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Link, bool) -> Message,
+    {
+        self.on_breakpoint_toggle = Some(Box::new(f));
+        self
+    }
+
+    // Called with the graph-space position of a click on empty canvas.
+    // Dragging the canvas to pan it does not count as a click.
+    pub fn on_canvas_click<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Point) -> Message,
+    {
+        self.on_canvas_click = Some(Box::new(f));
+        self
+    }
+
+    pub fn on_canvas_double_click<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Point) -> Message,
+    {
+        self.on_canvas_double_click = Some(Box::new(f));
+        self
+    }
+
+    // Like `on_canvas_click`, but reports the cell of a grid with the given graph-space spacing
+    // that was clicked, see `grid_cell_of`. Useful for editors whose nodes are placed on a grid.
+    pub fn on_canvas_cell_click<F>(mut self, spacing: f32, f: F) -> Self
+    where
+        F: 'a + Fn((i32, i32)) -> Message,
+    {
+        self.on_canvas_cell_click = Some((spacing, Box::new(f)));
+        self
+    }
+
+    // Accepts a drop from outside the container, e.g. a node type dragged from a palette.
+    // The application keeps track of the payload being dragged (if any) and passes it here;
+    // when the mouse button is released over the container while a payload is set, `f` is
+    // called with the graph-space drop position and the payload.
+    pub fn on_drop<P, F>(mut self, dragged_payload: Option<P>, f: F) -> Self
+    where
+        P: Clone + 'a,
+        F: 'a + Fn(Point, P) -> Message,
+    {
+        self.on_drop = dragged_payload.map(|payload| {
+            Box::new(move |position| f(position, payload.clone()))
+                as Box<dyn Fn(Point) -> Message + 'a>
+        });
+        self
+    }
+
+    // Called with the index of a node that has a subgraph (see `Node::subgraph`) when it is
+    // double-clicked. `SubgraphPath` helps keeping track of the entered subgraphs.
+    pub fn on_enter_subgraph<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(usize) -> Message,
+    {
+        self.on_enter_subgraph = Some(Box::new(f));
+        self
+    }
+
+    // Lets new nodes be picked from a searchable list, see `NodePalette`
+    pub fn node_palette(mut self, node_palette: NodePalette<'a, Message>) -> Self {
+        self.node_palette = Some(node_palette);
+        self
+    }
+
     pub fn matrix(mut self, m: Matrix) -> Self {
         self.matrix = m;
         self
     }
 
+    // Shows the viewport of a matrix shared with other containers, and reports panning and
+    // zooming as changes for `SyncedMatrix::update`. Takes the place of `matrix`, `on_translate`
+    // and `on_scale`.
+    pub fn synced_matrix<F>(mut self, synced: &SyncedMatrix, f: F) -> Self
+    where
+        F: 'a + Fn(ViewportChange) -> Message,
+    {
+        let on_scale = Rc::new(f);
+        let on_translate = Rc::clone(&on_scale);
+
+        self.matrix = synced.matrix();
+        self.on_translate = Some(Box::new(move |(x, y)| {
+            on_translate(ViewportChange::Translate(x, y))
+        }));
+        self.on_scale = Some(Box::new(move |x, y, amount| {
+            on_scale(ViewportChange::Scale(x, y, amount))
+        }));
+        self
+    }
+
     pub fn width(mut self, width: Length) -> Self {
         self.width = width;
         self
@@ -145,11 +592,565 @@ where
         self
     }
 
+    // Draws all connections that support it as one mesh, instead of one mesh per connection.
+    // This greatly reduces draw overhead for graphs with many connections.
+    pub fn batch_connections(mut self, batch_connections: bool) -> Self {
+        self.batch_connections = batch_connections;
+        self
+    }
+
+    // Lets the sockets be focused and connected with the keyboard: Tab (or Shift+Tab) and the up
+    // and down arrow keys cycle through the sockets, the left and right arrow keys move to the
+    // nearest socket in that direction, Enter starts a connection from the focused socket or
+    // completes it, and Escape cancels it. Keys are only handled while the cursor is over the
+    // editor, or after a click on it until something else is clicked, and key presses captured by
+    // the nodes (e.g. by a focused text input) are left alone. The connection is reported
+    // through `on_dangling` and `on_connect`, like one made with the mouse.
+    pub fn keyboard_navigation(mut self, keyboard_navigation: bool) -> Self {
+        self.keyboard_navigation = keyboard_navigation;
+        self
+    }
+
+    // The scale factor of the window (see `Application::scale_factor`), with which the
+    // guidelines and nodes are aligned to physical pixels. Defaults to 1.
+    pub fn scale_factor(mut self, scale_factor: f64) -> Self {
+        self.scale_factor = scale_factor as f32;
+        self
+    }
+
+    // The width of the rings drawn around focused and rejected sockets. With
+    // `SizeMode::Graph`, the gap between the ring and the socket scales along with it.
+    pub fn outline_width(mut self, outline_width: impl Into<SizeMode>) -> Self {
+        self.outline_width = outline_width.into();
+        self
+    }
+
+    // Below this scale, sockets cannot be hovered or grabbed, so that clicking a zoomed out graph
+    // does not start connections by accident. Nodes can still be dragged.
+    pub fn min_socket_scale(mut self, min_socket_scale: f32) -> Self {
+        self.min_socket_scale = min_socket_scale;
+        self
+    }
+
+    // Also leaves out the blobs of the sockets below the `min_socket_scale`
+    pub fn hide_small_sockets(mut self, hide_small_sockets: bool) -> Self {
+        self.hide_small_sockets = hide_small_sockets;
+        self
+    }
+
+    // What the mouse wheel does depending on the held modifiers, e.g. `Bindings::editor()` to pan
+    // with the wheel and zoom with Ctrl + wheel, or `Bindings::zoom_with` to only zoom while a
+    // modifier is held. By default, the wheel always zooms. Panning is reported through
+    // `on_translate`.
+    pub fn bindings(mut self, bindings: Bindings) -> Self {
+        self.bindings = bindings;
+        self
+    }
+
+    // Bands drawn below the grid, see `Lane`
+    pub fn lanes(mut self, lanes: Vec<Lane>) -> Self {
+        self.lanes = lanes;
+        self
+    }
+
+    // Overrides the `grid_kind` of the style sheet
+    pub fn grid_style(mut self, grid_style: GridStyle) -> Self {
+        self.grid_style = Some(grid_style);
+        self
+    }
+
+    // Scrolls the viewport while a node or a dangling connection is dragged within `margin`
+    // pixels of the container's edges, by up to `speed` pixels per second. The translation is
+    // reported through `on_translate`, so that needs to be set as well.
+    pub fn auto_pan(mut self, margin: f32, speed: f32) -> Self {
+        self.auto_pan = Some(AutoPan {
+            margin: margin.max(1.0),
+            speed,
+        });
+        self
+    }
+
+    // Keeps the center of the viewport within the given graph-space rectangle while panning
+    pub fn pan_bounds(mut self, bounds: Rectangle) -> Self {
+        self.pan_limit = Some(PanLimit::Bounds(bounds));
+        self
+    }
+
+    // Keeps the center of the viewport within the bounds of all nodes, extended by `padding` (in
+    // graph space) on every side, so that the graph cannot get lost off-screen
+    pub fn clamp_to_content(mut self, padding: f32) -> Self {
+        self.pan_limit = Some(PanLimit::Content { padding });
+        self
+    }
+
+    // Keeps the viewport moving after the canvas is released during a fast pan, slowing down by
+    // `friction` (the fraction of the speed lost per second, between 0 and 1) until it stops.
+    // Pressing anywhere or scrolling stops it right away. The translation is reported through
+    // `on_translate`. A friction that is not finite leaves kinetic panning off.
+    pub fn kinetic_panning(mut self, friction: f32) -> Self {
+        self.kinetic_friction = friction.is_finite().then(|| friction.clamp(0.01, 1.0));
+        self
+    }
+
+    pub fn interaction(mut self, interaction: Interaction) -> Self {
+        self.interaction = interaction;
+        self
+    }
+
     pub fn dangling_source(mut self, dangling_source: Option<LogicalEndpoint>) -> Self {
         self.dangling_source = dangling_source;
         self
     }
 
+    // Refuses connections that would close a cycle. The links of the connections in the content
+    // are followed from their output to their input sockets to find one.
+    pub fn acyclic(mut self, acyclic: bool) -> Self {
+        self.acyclic = acyclic;
+        self
+    }
+
+    // When several links end in the same input socket, dragging away from it grabs the link
+    // whose curve is nearest to the cursor, rather than the topmost one. This applies to both
+    // `on_disconnect` and `on_detach`.
+    pub fn pick_nearest_link(mut self, pick_nearest_link: bool) -> Self {
+        self.pick_nearest_link = pick_nearest_link;
+        self
+    }
+
+    // The selected nodes and links, which are highlighted with the `selection_color` of the
+    // style
+    pub fn selection(mut self, selection: &'a Selection) -> Self {
+        self.selection = Some(selection);
+        self
+    }
+
+    // Lets the container manage the selection: pressing on a node or a connection selects only
+    // it, or toggles it while Shift is held, and clicking the empty canvas clears the selection.
+    // Called with the new selection whenever it changes; store it and pass it back with
+    // `selection`.
+    pub fn on_selection_change<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Selection) -> Message,
+    {
+        self.on_selection_change = Some(Box::new(f));
+        self
+    }
+
+    // Dragging the empty canvas while all of the modifiers are held draws a marquee instead of
+    // panning, and selects the nodes whose centers end up inside of it. With Shift held as well,
+    // they are added to the selection. Needs `on_selection_change`.
+    pub fn marquee(mut self, marquee: Marquee, modifiers: keyboard::Modifiers) -> Self {
+        self.marquee = Some((marquee, modifiers));
+        self
+    }
+
+    // While the cursor is over a connection, outlines the sockets and nodes at both of its ends,
+    // to help following wires through dense graphs. The outlines use the `traced_color` of the
+    // style.
+    pub fn trace_connections(mut self, trace_connections: bool) -> Self {
+        self.trace_connections = trace_connections;
+        self
+    }
+
+    // Pulls connections that share a socket towards each other, so that the links fanning out
+    // of an output (or into an input) leave it as one bundle and only split up further along.
+    // The strength ranges from 0 (off, the default) to 1 (the connections of a socket meet in a
+    // single point). Only applies to connections with `Routing::Direct`.
+    pub fn edge_bundling(mut self, strength: f32) -> Self {
+        self.edge_bundling = strength.clamp(0.0, 1.0);
+        self
+    }
+
+    // The links ending in the socket, topmost last, along with the distance of their curves to
+    // an absolute position
+    fn links_ending_in(
+        &self,
+        trees: &[widget::Tree],
+        socket_state: &SocketLayoutState,
+        layout: Layout<'_>,
+        socket: LogicalEndpoint,
+        position: Point,
+    ) -> Vec<(&Link, f32)> {
+        self.content
+            .iter()
+            .zip(trees)
+            .zip(layout.children())
+            .filter_map(|((element, tree), layout)| {
+                let element = element.as_scalable_widget();
+                let link = element.link()?;
+                (socket_state.logical_endpoint(link.end()) == Some(socket)).then(|| {
+                    let distance = element
+                        .distance_to(tree, position - layout.bounds().position())
+                        .unwrap_or(f32::INFINITY);
+                    (link, distance)
+                })
+            })
+            .collect()
+    }
+
+    // The link of the topmost connection near an absolute position
+    fn connection_at(
+        &self,
+        trees: &[widget::Tree],
+        layout: Layout<'_>,
+        position: Point,
+    ) -> Option<&Link> {
+        self.content
+            .iter()
+            .zip(trees)
+            .zip(layout.children())
+            .filter_map(|((element, tree), layout)| {
+                let element = element.as_scalable_widget();
+                let distance = element.distance_to(tree, position - layout.bounds().position())?;
+                Some((element.link()?, distance))
+            })
+            .filter(|(_, distance)| *distance <= CONNECTION_HIT_DISTANCE)
+            // The nearest one, and the last of equally near ones
+            .max_by(|(_, a), (_, b)| b.total_cmp(a))
+            .map(|(link, _)| link)
+    }
+
+    // The topmost node or connection at the given position, if any. Nodes are drawn above the
+    // connections, so they take precedence.
+    fn selectable_at(
+        &self,
+        trees: &[widget::Tree],
+        socket_state: &SocketLayoutState,
+        layout: Layout<'_>,
+        position: Point,
+    ) -> Option<Selectable> {
+        let offset = self.matrix.get_translation();
+        let relative =
+            position - Vector::new(layout.bounds().x + offset.0, layout.bounds().y + offset.1);
+        match socket_state
+            .nodes
+            .iter()
+            .rposition(|node| node.contains(relative))
+        {
+            Some(node) => Some(Selectable::Node(node)),
+            None => self
+                .connection_at(trees, layout, position)
+                .cloned()
+                .map(Selectable::Link),
+        }
+    }
+
+    // The link of the breakpoint glyph at the given position, if any, and whether its breakpoint
+    // is set
+    fn breakpoint_at(
+        &self,
+        trees: &[widget::Tree],
+        layout: Layout<'_>,
+        position: Point,
+    ) -> Option<(&Link, bool)> {
+        self.content
+            .iter()
+            .zip(trees)
+            .zip(layout.children())
+            .filter_map(|((element, tree), layout)| {
+                let element = element.as_scalable_widget();
+                let set = element.breakpoint_at(tree, position - layout.bounds().position())?;
+                Some((element.link()?, set))
+            })
+            // Connections are drawn in order, so the last one is on top
+            .last()
+    }
+
+    // Starts dragging from an input socket by grabbing a link that ends in it. Returns the link
+    // if it was detached.
+    fn grab_link(
+        &self,
+        shell: &mut Shell<'_, Message>,
+        socket_state: &SocketLayoutState,
+        socket: LogicalEndpoint,
+        link: Option<Link>,
+        graph_position: Point,
+    ) -> Option<Link> {
+        // If detaching is enabled, pick up the link
+        let detached_link = self.on_detach.as_ref().and(link.clone());
+
+        // The primary intent of dragging from an input socket is
+        // removing the connection to the previous node.
+        // The crate user may still desire to implement a Blender-like
+        // behaviour where it drags out a new connection
+        if let Some(link) = &detached_link {
+            if let Some(source) = socket_state.logical_endpoint(link.start()) {
+                self.try_emit_dangling(shell, graph_position, source);
+            }
+        } else if let Some(f) = &self.on_disconnect {
+            shell.publish(f(socket, graph_position, link));
+        }
+
+        detached_link
+    }
+
+    // Why the link may not be made, if it may not. The detached link is being moved and does not
+    // count.
+    fn rejection(&self, link: &Link, detached: Option<&Link>) -> Option<RejectionReason> {
+        let (start, end) = link.unwrap_sockets();
+        if start.node_index == end.node_index {
+            return Some(RejectionReason::SameNode);
+        }
+
+        if self.acyclic {
+            let adjacency = Adjacency::new(
+                self.links()
+                    .into_iter()
+                    .filter(|other| Some(*other) != detached),
+            );
+            if adjacency.reaches(end.node_index, start.node_index) {
+                return Some(RejectionReason::Cycle);
+            }
+        }
+
+        None
+    }
+
+    // The socket under the cursor, or focused with the keyboard, that the connection being made
+    // may not be attached to
+    fn rejected_socket(
+        &self,
+        state: &GraphContainerState,
+        socket_state: &SocketLayoutState,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<LogicalEndpoint> {
+        let target = match state.keyboard_source {
+            Some(_) => state.focused_socket?,
+            None => self.socket_at(socket_state, bounds, cursor)?,
+        };
+        self.rejects(state, socket_state, target).then_some(target)
+    }
+
+    // Whether the connection being made may not be attached to `target`
+    fn rejects(
+        &self,
+        state: &GraphContainerState,
+        socket_state: &SocketLayoutState,
+        target: LogicalEndpoint,
+    ) -> bool {
+        let source = match state.keyboard_source {
+            Some(source) => Some(source),
+            None => self
+                .dangling_source
+                .or_else(|| state.detached_source(socket_state)),
+        };
+
+        source
+            .and_then(|source| link_between(source, target))
+            .is_some_and(|link| self.rejection(&link, state.detached_link()).is_some())
+    }
+
+    fn links(&self) -> Vec<&Link> {
+        self.content
+            .iter()
+            .filter_map(|element| element.as_scalable_widget().link())
+            .collect()
+    }
+
+    // The socket under the cursor, if any
+    fn socket_at(
+        &self,
+        socket_state: &SocketLayoutState,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<LogicalEndpoint> {
+        if !self.sockets_interactive() {
+            return None;
+        }
+
+        let cursor_position = cursor.position_in(bounds)?;
+        let offset = self.matrix.get_translation();
+        socket_state.socket_at(Point::new(
+            cursor_position.x - offset.0,
+            cursor_position.y - offset.1,
+        ))
+    }
+
+    fn sockets_interactive(&self) -> bool {
+        self.matrix.get_scale() >= self.min_socket_scale
+    }
+
+    fn handle_key(
+        &self,
+        shell: &mut Shell<'_, Message>,
+        state: &mut GraphContainerState,
+        socket_state: &SocketLayoutState,
+        key: &keyboard::Key,
+        modifiers: keyboard::Modifiers,
+    ) -> event::Status {
+        let order = socket_state.navigation_order();
+        let focused = state
+            .focused_socket
+            .and_then(|focused| order.iter().position(|endpoint| *endpoint == focused));
+        let scale = self.matrix.get_scale();
+        let center = |endpoint: &LogicalEndpoint| {
+            socket_state
+                .blob(endpoint)
+                .map(|blob| Point::new(blob.center_x() / scale, blob.center_y() / scale))
+        };
+
+        let cycle = |step: isize| {
+            (!order.is_empty()).then(|| {
+                let next = match focused {
+                    Some(index) => (index as isize + step).rem_euclid(order.len() as isize),
+                    None if step > 0 => 0,
+                    None => order.len() as isize - 1,
+                };
+                order[next as usize]
+            })
+        };
+
+        // The nearest socket to the left or right, preferring ones on the same height
+        let nearest = |direction: f32| {
+            let from = center(&order[focused?])?;
+            order
+                .iter()
+                .filter_map(|endpoint| Some((*endpoint, center(endpoint)?)))
+                .filter(|(_, to)| (to.x - from.x) * direction > 0.0)
+                .min_by(|(_, a), (_, b)| {
+                    let cost = |to: &Point| (to.x - from.x).abs() + (to.y - from.y).abs() * 2.0;
+                    cost(a).total_cmp(&cost(b))
+                })
+                .map(|(endpoint, _)| endpoint)
+        };
+
+        let new_focus = match key {
+            keyboard::Key::Named(keyboard::key::Named::Tab) if modifiers.shift() => cycle(-1),
+            keyboard::Key::Named(keyboard::key::Named::Tab | keyboard::key::Named::ArrowDown) => {
+                cycle(1)
+            }
+            keyboard::Key::Named(keyboard::key::Named::ArrowUp) => cycle(-1),
+            keyboard::Key::Named(keyboard::key::Named::ArrowLeft) => nearest(-1.0),
+            keyboard::Key::Named(keyboard::key::Named::ArrowRight) => nearest(1.0),
+            keyboard::Key::Named(keyboard::key::Named::Enter) => {
+                let Some(focused) = focused.map(|index| order[index]) else {
+                    return event::Status::Ignored;
+                };
+
+                match state.keyboard_source {
+                    None => {
+                        state.keyboard_source = Some(focused);
+                        if let Some(position) = center(&focused) {
+                            self.try_emit_dangling(shell, position, focused);
+                        }
+                    }
+                    Some(source) => {
+                        let Some(link) = link_between(source, focused) else {
+                            return event::Status::Captured;
+                        };
+
+                        if let Some(reason) = self.rejection(&link, None) {
+                            if let Some(f) = &self.on_rejected_connection {
+                                shell.publish(f(link, reason));
+                            }
+                            return event::Status::Captured;
+                        }
+
+                        state.keyboard_source = None;
+                        if let Some(f) = &self.on_dangling {
+                            shell.publish(f(None));
+                        }
+                        if let Some(f) = &self.on_connect {
+                            shell.publish(f(link));
+                        }
+                    }
+                }
+                return event::Status::Captured;
+            }
+            keyboard::Key::Named(keyboard::key::Named::Escape) => {
+                if state.keyboard_source.take().is_some() {
+                    if let Some(f) = &self.on_dangling {
+                        shell.publish(f(None));
+                    }
+                } else if state.focused_socket.take().is_none() {
+                    return event::Status::Ignored;
+                }
+                return event::Status::Captured;
+            }
+            _ => return event::Status::Ignored,
+        };
+
+        let Some(new_focus) = new_focus else {
+            return event::Status::Ignored;
+        };
+        state.focused_socket = Some(new_focus);
+
+        // The connection being made follows the focus
+        if let (Some(source), Some(position)) = (state.keyboard_source, center(&new_focus)) {
+            self.try_emit_dangling(shell, position, source);
+        }
+
+        event::Status::Captured
+    }
+
+    fn emit_click(
+        &self,
+        shell: &mut Shell<'_, Message>,
+        state: &mut GraphContainerState,
+        cursor_position: Point,
+        graph_position: Point,
+    ) {
+        let now = Instant::now();
+        let is_double_click = state.last_click.is_some_and(|(time, position)| {
+            now.duration_since(time) < DOUBLE_CLICK_INTERVAL
+                && position.distance(graph_position) * self.matrix.get_scale() < CLICK_DISTANCE
+        });
+
+        if is_double_click {
+            // Don't let a third click count as another double click
+            state.last_click = None;
+            if let Some(f) = &self.on_canvas_double_click {
+                shell.publish(f(graph_position));
+            }
+            if self.node_palette.is_some() && self.interaction == Interaction::Full {
+                state.palette = Some(PaletteState::new(cursor_position, graph_position));
+            }
+        } else {
+            state.last_click = Some((now, graph_position));
+            if let Some(f) = &self.on_canvas_click {
+                shell.publish(f(graph_position));
+            }
+            if let Some((spacing, f)) = &self.on_canvas_cell_click {
+                shell.publish(f(grid_cell_of(graph_position, *spacing)));
+            }
+        }
+    }
+
+    // Restricts a translation of the viewport such that it does not move the viewport center
+    // further away from the pan limit. Translations towards the limit are always allowed, so the
+    // viewport does not jump if it is already outside of it, e.g. because the graph changed.
+    fn clamp_translation(
+        &self,
+        bounds: Rectangle,
+        graph_bounds: Option<Rectangle>,
+        translation: Vector,
+    ) -> Vector {
+        let limit = match (self.pan_limit, graph_bounds) {
+            (Some(PanLimit::Bounds(limit)), _) => limit,
+            (Some(PanLimit::Content { padding }), Some(graph_bounds)) => {
+                graph_bounds.expand(padding)
+            }
+            _ => return translation,
+        };
+
+        let top_left = graph_to_screen(&self.matrix, bounds, limit.position());
+        let bottom_right = graph_to_screen(
+            &self.matrix,
+            bounds,
+            Point::new(limit.x + limit.width, limit.y + limit.height),
+        );
+        let center = bounds.center();
+
+        let axis = |translation: f32, center: f32, start: f32, end: f32| {
+            translation.clamp((center - end).min(0.0), (center - start).max(0.0))
+        };
+        Vector::new(
+            axis(translation.x, center.x, top_left.x, bottom_right.x),
+            axis(translation.y, center.y, top_left.y, bottom_right.y),
+        )
+    }
+
     fn try_emit_dangling(
         &self,
         shell: &mut Shell<'_, Message>,
@@ -178,11 +1179,47 @@ where
     GraphContainer::new(content)
 }
 
+// Converts an absolute (window) position into graph coordinates, given the matrix and the
+// bounds of the graph container it was passed to. Returns `None` if the matrix cannot be
+// inverted (e.g. after scaling by zero), as every graph position is then shown at the same point.
+pub fn screen_to_graph(matrix: &Matrix, bounds: Rectangle, point: Point) -> Option<Point> {
+    let relative = Point::new(point.x - bounds.x, point.y - bounds.y);
+    Some(matrix.invert()?.transform_point(relative))
+}
+
+// The inverse of `screen_to_graph`.
+pub fn graph_to_screen(matrix: &Matrix, bounds: Rectangle, point: Point) -> Point {
+    matrix.transform_point(point) + Vector::new(bounds.x, bounds.y)
+}
+
+// Moves a graph-space point to the nearest intersection of a grid with the given spacing, e.g.
+// to place nodes on the guidelines of the graph container
+pub fn snap_point(point: Point, spacing: f32) -> Point {
+    Point::new(
+        (point.x / spacing).round() * spacing,
+        (point.y / spacing).round() * spacing,
+    )
+}
+
+// The column and row of the cell of a grid with the given spacing that contains a graph-space
+// point. Cell (0, 0) has its top left corner at the origin of the graph.
+pub fn grid_cell_of(point: Point, spacing: f32) -> (i32, i32) {
+    (
+        (point.x / spacing).floor() as i32,
+        (point.y / spacing).floor() as i32,
+    )
+}
+
+// The graph-space top left corner of a cell of a grid with the given spacing
+pub fn grid_cell_origin(cell: (i32, i32), spacing: f32) -> Point {
+    Point::new(cell.0 as f32 * spacing, cell.1 as f32 * spacing)
+}
+
 impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
     for GraphContainer<'a, Message, Theme, Renderer>
 where
     Theme: StyleSheet,
-    Renderer: renderer::Renderer,
+    Renderer: renderer::Renderer + text::Renderer + MeshRenderer,
 {
     fn children(&self) -> Vec<widget::Tree> {
         let mut children = Vec::new();
@@ -195,6 +1232,15 @@ where
     }
 
     fn diff(&self, tree: &mut widget::Tree) {
+        // The links may have changed, so the socket that was rejected may be allowed now
+        let TreeState {
+            interaction: state,
+            sockets: socket_state,
+        } = tree.state.downcast_mut::<TreeState>();
+        state.rejected_socket = state
+            .rejected_socket
+            .filter(|target| self.rejects(state, socket_state, *target));
+
         tree.diff_children(self.content.as_slice())
     }
 
@@ -206,12 +1252,52 @@ where
     }
 
     fn tag(&self) -> widget::tree::Tag {
-        widget::tree::Tag::of::<widget::tree::State>()
+        widget::tree::Tag::of::<TreeState>()
     }
 
     fn state(&self) -> widget::tree::State {
-        widget::tree::State::new(GraphContainerState {
-            drag_start_position: None,
+        let interaction = GraphContainerState {
+            gesture: Gesture::Idle,
+            last_socket_positions: Vec::new(),
+            last_graph_bounds: None,
+            last_node_sizes: Vec::new(),
+            modifiers: keyboard::Modifiers::default(),
+            hovered_socket: None,
+            traced_link: None,
+            focused_socket: None,
+            keyboard_source: None,
+            has_focus: false,
+            rejected_socket: None,
+            last_click: None,
+            last_subgraph_press: None,
+            last_connection_click: None,
+            last_auto_pan: None,
+            pan_velocity: Vector::ZERO,
+            last_pan_move: None,
+            inertia: None,
+            palette: None,
+        };
+        widget::tree::State::new(TreeState {
+            interaction,
+            sockets: SocketLayoutState {
+                nodes: vec![],
+                locked: vec![],
+                inputs: vec![],
+                outputs: vec![],
+                input_hit_areas: vec![],
+                output_hit_areas: vec![],
+                input_sides: vec![],
+                output_sides: vec![],
+                socket_names: vec![],
+                scale_factor: 1.0,
+                blobs_hidden: false,
+                socket_grid: Default::default(),
+                bundle_strength: 0.0,
+                bundle_targets: Default::default(),
+                channels: Default::default(),
+                obstacles_hash: 0,
+                done: false,
+            },
         })
     }
 
@@ -233,25 +1319,43 @@ where
         let scale = self.matrix.get_scale();
         let offset = self.matrix.get_translation();
 
-        let mut socket_layout_state = self
-            .socket_state
-            .lock()
-            .expect("should be able to lock socket state mutex in layout()");
+        let socket_layout_state = &mut tree.state.downcast_mut::<TreeState>().sockets;
         socket_layout_state.clear();
+        socket_layout_state.scale_factor = self.scale_factor;
+        socket_layout_state.blobs_hidden = self.hide_small_sockets && !self.sockets_interactive();
+        socket_layout_state.bundle_strength = self.edge_bundling;
 
         for (node_index, node) in self.content.iter().enumerate() {
+            // Bundles are formed once all nodes, which come first, have been laid out
+            if self.edge_bundling > 0.0
+                && !socket_layout_state.done
+                && node.as_scalable_widget().link().is_some()
+            {
+                let links = self
+                    .content
+                    .iter()
+                    .filter_map(|element| element.as_scalable_widget().link());
+                socket_layout_state.bundle_targets =
+                    bundle_targets(links, scale, socket_layout_state);
+            }
+
             let mut node = node.as_scalable_widget().layout(
                 &mut tree.children[node_index],
                 _renderer,
                 &limits,
                 scale,
-                &mut socket_layout_state,
+                socket_layout_state,
             );
-            node = node.translate(Vector::new(offset.0, offset.1));
+            node = node.translate(Vector::new(
+                snap_to_pixel(offset.0, self.scale_factor),
+                snap_to_pixel(offset.1, self.scale_factor),
+            ));
 
             content.push(node);
         }
 
+        socket_layout_state.build_socket_grid();
+
         let size = limits.resolve(self.width, self.height, Size::ZERO);
 
         layout::Node::with_children(size, content)
@@ -264,6 +1368,13 @@ where
         renderer: &Renderer,
         operation: &mut dyn Operation<Message>,
     ) {
+        operation.custom(
+            &mut GraphViewport {
+                bounds: layout.bounds(),
+            },
+            None,
+        );
+
         operation.container(None, layout.bounds(), &mut |operation| {
             self.content
                 .iter()
@@ -289,140 +1400,659 @@ where
         viewport: &Rectangle<f32>,
     ) -> event::Status {
         let mut status = event::Status::Ignored;
-        let state = tree.state.downcast_mut::<GraphContainerState>();
-        let socket_state = self
-            .socket_state
-            .lock()
-            .expect("should be able to lock socket state mutex in on_event()");
-
-        // Socket-related processing
-        if let Event::Mouse(mouse_event) = event {
-            if let Some(cursor_position) = cursor.position_in(layout.bounds()) {
-                let offset = self.matrix.get_translation();
-                let translated_cursor_position =
-                    Point::new(cursor_position.x - offset.0, cursor_position.y - offset.1);
-
-                let scale = self.matrix.get_scale();
-                let translated_descaled_cursor_position = Point::new(
-                    translated_cursor_position.x / scale,
-                    translated_cursor_position.y / scale,
-                );
+        let TreeState {
+            interaction: state,
+            sockets: socket_state,
+        } = tree.state.downcast_mut::<TreeState>();
+
+        if let Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) = event {
+            state.modifiers = modifiers;
+        }
+        if let Event::Mouse(mouse::Event::ButtonPressed(_)) = event {
+            state.has_focus = cursor.is_over(layout.bounds());
+        }
+        if let Event::Mouse(mouse::Event::CursorMoved { .. }) = event {
+            state.rejected_socket =
+                self.rejected_socket(state, socket_state, layout.bounds(), cursor);
+        }
+
+        // Layout cannot publish messages, so report changed socket positions and graph bounds on
+        // the next event
+        if let Some(f) = &self.on_socket_layout {
+            let positions = socket_state.positions(self.matrix.get_scale());
+            if positions != state.last_socket_positions {
+                state.last_socket_positions.clone_from(&positions);
+                shell.publish(f(positions));
+            }
+        }
+        if let Some(f) = &self.on_graph_bounds {
+            let bounds = socket_state.graph_bounds(self.matrix.get_scale());
+            if bounds != state.last_graph_bounds {
+                state.last_graph_bounds = bounds;
+                shell.publish(f(bounds));
+            }
+        }
+        if let Some(f) = &self.on_node_measured {
+            let sizes = socket_state.node_sizes(self.matrix.get_scale());
+            state.last_node_sizes.truncate(sizes.len());
+            for (index, size) in sizes.into_iter().enumerate() {
+                match state.last_node_sizes.get_mut(index) {
+                    // Rounding in the scaled layout makes sizes jitter slightly while zooming
+                    Some(last)
+                        if (last.width - size.width).abs() <= NODE_SIZE_TOLERANCE
+                            && (last.height - size.height).abs() <= NODE_SIZE_TOLERANCE => {}
+                    Some(last) => {
+                        *last = size;
+                        shell.publish(f(index, size));
+                    }
+                    None => {
+                        state.last_node_sizes.push(size);
+                        shell.publish(f(index, size));
+                    }
+                }
+            }
+        }
+
+        if let (
+            Some(f),
+            Event::Mouse(mouse::Event::CursorMoved { .. } | mouse::Event::CursorLeft),
+        ) = (&self.on_socket_hover, &event)
+        {
+            let hovered_socket = match self.interaction {
+                Interaction::Full => self.socket_at(socket_state, layout.bounds(), cursor),
+                _ => None,
+            };
+            if hovered_socket != state.hovered_socket {
+                state.hovered_socket = hovered_socket;
+                shell.publish(f(hovered_socket));
+            }
+        }
+
+        // Animations advance on every redraw, and only ask for another frame while one of them is
+        // visible, so that an idle graph is neither laid out nor drawn again
+        if let Event::Window(_, window::Event::RedrawRequested(now)) = event {
+            let bounds = layout.bounds();
+            let mut animating = false;
+            for ((child, child_tree), child_layout) in self
+                .content
+                .iter()
+                .zip(&mut tree.children)
+                .zip(layout.children())
+            {
+                let visible = child_layout.bounds().intersects(&bounds);
+                animating |= child.as_scalable_widget().animate(child_tree, now) && visible;
+            }
+            if animating {
+                shell.request_redraw(window::RedrawRequest::NextFrame);
+            }
+        }
+
+        if let (true, Event::Mouse(mouse::Event::CursorMoved { .. } | mouse::Event::CursorLeft)) =
+            (self.trace_connections, &event)
+        {
+            // Nodes are drawn above the connections, so the ones below them are not traced
+            state.traced_link = cursor
+                .position_over(layout.bounds())
+                .filter(|_| state.gesture == Gesture::Idle)
+                .filter(|position| {
+                    let offset = self.matrix.get_translation();
+                    let relative = *position
+                        - Vector::new(layout.bounds().x + offset.0, layout.bounds().y + offset.1);
+                    !socket_state
+                        .nodes
+                        .iter()
+                        .any(|node| node.contains(relative))
+                })
+                .and_then(|position| self.connection_at(&tree.children, layout, position))
+                .cloned();
+        }
+
+        if self.interaction == Interaction::None {
+            return status;
+        }
+
+        if let Some(inertia) = &mut state.inertia {
+            let mut stopped = false;
+            match event {
+                Event::Window(_, window::Event::RedrawRequested(now)) => {
+                    let dt = inertia
+                        .last_frame
+                        .map_or(AUTO_PAN_FIRST_FRAME, |last| now.duration_since(last))
+                        .min(MAX_AUTO_PAN_FRAME)
+                        .as_secs_f32();
+                    let translation = self.clamp_translation(
+                        layout.bounds(),
+                        socket_state.graph_bounds(self.matrix.get_scale()),
+                        inertia.velocity * dt,
+                    );
+                    if let Some(f) = &self.on_translate {
+                        shell.publish(f((translation.x, translation.y)));
+                    }
+
+                    let friction = self.kinetic_friction.unwrap_or(1.0);
+                    let velocity = slowed_down(inertia.velocity, friction, dt);
+                    inertia.velocity = velocity.unwrap_or(Vector::ZERO);
+                    inertia.last_frame = Some(now);
+                    // Also stops at the pan limits
+                    stopped = translation == Vector::ZERO || velocity.is_none();
+                    if !stopped {
+                        shell.request_redraw(window::RedrawRequest::NextFrame);
+                    }
+                }
+                Event::Mouse(
+                    mouse::Event::ButtonPressed(_) | mouse::Event::WheelScrolled { .. },
+                ) => stopped = true,
+                _ => {}
+            }
+            if stopped {
+                state.inertia = None;
+            }
+        }
+
+        if let (Some(f), Interaction::Full) = (&self.on_drop, self.interaction) {
+            if let Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) = event {
+                if let Some(graph_position) = cursor
+                    .position_over(layout.bounds())
+                    .and_then(|position| screen_to_graph(&self.matrix, layout.bounds(), position))
+                {
+                    shell.publish(f(graph_position));
+                    return event::Status::Captured;
+                }
+            }
+        }
+
+        if let (Some(auto_pan), Some(f), Interaction::Full) =
+            (self.auto_pan, &self.on_translate, self.interaction)
+        {
+            if let Event::Window(_, window::Event::RedrawRequested(now)) = event {
+                let mut panned = false;
+
+                if let Some(cursor_position) = cursor.position() {
+                    let dt = state
+                        .last_auto_pan
+                        .map_or(AUTO_PAN_FIRST_FRAME, |last| now.duration_since(last))
+                        .min(MAX_AUTO_PAN_FRAME);
+                    let translation = self.clamp_translation(
+                        layout.bounds(),
+                        socket_state.graph_bounds(self.matrix.get_scale()),
+                        auto_pan.translation(layout.bounds(), cursor_position, dt.as_secs_f32()),
+                    );
+
+                    if translation != Vector::ZERO {
+                        // Dragged nodes move in the opposite direction, to stay under the cursor
+                        let node_messages: Vec<Message> = self
+                            .content
+                            .iter()
+                            .zip(&mut tree.children)
+                            .flat_map(|(child, child_tree)| {
+                                child
+                                    .as_scalable_widget()
+                                    .drag_messages(child_tree, translation * -1.0)
+                            })
+                            .collect();
+
+                        if self.dangling_source.is_some() || !node_messages.is_empty() {
+                            shell.publish(f((translation.x, translation.y)));
+                            for message in node_messages {
+                                shell.publish(message);
+                            }
+
+                            let matrix = self.matrix.translate(translation.x, translation.y);
+                            if let (Some(dangling_source), Some(graph_position)) = (
+                                self.dangling_source,
+                                screen_to_graph(&matrix, layout.bounds(), cursor_position),
+                            ) {
+                                self.try_emit_dangling(shell, graph_position, dangling_source);
+                            }
+
+                            shell.request_redraw(window::RedrawRequest::NextFrame);
+                            panned = true;
+                        }
+                    }
+                }
+
+                state.last_auto_pan = panned.then_some(now);
+            }
+        }
+
+        // The selection changes on press, before any gesture starts, so that dragging a node that
+        // was not selected moves only that node. Presses on sockets start connections instead.
+        if let (
+            Some(f),
+            Interaction::Full,
+            Gesture::Idle,
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+            Some(cursor_position),
+        ) = (
+            &self.on_selection_change,
+            self.interaction,
+            &state.gesture,
+            &event,
+            cursor.position_over(layout.bounds()),
+        ) {
+            let current = self.selection.cloned().unwrap_or_default();
+            let on_socket = self
+                .socket_at(socket_state, layout.bounds(), cursor)
+                .is_some();
+            if let (false, Some(item)) = (
+                on_socket,
+                self.selectable_at(&tree.children, socket_state, layout, cursor_position),
+            ) {
+                let selection = current.clicked(item, state.modifiers.shift());
+                if selection != current {
+                    shell.publish(f(selection));
+                }
+            }
+        }
+
+        // Events are handled according to the gesture in progress. This way, a node drag that
+        // passes over a socket cannot turn into a connection, and releasing the button anywhere
+        // ends the gesture it belongs to, even outside of the container.
+        let bounds = layout.bounds();
+        let scale = self.matrix.get_scale();
+
+        if let Gesture::PanningViewport {
+            last_position,
+            press_position,
+        } = state.gesture
+        {
+            match event {
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    state.gesture = Gesture::Idle;
+                    let velocity = std::mem::replace(&mut state.pan_velocity, Vector::ZERO);
+                    if let (Some(_), Some(last_move)) =
+                        (self.kinetic_friction, state.last_pan_move.take())
+                    {
+                        if last_move.elapsed() < KINETIC_RELEASE_WINDOW
+                            && velocity.x.hypot(velocity.y) >= KINETIC_START_SPEED
+                        {
+                            state.inertia = Some(Inertia {
+                                velocity,
+                                last_frame: None,
+                            });
+                            shell.request_redraw(window::RedrawRequest::NextFrame);
+                        }
+                    }
+                    if let Some(cursor_position) = cursor.position() {
+                        if press_position.distance(cursor_position) < CLICK_DISTANCE {
+                            if let Some(graph_position) =
+                                screen_to_graph(&self.matrix, bounds, cursor_position)
+                            {
+                                self.emit_click(shell, state, cursor_position, graph_position);
+                            }
+
+                            // A click on a connection (without `on_connection_split`) or on a
+                            // node that does not capture clicks pans as well; it selected them
+                            // on press, so it must not clear the selection
+                            let on_item = self
+                                .selectable_at(
+                                    &tree.children,
+                                    socket_state,
+                                    layout,
+                                    cursor_position,
+                                )
+                                .is_some();
+                            if let (Some(f), Some(selection), false, false) = (
+                                &self.on_selection_change,
+                                self.selection,
+                                on_item,
+                                state.modifiers.shift(),
+                            ) {
+                                if !selection.is_empty() {
+                                    shell.publish(f(Selection::new()));
+                                }
+                            }
+                        }
+                    }
+                    return event::Status::Captured;
+                }
+                Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                    if let Some(cursor_position) = cursor.position() {
+                        let delta = self.clamp_translation(
+                            bounds,
+                            socket_state.graph_bounds(scale),
+                            cursor_position - last_position,
+                        );
+                        state.gesture = Gesture::PanningViewport {
+                            last_position: cursor_position,
+                            press_position,
+                        };
+                        if self.kinetic_friction.is_some() {
+                            let now = Instant::now();
+                            if let Some(last_move) = state.last_pan_move {
+                                let dt = now.duration_since(last_move).as_secs_f32();
+                                state.pan_velocity =
+                                    smoothed_velocity(state.pan_velocity, delta, dt);
+                            }
+                            state.last_pan_move = Some(now);
+                        }
+                        if let Some(f) = &self.on_translate {
+                            shell.publish(f((delta.x, delta.y)));
+                        }
+                    }
+                    return event::Status::Captured;
+                }
+                _ => {}
+            }
+        }
 
-                // Find the socket we're hovering over
-                let mut hovered_socket: Option<LogicalEndpoint> = None;
-                for (role, node_sockets) in [
-                    (SocketRole::In, &socket_state.inputs),
-                    (SocketRole::Out, &socket_state.outputs),
-                ] {
-                    for (node_index, sockets) in node_sockets.iter().enumerate() {
-                        for (socket_index, blob_rect) in sockets.iter().enumerate() {
-                            if blob_rect.contains(translated_cursor_position) {
-                                hovered_socket = Some(LogicalEndpoint {
-                                    node_index,
-                                    role,
-                                    socket_index,
-                                });
+        if let Gesture::Selecting { marquee, path, add } = &mut state.gesture {
+            match (&event, cursor.position()) {
+                (Event::Mouse(mouse::Event::CursorMoved { .. }), Some(cursor_position)) => {
+                    match marquee {
+                        Marquee::Box => {
+                            path.truncate(1);
+                            path.push(cursor_position);
+                        }
+                        Marquee::Lasso => {
+                            if path.last().is_none_or(|last| {
+                                last.distance(cursor_position) >= LASSO_POINT_SPACING
+                            }) {
+                                path.push(cursor_position);
                             }
                         }
                     }
+                    shell.request_redraw(window::RedrawRequest::NextFrame);
+                    return event::Status::Captured;
                 }
+                (Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)), _) => {
+                    let (marquee, path, add) = (*marquee, std::mem::take(path), *add);
+                    state.gesture = Gesture::Idle;
+
+                    let current = self.selection.cloned().unwrap_or_default();
+                    let mut selection = if add {
+                        current.clone()
+                    } else {
+                        Selection::new()
+                    };
+                    let dragged = path
+                        .iter()
+                        .any(|point| point.distance(path[0]) >= CLICK_DISTANCE);
+                    if dragged {
+                        let offset = self.matrix.get_translation();
+                        let origin = Vector::new(bounds.x + offset.0, bounds.y + offset.1);
+                        for (index, node) in socket_state.nodes.iter().enumerate() {
+                            if marquee.contains(&path, node.center() + origin) {
+                                selection.select_node(index);
+                            }
+                        }
+                    } else {
+                        // Just a click on the empty canvas
+                        if let Some(graph_position) = screen_to_graph(&self.matrix, bounds, path[0])
+                        {
+                            self.emit_click(shell, state, path[0], graph_position);
+                        }
+                    }
 
-                match mouse_event {
-                    mouse::Event::ButtonPressed(mouse::Button::Left) => {
-                        if let Some(hovered_socket) = hovered_socket {
-                            match hovered_socket.role {
-                                SocketRole::In => {
-                                    // The primary intent of dragging from an input socket is
-                                    // removing the connection to the previous node.
-                                    // The crate user may still desire to implement a Blender-like
-                                    // behaviour where it drags out a new connection
-                                    if let Some(f) = &self.on_disconnect {
-                                        shell.publish(f(
-                                            hovered_socket,
-                                            translated_descaled_cursor_position,
-                                        ));
-                                    }
-                                }
-                                SocketRole::Out => {
-                                    // Create a new dangling connection from the output socket
-                                    self.try_emit_dangling(
-                                        shell,
-                                        translated_descaled_cursor_position,
-                                        hovered_socket,
-                                    );
-                                }
+                    if let (Some(f), true) = (&self.on_selection_change, selection != current) {
+                        shell.publish(f(selection));
+                    }
+                    return event::Status::Captured;
+                }
+                _ => {}
+            }
+        }
+
+        if let Gesture::PickingLink {
+            socket,
+            press_position,
+        } = state.gesture
+        {
+            match (&event, cursor.position()) {
+                (Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)), _) => {
+                    state.gesture = Gesture::Idle;
+                    return event::Status::Captured;
+                }
+                (Event::Mouse(mouse::Event::CursorMoved { .. }), Some(cursor_position))
+                    if press_position.distance(cursor_position) >= LINK_PICK_DISTANCE =>
+                {
+                    let nearest_link = self
+                        .links_ending_in(
+                            &tree.children,
+                            socket_state,
+                            layout,
+                            socket,
+                            cursor_position,
+                        )
+                        .into_iter()
+                        // On a tie, the topmost link wins
+                        .rev()
+                        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                        .map(|(link, _)| link.clone());
+                    let Some(graph_position) =
+                        screen_to_graph(&self.matrix, bounds, cursor_position)
+                    else {
+                        return event::Status::Captured;
+                    };
+                    let detached_link =
+                        self.grab_link(shell, socket_state, socket, nearest_link, graph_position);
+                    state.gesture = Gesture::DraggingConnection { detached_link };
+                    return event::Status::Captured;
+                }
+                (Event::Mouse(mouse::Event::CursorMoved { .. }), _) => {
+                    return event::Status::Captured;
+                }
+                _ => {}
+            }
+        }
+
+        if let Gesture::BendingConnection {
+            link,
+            press_position,
+        } = &state.gesture
+        {
+            match event {
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    let link = link.clone();
+                    let press_position = *press_position;
+                    state.gesture = Gesture::Idle;
+
+                    if let Some((cursor_position, graph_position)) =
+                        cursor.position().and_then(|position| {
+                            Some((position, screen_to_graph(&self.matrix, bounds, position)?))
+                        })
+                    {
+                        let now = Instant::now();
+                        if press_position.distance(cursor_position) >= CLICK_DISTANCE {
+                            if let Some(f) = &self.on_connection_split {
+                                shell.publish(f(link, graph_position));
                             }
-                            status = event::Status::Captured;
+                        } else if state.last_connection_click.as_ref().is_some_and(
+                            |(time, last)| {
+                                *last == link && now.duration_since(*time) < DOUBLE_CLICK_INTERVAL
+                            },
+                        ) {
+                            state.last_connection_click = None;
+                            if let Some(f) = self
+                                .on_connection_double_click
+                                .as_ref()
+                                .or(self.on_connection_split.as_ref())
+                            {
+                                shell.publish(f(link, graph_position));
+                            }
+                        } else {
+                            state.last_connection_click = Some((now, link));
                         }
                     }
-                    mouse::Event::CursorMoved { .. } => {
-                        // Update the existing dangling connection, if it exists
-                        if let Some(dangling_source) = self.dangling_source {
-                            self.try_emit_dangling(
-                                shell,
-                                translated_descaled_cursor_position,
-                                dangling_source,
-                            );
-                            status = event::Status::Captured;
+                    return event::Status::Captured;
+                }
+                Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                    // Without splitting, the press only waits for a double click, and dragging
+                    // pans the viewport instead
+                    if self.on_connection_split.is_none() {
+                        if press_position.distance(position) >= CLICK_DISTANCE {
+                            state.gesture = Gesture::PanningViewport {
+                                last_position: *press_position,
+                                press_position: *press_position,
+                            };
                         }
+                        return event::Status::Captured;
                     }
-                    mouse::Event::ButtonReleased(mouse::Button::Left) => {
-                        if let Some(dangling_source) = self.dangling_source {
-                            // No matter what happens, the dangling connection needs to be removed
-                            if let Some(f) = &self.on_dangling {
-                                shell.publish(f(None));
-                            }
 
-                            // If we're hovering over a socket while releasing the button,
-                            // there's a chance we're about to make a connection
-                            if let Some(hovered_socket) = hovered_socket {
-                                // Don't allow connecting input to input or output to output
-                                // sockets, and don't allow connecting a node to itself.
-                                // This does not definitively detect cycles, but it's a start
-                                if dangling_source.role != hovered_socket.role
-                                    && dangling_source.node_index != hovered_socket.node_index
-                                {
-                                    if let Some(f) = &self.on_connect {
-                                        let link = Link::from_unordered(
-                                            Endpoint::Socket(dangling_source),
-                                            Endpoint::Socket(hovered_socket),
-                                        );
-                                        shell.publish(f(link));
-                                    }
+                    // Moves the preview of the split
+                    shell.request_redraw(window::RedrawRequest::NextFrame);
+                    return event::Status::Captured;
+                }
+                _ => {}
+            }
+        }
+
+        // A connection is dragged either after a press on a socket, or whenever the application
+        // sets a dangling source
+        let connection_source = self
+            .dangling_source
+            .or_else(|| state.detached_source(socket_state));
+        let dragging_connection = matches!(state.gesture, Gesture::DraggingConnection { .. })
+            || connection_source.is_some();
+
+        if dragging_connection && self.interaction == Interaction::Full {
+            match event {
+                Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                    if let (Some(source), Some(graph_position)) = (
+                        connection_source,
+                        cursor
+                            .position_over(bounds)
+                            .and_then(|position| screen_to_graph(&self.matrix, bounds, position)),
+                    ) {
+                        self.try_emit_dangling(shell, graph_position, source);
+                        return event::Status::Captured;
+                    }
+                }
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    let detached_link = match std::mem::take(&mut state.gesture) {
+                        Gesture::DraggingConnection { detached_link } => detached_link,
+                        _ => None,
+                    };
+                    state.rejected_socket = None;
+
+                    if let Some(source) = connection_source {
+                        // No matter what happens, the dangling connection needs to be removed
+                        if let Some(f) = &self.on_dangling {
+                            shell.publish(f(None));
+                        }
+
+                        // If we're hovering over a socket while releasing the button,
+                        // there's a chance we're about to make a connection
+                        let mut new_link = self
+                            .socket_at(socket_state, bounds, cursor)
+                            .and_then(|hovered_socket| link_between(source, hovered_socket));
+                        if let Some(link) = &new_link {
+                            if let Some(reason) = self.rejection(link, detached_link.as_ref()) {
+                                if let Some(f) = &self.on_rejected_connection {
+                                    shell.publish(f(link.clone(), reason));
                                 }
+                                new_link = None;
+                            }
+                        }
+
+                        if let Some(detached_link) = detached_link {
+                            if let Some(f) = &self.on_detach {
+                                shell.publish(f(detached_link, new_link));
+                            }
+                        } else if let Some(new_link) = new_link {
+                            if let Some(f) = &self.on_connect {
+                                shell.publish(f(new_link));
                             }
-                            status = event::Status::Captured;
                         }
                     }
-                    _ => {}
+                    return event::Status::Captured;
                 }
+                _ => {}
             }
         }
 
-        if status == event::Status::Captured {
-            return status;
-        }
+        // Pressing on a socket starts dragging a connection
+        if let (
+            Gesture::Idle,
+            Interaction::Full,
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+        ) = (&state.gesture, self.interaction, &event)
+        {
+            if let (Some(hovered_socket), Some((cursor_position, graph_position))) = (
+                self.socket_at(socket_state, bounds, cursor),
+                cursor.position().and_then(|position| {
+                    Some((position, screen_to_graph(&self.matrix, bounds, position)?))
+                }),
+            ) {
+                let mut detached_link = None;
+
+                match hovered_socket.role {
+                    SocketRole::In => {
+                        let links = self.links_ending_in(
+                            &tree.children,
+                            socket_state,
+                            layout,
+                            hovered_socket,
+                            cursor_position,
+                        );
+                        if self.pick_nearest_link && links.len() > 1 {
+                            // Wait for the cursor to move away before deciding on a link
+                            state.gesture = Gesture::PickingLink {
+                                socket: hovered_socket,
+                                press_position: cursor_position,
+                            };
+                            return event::Status::Captured;
+                        }
 
-        if let Some(start) = state.drag_start_position {
-            // Moving the viewport
-            if let Some(cursor_position) = cursor.position_in(layout.bounds()) {
-                match event {
-                    Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
-                        state.drag_start_position = None;
+                        let topmost_link = links.last().map(|(link, _)| (*link).clone());
+                        detached_link = self.grab_link(
+                            shell,
+                            socket_state,
+                            hovered_socket,
+                            topmost_link,
+                            graph_position,
+                        );
                     }
-                    Event::Mouse(mouse::Event::CursorMoved { .. }) => {
-                        let delta = cursor_position - start;
-                        state.drag_start_position = Some(cursor_position);
-                        if let Some(f) = &self.on_translate {
-                            let message = f((delta.x, delta.y));
-                            shell.publish(message);
-                        }
-                        status = event::Status::Captured;
+                    SocketRole::Out => {
+                        // Create a new dangling connection from the output socket
+                        self.try_emit_dangling(shell, graph_position, hovered_socket);
                     }
-                    _ => {}
                 }
+
+                state.gesture = Gesture::DraggingConnection { detached_link };
+                return event::Status::Captured;
             }
-        } else {
+        }
+
+        if let (
+            Some(f),
+            Interaction::Full,
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+            Some(cursor_position),
+        ) = (
+            &self.on_enter_subgraph,
+            self.interaction,
+            &event,
+            cursor.position_over(bounds),
+        ) {
+            // The topmost node under the cursor. Nodes come before the connections.
+            let pressed = self
+                .content
+                .iter()
+                .zip(layout.children())
+                .take(socket_state.nodes.len())
+                .enumerate()
+                .filter(|(_, (_, layout))| layout.bounds().contains(cursor_position))
+                .last()
+                .filter(|(_, (child, _))| child.as_scalable_widget().has_subgraph())
+                .map(|(index, _)| index);
+
+            if let Some(index) = pressed {
+                let now = Instant::now();
+                if state.last_subgraph_press.is_some_and(|(time, last)| {
+                    last == index && now.duration_since(time) < DOUBLE_CLICK_INTERVAL
+                }) {
+                    state.last_subgraph_press = None;
+                    shell.publish(f(index));
+                    return event::Status::Captured;
+                }
+                state.last_subgraph_press = Some((now, index));
+            } else {
+                state.last_subgraph_press = None;
+            }
+        }
+
+        if self.interaction == Interaction::Full {
             // Process events for our children (i.e. nodes), until one of the children
             // captures the event.
             // We process these in reverse storage order, as they are drawn in forward order,
@@ -436,9 +2066,9 @@ where
                 .zip(&mut tree.children)
                 .zip(layout.children())
                 .collect();
-            for ((child, state), layout) in event_queue.into_iter().rev() {
+            for ((child, child_tree), layout) in event_queue.into_iter().rev() {
                 let child_status = child.as_widget_mut().on_event(
-                    state,
+                    child_tree,
                     event.clone(),
                     layout,
                     cursor,
@@ -452,28 +2082,183 @@ where
                     break;
                 }
             }
+
+            match (&state.gesture, &event) {
+                (Gesture::Idle, Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)))
+                    if self
+                        .content
+                        .iter()
+                        .zip(&tree.children)
+                        .any(|(child, child_tree)| {
+                            child.as_scalable_widget().is_dragged(child_tree)
+                        }) =>
+                {
+                    state.gesture = Gesture::DraggingNode;
+                }
+                (
+                    Gesture::DraggingNode,
+                    Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)),
+                ) => {
+                    state.gesture = Gesture::Idle;
+                }
+                _ => {}
+            }
+        }
+
+        if let (
+            true,
+            true,
+            Interaction::Full,
+            event::Status::Ignored,
+            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }),
+        ) = (
+            self.keyboard_navigation,
+            state.has_focus || cursor.is_over(layout.bounds()),
+            self.interaction,
+            status,
+            &event,
+        ) {
+            status = self.handle_key(shell, state, socket_state, key, *modifiers);
+            state.rejected_socket =
+                self.rejected_socket(state, socket_state, layout.bounds(), cursor);
+        }
+
+        // Without keyboard navigation, Tab opens the node palette at the cursor
+        if let (
+            Some(_),
+            false,
+            Interaction::Full,
+            event::Status::Ignored,
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(keyboard::key::Named::Tab),
+                ..
+            }),
+            Some(cursor_position),
+        ) = (
+            &self.node_palette,
+            self.keyboard_navigation,
+            self.interaction,
+            status,
+            &event,
+            cursor.position_over(bounds),
+        ) {
+            if let Some(graph_position) = screen_to_graph(&self.matrix, bounds, cursor_position) {
+                state.palette = Some(PaletteState::new(cursor_position, graph_position));
+                status = event::Status::Captured;
+            }
+        }
+
+        // Breakpoint glyphs lie on top of their connections, so they take precedence over them
+        if let (
+            Some(f),
+            Interaction::Full,
+            event::Status::Ignored,
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+            Some(cursor_position),
+        ) = (
+            &self.on_breakpoint_toggle,
+            self.interaction,
+            status,
+            &event,
+            cursor.position_over(bounds),
+        ) {
+            if let (Gesture::Idle, Some((link, set))) = (
+                &state.gesture,
+                self.breakpoint_at(&tree.children, layout, cursor_position),
+            ) {
+                shell.publish(f(link.clone(), !set));
+                status = event::Status::Captured;
+            }
         }
 
         if status == event::Status::Ignored {
-            if let Some(cursor_position) = cursor.position_in(layout.bounds()) {
+            if let Some(cursor_position) = cursor.position_over(bounds) {
                 // Initiating viewport movement/scaling
                 match event {
-                    Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
-                        state.drag_start_position = Some(cursor_position);
+                    Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                        if state.gesture == Gesture::Idle =>
+                    {
+                        let connections_clickable = self.on_connection_split.is_some()
+                            || self.on_connection_double_click.is_some();
+                        let bent_link = match (connections_clickable, self.interaction) {
+                            (true, Interaction::Full) => self
+                                .connection_at(&tree.children, layout, cursor_position)
+                                .cloned(),
+                            _ => None,
+                        };
+
+                        let marquee = match (self.marquee, &self.on_selection_change) {
+                            (Some((marquee, modifiers)), Some(_))
+                                if self.interaction == Interaction::Full
+                                    && state.modifiers.contains(modifiers)
+                                    && self
+                                        .selectable_at(
+                                            &tree.children,
+                                            socket_state,
+                                            layout,
+                                            cursor_position,
+                                        )
+                                        .is_none() =>
+                            {
+                                Some(marquee)
+                            }
+                            _ => None,
+                        };
+
+                        state.gesture = match (bent_link, marquee) {
+                            (Some(link), _) => Gesture::BendingConnection {
+                                link,
+                                press_position: cursor_position,
+                            },
+                            (None, Some(marquee)) => Gesture::Selecting {
+                                marquee,
+                                path: vec![cursor_position],
+                                add: state.modifiers.shift(),
+                            },
+                            (None, None) => Gesture::PanningViewport {
+                                last_position: cursor_position,
+                                press_position: cursor_position,
+                            },
+                        };
                         status = event::Status::Captured;
                     }
                     Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
-                        if let Some(f) = &self.on_scale {
-                            match delta {
-                                mouse::ScrollDelta::Lines { y, .. } => {
-                                    let message = f(cursor_position.x, cursor_position.y, y);
-                                    shell.publish(message);
-                                }
-                                mouse::ScrollDelta::Pixels { y, .. } => {
-                                    let message = f(cursor_position.x, cursor_position.y, y);
-                                    shell.publish(message);
+                        // Zooming is reported in the units of the event, panning in pixels
+                        let (x, y, amount) = match delta {
+                            mouse::ScrollDelta::Lines { x, y } => {
+                                (x * WHEEL_LINE_HEIGHT, y * WHEEL_LINE_HEIGHT, y)
+                            }
+                            mouse::ScrollDelta::Pixels { x, y } => (x, y, y),
+                        };
+                        let translation = match self.bindings.wheel_action(state.modifiers) {
+                            WheelAction::Zoom => {
+                                if let (Some(f), true) = (&self.on_scale, amount != 0.0) {
+                                    let relative_position =
+                                        cursor_position - Vector::new(bounds.x, bounds.y);
+                                    shell.publish(f(
+                                        relative_position.x,
+                                        relative_position.y,
+                                        amount,
+                                    ));
+                                    status = event::Status::Captured;
                                 }
+                                None
                             }
+                            WheelAction::Pan => Some(Vector::new(x, y)),
+                            WheelAction::PanHorizontal => Some(Vector::new(x + y, 0.0)),
+                            WheelAction::Ignore => None,
+                        };
+
+                        if let (Some(f), Some(translation)) = (
+                            &self.on_translate,
+                            translation.filter(|translation| *translation != Vector::ZERO),
+                        ) {
+                            let translation = self.clamp_translation(
+                                bounds,
+                                socket_state.graph_bounds(self.matrix.get_scale()),
+                                translation,
+                            );
+                            shell.publish(f((translation.x, translation.y)));
                             status = event::Status::Captured;
                         }
                     }
@@ -493,6 +2278,41 @@ where
         viewport: &Rectangle,
         renderer: &Renderer,
     ) -> mouse::Interaction {
+        let TreeState {
+            interaction: state,
+            sockets: socket_state,
+        } = tree.state.downcast_ref::<TreeState>();
+
+        match state.gesture {
+            Gesture::PanningViewport { .. } => return mouse::Interaction::Grab,
+            Gesture::Selecting { .. } => return mouse::Interaction::Crosshair,
+            _ => {}
+        }
+
+        if self.interaction != Interaction::Full {
+            return mouse::Interaction::default();
+        }
+
+        if cursor.is_over(layout.bounds()) {
+            let hovered_socket = self.socket_at(socket_state, layout.bounds(), cursor);
+
+            let dangling_source = self
+                .dangling_source
+                .or_else(|| state.detached_source(socket_state));
+
+            match (dangling_source, hovered_socket) {
+                (Some(source), Some(hovered_socket))
+                    if link_between(source, hovered_socket).is_none()
+                        || state.rejected_socket == Some(hovered_socket) =>
+                {
+                    return mouse::Interaction::NotAllowed;
+                }
+                (Some(_), _) => return mouse::Interaction::Crosshair,
+                (None, Some(_)) => return mouse::Interaction::Pointer,
+                (None, None) => {}
+            }
+        }
+
         self.content
             .iter()
             .zip(&tree.children)
@@ -506,6 +2326,43 @@ where
             .unwrap_or_default()
     }
 
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut widget::Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        let widget::Tree {
+            state, children, ..
+        } = tree;
+
+        let mut children = self
+            .content
+            .iter_mut()
+            .zip(children)
+            .zip(layout.children())
+            .filter_map(|((child, state), layout)| {
+                child
+                    .as_widget_mut()
+                    .overlay(state, layout, renderer, translation)
+            })
+            .collect::<Vec<_>>();
+
+        let palette_state = &mut state.downcast_mut::<TreeState>().interaction.palette;
+        if let (Some(palette), Some(_)) = (&self.node_palette, &palette_state) {
+            // Last, so that it is drawn on top of the overlays of the nodes
+            children.push(overlay::Element::new(Box::new(PaletteOverlay {
+                palette,
+                state: palette_state,
+                style: &self.style,
+                translation,
+            })));
+        }
+
+        (!children.is_empty()).then(|| overlay::Group::with_children(children).overlay())
+    }
+
     fn draw(
         &self,
         state: &widget::Tree,
@@ -517,11 +2374,25 @@ where
         viewport: &Rectangle,
     ) {
         let style = theme.appearance(&self.style);
+        let TreeState {
+            interaction: container_state,
+            sockets: socket_state,
+        } = state.state.downcast_ref::<TreeState>();
 
         let bounds = layout.bounds();
 
+        // The grid is a mesh, and meshes are drawn after quads within a layer. So to keep it
+        // behind the nodes, it needs to be drawn in its own layer.
         renderer.with_layer(bounds, |renderer| {
             draw_background(renderer, bounds, style);
+            draw_lanes(
+                renderer,
+                &self.lanes,
+                &self.matrix,
+                bounds,
+                style.mid_guidelines_color,
+                renderer_style.text_color,
+            );
 
             let offset = self.matrix.get_translation();
             let scale = self.matrix.get_scale();
@@ -533,41 +2404,138 @@ where
                 .max(style.major_guidelines_spacing.unwrap())
                 .max(style.mid_guidelines_spacing.unwrap());
 
-            draw_guidelines(
-                renderer,
-                bounds,
-                offset,
-                normalized_scale,
-                style.minor_guidelines_spacing.unwrap(),
-                biggest_spacing,
-                style.minor_guidelines_color.unwrap(),
-            );
+            let mut grid = Indexed {
+                vertices: Vec::new(),
+                indices: Vec::new(),
+            };
+            let grid_style = self
+                .grid_style
+                .or(style.grid_kind)
+                .unwrap_or(GridStyle::Lines);
+
+            for (spacing, color) in [
+                (
+                    style.minor_guidelines_spacing.unwrap(),
+                    style.minor_guidelines_color.unwrap(),
+                ),
+                (
+                    style.mid_guidelines_spacing.unwrap(),
+                    style.mid_guidelines_color.unwrap(),
+                ),
+                (
+                    style.major_guidelines_spacing.unwrap(),
+                    style.major_guidelines_color.unwrap(),
+                ),
+            ] {
+                tessellate_guidelines(
+                    &mut grid,
+                    bounds,
+                    offset,
+                    normalized_scale,
+                    spacing,
+                    biggest_spacing,
+                    color,
+                    grid_style,
+                    self.scale_factor,
+                );
+            }
 
-            draw_guidelines(
-                renderer,
-                bounds,
-                offset,
-                normalized_scale,
-                style.mid_guidelines_spacing.unwrap(),
-                biggest_spacing,
-                style.mid_guidelines_color.unwrap(),
-            );
+            // Highlight the axes going through the graph origin
+            let origin = Point::new(bounds.x + offset.0, bounds.y + offset.1);
+            if let Some(color) = style.y_axis_color {
+                if origin.x > bounds.x && origin.x < bounds.x + bounds.width {
+                    push_rectangle(
+                        &mut grid,
+                        Rectangle::new(
+                            Point::new(origin.x, bounds.y),
+                            Size::new(1.0, bounds.height),
+                        ),
+                        color,
+                    );
+                }
+            }
+            if let Some(color) = style.x_axis_color {
+                if origin.y > bounds.y && origin.y < bounds.y + bounds.height {
+                    push_rectangle(
+                        &mut grid,
+                        Rectangle::new(
+                            Point::new(bounds.x, origin.y),
+                            Size::new(bounds.width, 1.0),
+                        ),
+                        color,
+                    );
+                }
+            }
 
-            draw_guidelines(
-                renderer,
-                bounds,
-                offset,
-                normalized_scale,
-                style.major_guidelines_spacing.unwrap(),
-                biggest_spacing,
-                style.major_guidelines_color.unwrap(),
-            );
+            if !grid.indices.is_empty() {
+                renderer.draw_buffers(grid);
+            }
+        });
+
+        let offset = self.matrix.get_translation();
+        let multiplier = self.outline_width.multiplier(self.matrix.get_scale());
+        let selection_color = style.selection_color.unwrap_or(renderer_style.text_color);
+
+        renderer.with_layer(bounds, |renderer| {
+            let mut batch = Indexed {
+                vertices: Vec::new(),
+                indices: Vec::new(),
+            };
+
+            // The halos of selected connections lie below all connections
+            if let Some(selection) = self
+                .selection
+                .filter(|selection| !selection.links().is_empty())
+            {
+                let mut halos = Indexed {
+                    vertices: Vec::new(),
+                    indices: Vec::new(),
+                };
+                for ((child, child_tree), child_layout) in self
+                    .content
+                    .iter()
+                    .zip(&state.children)
+                    .zip(layout.children())
+                {
+                    let child = child.as_scalable_widget();
+                    if !child
+                        .link()
+                        .is_some_and(|link| selection.contains_link(link))
+                    {
+                        continue;
+                    }
+                    let child_bounds = child_layout.bounds();
+                    if let Some(mesh) = child.halo_mesh(
+                        child_tree,
+                        self.outline_width.value() * multiplier,
+                        selection_color,
+                        Vector::new(child_bounds.x, child_bounds.y),
+                    ) {
+                        let first_index = halos.vertices.len() as u32;
+                        halos.vertices.extend(mesh.vertices);
+                        halos
+                            .indices
+                            .extend(mesh.indices.into_iter().map(|index| index + first_index));
+                    }
+                }
+                if !halos.indices.is_empty() {
+                    renderer.draw_buffers(halos);
+                }
+            }
 
             let mut children_layout = layout.children();
             for i in 0..self.content.len() {
                 let layout = children_layout.next().unwrap();
                 let node = self.content[i].as_widget();
 
+                // The detached link is replaced by the dangling connection until it is dropped
+                if container_state.detached_link().is_some()
+                    && self.content[i].as_scalable_widget().link()
+                        == container_state.detached_link()
+                {
+                    continue;
+                }
+
                 let child_bounds = layout.bounds();
                 let intersect = child_bounds.intersection(&bounds);
 
@@ -581,6 +2549,44 @@ where
                     continue;
                 }
 
+                let visible_area = Rectangle::new(
+                    Point::new(bounds.x - child_bounds.x, bounds.y - child_bounds.y),
+                    bounds.size(),
+                );
+                if !self.content[i]
+                    .as_scalable_widget()
+                    .is_visible(&state.children[i], visible_area)
+                {
+                    continue;
+                }
+
+                if self.batch_connections {
+                    let translation = Vector::new(child_bounds.x, child_bounds.y);
+                    if let Some(mesh) = self.content[i].as_scalable_widget().batched_mesh(
+                        &state.children[i],
+                        theme,
+                        translation,
+                    ) {
+                        let first_index = batch.vertices.len() as u32;
+                        batch.vertices.extend(mesh.vertices);
+                        batch
+                            .indices
+                            .extend(mesh.indices.into_iter().map(|index| index + first_index));
+                        continue;
+                    }
+                }
+
+                // Flush the batch first, so elements drawn on their own keep their draw order
+                if !batch.indices.is_empty() {
+                    renderer.draw_buffers(std::mem::replace(
+                        &mut batch,
+                        Indexed {
+                            vertices: Vec::new(),
+                            indices: Vec::new(),
+                        },
+                    ));
+                }
+
                 node.draw(
                     &state.children[i],
                     renderer,
@@ -590,8 +2596,175 @@ where
                     cursor,
                     viewport,
                 );
+                if let Some(mesh) = self.content[i].as_scalable_widget().overlay_mesh(
+                    &state.children[i],
+                    theme,
+                    layout,
+                ) {
+                    renderer.draw_buffers(mesh);
+                }
+            }
+
+            if !batch.indices.is_empty() {
+                renderer.draw_buffers(batch);
             }
         });
+
+        // Outlines an area given in (scaled, untranslated) layout units, leaving a small gap
+        let mut draw_outline = |area: Rectangle, rounded: bool, color: Color| {
+            let outline = (area + Vector::new(bounds.x + offset.0, bounds.y + offset.1))
+                .expand(FOCUS_RING_GAP * multiplier);
+            let radius = if rounded {
+                outline.width.max(outline.height) / 2.0
+            } else {
+                FOCUS_RING_GAP * multiplier
+            };
+
+            // The outline needs to be drawn above the connections, which are meshes
+            renderer.with_layer(bounds, |renderer| {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: outline,
+                        border: Border {
+                            color,
+                            width: self.outline_width.value() * multiplier,
+                            radius: radius.into(),
+                        },
+                        ..renderer::Quad::default()
+                    },
+                    Background::Color(Color::TRANSPARENT),
+                );
+            });
+        };
+
+        if let Some(selection) = self.selection {
+            for node in selection
+                .nodes()
+                .iter()
+                .filter_map(|index| socket_state.nodes.get(*index))
+            {
+                draw_outline(*node, false, selection_color);
+            }
+        }
+
+        let traced_color = style.traced_color.unwrap_or(renderer_style.text_color);
+        let traced_link = container_state
+            .traced_link
+            .as_ref()
+            .filter(|_| self.trace_connections);
+        if let Some(link) = traced_link {
+            for endpoint in [link.start(), link.end()] {
+                if let Some(node) = endpoint
+                    .node_index()
+                    .and_then(|index| socket_state.nodes.get(index))
+                {
+                    draw_outline(*node, false, traced_color);
+                }
+            }
+        }
+
+        let mut draw_ring = |socket: &LogicalEndpoint, color: Color| {
+            if let Some(blob) = socket_state.blob(socket) {
+                draw_outline(blob, true, color);
+            }
+        };
+
+        if let Some(link) = traced_link {
+            for endpoint in [link.start(), link.end()] {
+                if let Some(socket) = socket_state.logical_endpoint(endpoint) {
+                    draw_ring(&socket, traced_color);
+                }
+            }
+        }
+
+        let rejected = container_state.rejected_socket;
+        if let (true, Some(focused)) = (self.keyboard_navigation, container_state.focused_socket) {
+            if rejected != Some(focused) {
+                draw_ring(
+                    &focused,
+                    style.focus_color.unwrap_or(renderer_style.text_color),
+                );
+            }
+        }
+        if let Some(rejected) = rejected {
+            draw_ring(
+                &rejected,
+                style
+                    .rejected_color
+                    .unwrap_or(Color::from_rgb(0.9, 0.2, 0.2)),
+            );
+        }
+
+        if let Gesture::Selecting { marquee, path, .. } = &container_state.gesture {
+            let width = self.outline_width.value();
+            renderer.with_layer(bounds, |renderer| match marquee {
+                Marquee::Box => {
+                    let (first, last) = (path[0], path[path.len() - 1]);
+                    let top_left = Point::new(first.x.min(last.x), first.y.min(last.y));
+                    let size = Size::new((first.x - last.x).abs(), (first.y - last.y).abs());
+                    renderer.fill_quad(
+                        renderer::Quad {
+                            bounds: Rectangle::new(top_left, size),
+                            border: Border {
+                                color: selection_color,
+                                width,
+                                radius: 0.0.into(),
+                            },
+                            ..renderer::Quad::default()
+                        },
+                        Background::Color(Color {
+                            a: selection_color.a * MARQUEE_FILL_ALPHA,
+                            ..selection_color
+                        }),
+                    );
+                }
+                Marquee::Lasso if path.len() > 1 => {
+                    // Closed by a straight line back to the start
+                    let points: Vec<Vector> = path
+                        .iter()
+                        .chain(path.first())
+                        .map(|point| Vector::new(point.x, point.y))
+                        .collect();
+                    let (vertices, indices) = line_to_polygon(&points, width / 2.0);
+                    let color = iced::advanced::graphics::color::pack(selection_color);
+                    renderer.draw_buffers(Indexed {
+                        vertices: vertices
+                            .into_iter()
+                            .map(|vertex| SolidVertex2D {
+                                position: [vertex.x, vertex.y],
+                                color,
+                            })
+                            .collect(),
+                        indices,
+                    });
+                }
+                Marquee::Lasso => {}
+            });
+        }
+
+        if let (Gesture::BendingConnection { .. }, Some(_), Some(cursor_position)) = (
+            &container_state.gesture,
+            &self.on_connection_split,
+            cursor.position(),
+        ) {
+            let dot = Rectangle::new(
+                cursor_position - Vector::new(SPLIT_PREVIEW_RADIUS, SPLIT_PREVIEW_RADIUS),
+                Size::new(SPLIT_PREVIEW_RADIUS * 2.0, SPLIT_PREVIEW_RADIUS * 2.0),
+            );
+            renderer.with_layer(bounds, |renderer| {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: dot,
+                        border: Border {
+                            radius: SPLIT_PREVIEW_RADIUS.into(),
+                            ..Border::default()
+                        },
+                        ..renderer::Quad::default()
+                    },
+                    Background::Color(style.focus_color.unwrap_or(renderer_style.text_color)),
+                );
+            });
+        }
     }
 }
 
@@ -600,7 +2773,7 @@ impl<'a, Message, Theme, Renderer> From<GraphContainer<'a, Message, Theme, Rende
 where
     Message: 'a,
     Theme: StyleSheet + 'a,
-    Renderer: renderer::Renderer + 'a,
+    Renderer: renderer::Renderer + text::Renderer + MeshRenderer + 'a,
 {
     fn from(graph_container: GraphContainer<'a, Message, Theme, Renderer>) -> Self {
         Self::new(graph_container)
@@ -627,18 +2800,25 @@ where
     );
 }
 
-fn draw_guidelines<Renderer>(
-    renderer: &mut Renderer,
+#[allow(clippy::too_many_arguments)]
+fn tessellate_guidelines(
+    mesh: &mut Indexed<SolidVertex2D>,
     bounds: Rectangle,
     offset: (f32, f32),
     scale: f32,
     grid_spacing: f32,
     biggest_grid_spacing: f32,
     color: Color,
-) where
-    Renderer: renderer::Renderer,
-{
-    if grid_spacing * scale < 5.0_f32 {
+    grid_style: GridStyle,
+    scale_factor: f32,
+) {
+    // Dots and crosses are pushed one by one, so they are left out much earlier than lines
+    let min_spacing = match grid_style {
+        GridStyle::Lines => MIN_GUIDELINE_SPACING,
+        GridStyle::Dots | GridStyle::Cross => MIN_GRID_MARKER_SPACING,
+        GridStyle::None => return,
+    };
+    if grid_spacing * scale < min_spacing {
         return;
     }
 
@@ -646,68 +2826,109 @@ fn draw_guidelines<Renderer>(
 
     let offset_x = offset.0 % edge;
     let offset_y = offset.1 % edge;
-
-    let from_x = -edge + offset_x + bounds.x;
-    let to_x = bounds.x + bounds.width + edge;
     let step = grid_spacing * scale;
-    let number_of_steps = ((to_x - from_x) / step).abs().ceil() as usize;
-
-    for x in 0..number_of_steps {
-        let x = from_x + (x as f32 * step);
-
-        if x <= bounds.x || x >= bounds.x + bounds.width {
-            continue;
-        }
-
-        renderer.fill_quad(
-            renderer::Quad {
-                bounds: Rectangle {
-                    x,
-                    y: bounds.y,
-                    width: 1.0_f32,
-                    height: bounds.height,
-                },
-                border: Border {
-                    color: Color::BLACK,
-                    width: 0.0_f32,
-                    radius: [0.0_f32, 0.0_f32, 0.0_f32, 0.0_f32].into(),
-                },
-                ..renderer::Quad::default()
-            },
-            Background::Color(color),
-        );
+
+    let lines = |from: f32, start: f32, length: f32| {
+        let to = start + length + edge;
+        let number_of_steps = ((to - from) / step).abs().ceil() as usize;
+        (0..number_of_steps)
+            .map(move |i| from + (i as f32 * step))
+            .filter(move |position| *position > start && *position < start + length)
+    };
+    // Lines are aligned to physical pixels and are a whole number of them wide, as they would
+    // be blurred otherwise
+    let snap = move |position| snap_to_pixel(position, scale_factor);
+    let line_width = scale_factor.round().max(1.0) / scale_factor;
+    let xs = lines(-edge + offset_x + bounds.x, bounds.x, bounds.width).map(snap);
+    let ys = lines(-edge + offset_y + bounds.y, bounds.y, bounds.height).map(snap);
+
+    match grid_style {
+        GridStyle::Lines => {
+            for x in xs {
+                push_rectangle(
+                    mesh,
+                    Rectangle::new(
+                        Point::new(x, bounds.y),
+                        Size::new(line_width, bounds.height),
+                    ),
+                    color,
+                );
+            }
+            for y in ys {
+                push_rectangle(
+                    mesh,
+                    Rectangle::new(Point::new(bounds.x, y), Size::new(bounds.width, line_width)),
+                    color,
+                );
+            }
+        }
+        GridStyle::Dots => {
+            let ys: Vec<f32> = ys.collect();
+            for x in xs {
+                for y in &ys {
+                    push_rectangle(
+                        mesh,
+                        Rectangle::new(Point::new(x - 1.0, y - 1.0), Size::new(2.0, 2.0)),
+                        color,
+                    );
+                }
+            }
+        }
+        GridStyle::None => {}
+        GridStyle::Cross => {
+            const ARM: f32 = 3.0;
+            let ys: Vec<f32> = ys.collect();
+            for x in xs {
+                for y in &ys {
+                    push_rectangle(
+                        mesh,
+                        Rectangle::new(
+                            Point::new(x - ARM, *y),
+                            Size::new(ARM * 2.0 + line_width, line_width),
+                        ),
+                        color,
+                    );
+                    push_rectangle(
+                        mesh,
+                        Rectangle::new(
+                            Point::new(x, y - ARM),
+                            Size::new(line_width, ARM * 2.0 + line_width),
+                        ),
+                        color,
+                    );
+                }
+            }
+        }
     }
+}
 
-    let from_y = -edge + offset_y + bounds.y;
-    let to_y = bounds.y + bounds.height + edge;
-    let step = grid_spacing * scale;
-    let number_of_steps = ((to_y - from_y) / step).abs().ceil() as usize;
-
-    for y in 0..number_of_steps {
-        let y = from_y + (y as f32 * step);
-
-        if y <= bounds.y || y >= bounds.y + bounds.height {
-            continue;
-        }
-
-        renderer.fill_quad(
-            renderer::Quad {
-                bounds: Rectangle {
-                    x: bounds.x,
-                    y,
-                    width: bounds.width,
-                    height: 1.0_f32,
-                },
-                border: Border {
-                    color: Color::BLACK,
-                    width: 0.0_f32,
-                    radius: [0.0_f32, 0.0_f32, 0.0_f32, 0.0_f32].into(),
-                },
-                ..renderer::Quad::default()
-            },
-            Background::Color(color),
-        );
+// The link between two sockets, or `None` if they have the same role and cannot be linked
+fn link_between(source: LogicalEndpoint, target: LogicalEndpoint) -> Option<Link> {
+    (source.role != target.role)
+        .then(|| Link::from_unordered(Endpoint::Socket(source), Endpoint::Socket(target)))
+}
+
+fn push_rectangle(mesh: &mut Indexed<SolidVertex2D>, rectangle: Rectangle, color: Color) {
+    let color = iced::advanced::graphics::color::pack(color);
+    let start = mesh.vertices.len() as u32;
+
+    for (x, y) in [
+        (rectangle.x, rectangle.y),
+        (rectangle.x + rectangle.width, rectangle.y),
+        (
+            rectangle.x + rectangle.width,
+            rectangle.y + rectangle.height,
+        ),
+        (rectangle.x, rectangle.y + rectangle.height),
+    ] {
+        mesh.vertices.push(SolidVertex2D {
+            position: [x, y],
+            color,
+        });
     }
+
+    mesh.indices
+        .extend([start, start + 1, start + 2, start, start + 2, start + 3]);
 }
 
 fn normalize_scale(scale: f32) -> f32 {
@@ -719,3 +2940,311 @@ fn normalize_scale(scale: f32) -> f32 {
         scale
     }
 }
+
+// The panning speed after the cursor moved by `delta` within `dt` seconds, with the earlier
+// speed still counting towards it
+fn smoothed_velocity(velocity: Vector, delta: Vector, dt: f32) -> Vector {
+    if dt > 0.0 {
+        velocity * (1.0 - KINETIC_VELOCITY_WEIGHT) + delta * (KINETIC_VELOCITY_WEIGHT / dt)
+    } else {
+        velocity
+    }
+}
+
+// The speed of kinetic panning after slowing down by `friction` for `dt` seconds, or `None` once
+// it is too slow to go on
+fn slowed_down(velocity: Vector, friction: f32, dt: f32) -> Option<Vector> {
+    let velocity = velocity * (1.0 - friction).powf(dt);
+    (velocity.x.hypot(velocity.y) >= KINETIC_STOP_SPEED).then_some(velocity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node;
+    use iced::advanced::clipboard;
+    use iced::widget::text;
+    use iced::{Font, Pixels, Renderer, Theme};
+
+    const VIEWPORT: Size = Size::new(800.0, 600.0);
+    const NODE_CENTER: Point = Point::new(150.0, 120.0);
+    const CANVAS: Point = Point::new(600.0, 400.0);
+
+    // A container with one node at the top left, laid out in a fixed viewport with the software
+    // renderer
+    struct Harness {
+        renderer: Renderer,
+        tree: widget::Tree,
+        layout: layout::Node,
+    }
+
+    impl Harness {
+        fn new<M>(element: &Element<'_, M, Theme, Renderer>) -> Self {
+            let renderer = Renderer::TinySkia(iced_tiny_skia::Renderer::new(
+                iced_tiny_skia::Backend::new(),
+                Font::default(),
+                Pixels(16.0),
+            ));
+            let mut tree = widget::Tree::new(element);
+            let layout = element.as_widget().layout(
+                &mut tree,
+                &renderer,
+                &layout::Limits::new(Size::ZERO, VIEWPORT),
+            );
+            Self {
+                renderer,
+                tree,
+                layout,
+            }
+        }
+
+        // Returns the messages published in response to the event
+        fn send<M>(
+            &mut self,
+            element: &mut Element<'_, M, Theme, Renderer>,
+            event: Event,
+            cursor: mouse::Cursor,
+        ) -> Vec<M> {
+            let mut messages = Vec::new();
+            element.as_widget_mut().on_event(
+                &mut self.tree,
+                event,
+                Layout::new(&self.layout),
+                cursor,
+                &self.renderer,
+                &mut clipboard::Null,
+                &mut Shell::new(&mut messages),
+                &Rectangle::with_size(VIEWPORT),
+            );
+            messages
+        }
+
+        fn gesture(&self) -> &Gesture {
+            &self
+                .tree
+                .state
+                .downcast_ref::<TreeState>()
+                .interaction
+                .gesture
+        }
+    }
+
+    fn view() -> Element<'static, (), Theme, Renderer> {
+        let content = vec![node(text("Node"))
+            .position(Point::new(100.0, 100.0))
+            .width(Length::Fixed(100.0))
+            .into()];
+        graph_container(content)
+            .on_translate(|_| ())
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn press() -> Event {
+        Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+    }
+
+    fn release() -> Event {
+        Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+    }
+
+    fn move_to(position: Point) -> (Event, mouse::Cursor) {
+        (
+            Event::Mouse(mouse::Event::CursorMoved { position }),
+            mouse::Cursor::Available(position),
+        )
+    }
+
+    #[test]
+    fn panning_ends_on_release() {
+        let mut element = view();
+        let mut harness = Harness::new(&element);
+
+        harness.send(&mut element, press(), mouse::Cursor::Available(CANVAS));
+        assert!(matches!(
+            harness.gesture(),
+            Gesture::PanningViewport { press_position, .. } if *press_position == CANVAS
+        ));
+
+        let (event, cursor) = move_to(CANVAS + Vector::new(40.0, 30.0));
+        harness.send(&mut element, event, cursor);
+        assert!(matches!(
+            harness.gesture(),
+            Gesture::PanningViewport { last_position, .. }
+                if *last_position == CANVAS + Vector::new(40.0, 30.0)
+        ));
+
+        harness.send(&mut element, release(), cursor);
+        assert_eq!(*harness.gesture(), Gesture::Idle);
+    }
+
+    #[test]
+    fn dragging_a_node_ends_on_release() {
+        let mut element = view();
+        let mut harness = Harness::new(&element);
+
+        harness.send(&mut element, press(), mouse::Cursor::Available(NODE_CENTER));
+        assert_eq!(*harness.gesture(), Gesture::DraggingNode);
+
+        let (event, cursor) = move_to(NODE_CENTER + Vector::new(20.0, 0.0));
+        harness.send(&mut element, event, cursor);
+        assert_eq!(*harness.gesture(), Gesture::DraggingNode);
+
+        harness.send(&mut element, release(), cursor);
+        assert_eq!(*harness.gesture(), Gesture::Idle);
+    }
+
+    #[test]
+    fn release_outside_of_the_bounds_ends_the_gesture() {
+        let outside = Point::new(-50.0, VIEWPORT.height + 50.0);
+
+        let mut element = view();
+        let mut harness = Harness::new(&element);
+        harness.send(&mut element, press(), mouse::Cursor::Available(CANVAS));
+        let (event, cursor) = move_to(outside);
+        harness.send(&mut element, event.clone(), cursor);
+        harness.send(&mut element, release(), cursor);
+        assert_eq!(*harness.gesture(), Gesture::Idle);
+
+        harness.send(&mut element, press(), mouse::Cursor::Available(NODE_CENTER));
+        assert_eq!(*harness.gesture(), Gesture::DraggingNode);
+        harness.send(&mut element, event, cursor);
+        harness.send(&mut element, release(), cursor);
+        assert_eq!(*harness.gesture(), Gesture::Idle);
+
+        // The cursor may also leave the window entirely
+        harness.send(&mut element, press(), mouse::Cursor::Available(CANVAS));
+        harness.send(&mut element, release(), mouse::Cursor::Unavailable);
+        assert_eq!(*harness.gesture(), Gesture::Idle);
+    }
+
+    #[test]
+    fn box_selection_ends_on_release() {
+        let selection = Selection::new();
+        let mut element: Element<'_, (), Theme, Renderer> = graph_container(Vec::new())
+            .selection(&selection)
+            .on_selection_change(|_| ())
+            .marquee(Marquee::Box, keyboard::Modifiers::CTRL)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into();
+        let mut harness = Harness::new(&element);
+
+        let modifiers =
+            Event::Keyboard(keyboard::Event::ModifiersChanged(keyboard::Modifiers::CTRL));
+        harness.send(&mut element, modifiers, mouse::Cursor::Available(CANVAS));
+        harness.send(&mut element, press(), mouse::Cursor::Available(CANVAS));
+        assert!(matches!(
+            harness.gesture(),
+            Gesture::Selecting {
+                marquee: Marquee::Box,
+                ..
+            }
+        ));
+
+        let (event, cursor) = move_to(Point::ORIGIN);
+        harness.send(&mut element, event, cursor);
+        harness.send(&mut element, release(), cursor);
+        assert_eq!(*harness.gesture(), Gesture::Idle);
+    }
+
+    #[test]
+    fn dragging_a_node_stays_within_its_bounds() {
+        // The node is dragged twice without being rebuilt in between, as happens when several
+        // cursor events arrive within one frame
+        let mut element: Element<'_, Point, Theme, Renderer> =
+            graph_container(vec![node(text("Node"))
+                .position(Point::new(100.0, 100.0))
+                .width(Length::Fixed(100.0))
+                .position_bounds(Rectangle::new(Point::ORIGIN, Size::new(300.0, 300.0)))
+                .on_move(|position| position)
+                .into()])
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into();
+        let mut harness = Harness::new(&element);
+
+        harness.send(&mut element, press(), mouse::Cursor::Available(NODE_CENTER));
+        let (event, cursor) = move_to(NODE_CENTER + Vector::new(150.0, 0.0));
+        let messages = harness.send(&mut element, event, cursor);
+        assert_eq!(messages, vec![Point::new(200.0, 100.0)]);
+
+        let (event, cursor) = move_to(NODE_CENTER + Vector::new(300.0, 0.0));
+        let messages = harness.send(&mut element, event, cursor);
+        assert_eq!(messages, vec![Point::new(200.0, 100.0)]);
+    }
+
+    #[test]
+    fn on_move_accumulates_the_drag() {
+        let mut element: Element<'_, Point, Theme, Renderer> =
+            graph_container(vec![node(text("Node"))
+                .position(Point::new(100.0, 100.0))
+                .width(Length::Fixed(100.0))
+                .on_move(|position| position)
+                .into()])
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into();
+        let mut harness = Harness::new(&element);
+
+        harness.send(&mut element, press(), mouse::Cursor::Available(NODE_CENTER));
+        let (event, cursor) = move_to(NODE_CENTER + Vector::new(20.0, 10.0));
+        let messages = harness.send(&mut element, event, cursor);
+        assert_eq!(messages, vec![Point::new(120.0, 110.0)]);
+
+        // Without a rebuild, the node still has its original position
+        let (event, cursor) = move_to(NODE_CENTER + Vector::new(50.0, -10.0));
+        let messages = harness.send(&mut element, event, cursor);
+        assert_eq!(messages, vec![Point::new(150.0, 90.0)]);
+    }
+
+    #[test]
+    fn pan_velocity_favors_the_latest_move() {
+        let velocity = smoothed_velocity(Vector::ZERO, Vector::new(10.0, 0.0), 0.01);
+        assert!((velocity.x - 600.0).abs() < 1e-3);
+        assert_eq!(velocity.y, 0.0);
+
+        let velocity = smoothed_velocity(velocity, Vector::new(0.0, 10.0), 0.01);
+        assert!((velocity.x - 240.0).abs() < 1e-3);
+        assert!((velocity.y - 600.0).abs() < 1e-3);
+
+        // Moves reported at the same instant leave the speed as it is
+        assert_eq!(
+            smoothed_velocity(velocity, Vector::new(5.0, 5.0), 0.0),
+            velocity
+        );
+    }
+
+    #[test]
+    fn kinetic_panning_slows_down_until_it_stops() {
+        let velocity = Vector::new(1000.0, 0.0);
+        assert_eq!(
+            slowed_down(velocity, 0.5, 1.0),
+            Some(Vector::new(500.0, 0.0))
+        );
+        assert_eq!(slowed_down(velocity, 0.5, 0.0), Some(velocity));
+        assert_eq!(slowed_down(velocity, 1.0, 0.016), None);
+
+        let mut velocity = Some(velocity);
+        let mut frames = 0;
+        while let Some(current) = velocity {
+            velocity = slowed_down(current, 0.9, 0.016);
+            frames += 1;
+            assert!(frames < 1000, "kinetic panning never stopped");
+        }
+    }
+
+    #[test]
+    fn kinetic_panning_rejects_a_friction_that_is_not_finite() {
+        let container = |friction| {
+            graph_container::<(), Theme, Renderer>(Vec::new())
+                .kinetic_panning(friction)
+                .kinetic_friction
+        };
+        assert_eq!(container(f32::NAN), None);
+        assert_eq!(container(f32::INFINITY), None);
+        assert_eq!(container(0.0), Some(0.01));
+        assert_eq!(container(2.0), Some(1.0));
+    }
+}
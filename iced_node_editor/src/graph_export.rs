@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use iced::{Background, Color, Point, Rectangle, Vector};
+
+use crate::connection::{self as connection_widget, generate_spline};
+use crate::node::DEFAULT_BLOB_RADIUS;
+use crate::node_element::named_socket;
+use crate::styles::{connection, graph_container, node};
+use crate::{Endpoint, Link, LogicalEndpoint, SocketPosition};
+
+// Exporting happens outside of the widget tree, so the application describes the graph in graph
+// space: the bounds of its nodes (which it usually knows, as it chooses their sizes), and the
+// socket positions reported by `GraphContainer::on_socket_layout`.
+#[derive(Debug, Clone, Default)]
+pub struct GraphDescription {
+    // In the order of the node indices used by the links
+    pub nodes: Vec<NodeDescription>,
+    pub sockets: Vec<SocketPosition>,
+    // For every node, the socket each of its `Socket::name`s refers to; only needed for links
+    // with `Endpoint::NamedSocket`
+    pub socket_names: Vec<HashMap<String, LogicalEndpoint>>,
+    pub links: Vec<Link>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeDescription {
+    pub bounds: Rectangle,
+    // Drawn in the center of the node
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ExportStyle {
+    pub graph: graph_container::Appearance,
+    pub node: node::Appearance,
+    pub connection: connection::Appearance,
+    pub socket_radius: f32,
+    pub socket_color: Color,
+    pub connection_width: f32,
+    pub text_size: f32,
+    // Empty space around the graph, in graph units
+    pub padding: f32,
+}
+
+impl ExportStyle {
+    // Uses the default appearances of the given theme and the default connection width, as the
+    // widgets would. The text size is the default one of iced.
+    pub fn from_theme<Theme>(theme: &Theme) -> Self
+    where
+        Theme: graph_container::StyleSheet + node::StyleSheet + connection::StyleSheet,
+    {
+        let connection = connection::StyleSheet::appearance(
+            theme,
+            &<Theme as connection::StyleSheet>::Style::default(),
+        );
+
+        Self {
+            graph: graph_container::StyleSheet::appearance(
+                theme,
+                &<Theme as graph_container::StyleSheet>::Style::default(),
+            ),
+            node: node::StyleSheet::appearance(
+                theme,
+                &<Theme as node::StyleSheet>::Style::default(),
+            ),
+            connection,
+            socket_radius: DEFAULT_BLOB_RADIUS,
+            socket_color: connection.color.unwrap_or(Color::BLACK),
+            connection_width: connection_widget::DEFAULT_WIDTH,
+            text_size: iced::Settings::<()>::default().default_text_size.0,
+            padding: 20.0,
+        }
+    }
+}
+
+// Renders the graph as an SVG document. Connections are drawn with the same splines as in the
+// editor; the result can be rasterized with any SVG renderer to get a PNG.
+pub fn to_svg(graph: &GraphDescription, style: &ExportStyle) -> String {
+    let bounds = graph
+        .nodes
+        .iter()
+        .map(|node| node.bounds)
+        .reduce(|a, b| a.union(&b))
+        .unwrap_or(Rectangle::with_size(iced::Size::ZERO))
+        .expand(style.padding);
+
+    let mut svg = String::new();
+    // Writing to a `String` cannot fail
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+        bounds.x, bounds.y, bounds.width, bounds.height, bounds.width, bounds.height
+    );
+
+    if let Some(Background::Color(color)) = style.graph.background {
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+            bounds.x,
+            bounds.y,
+            bounds.width,
+            bounds.height,
+            paint("fill", color)
+        );
+    }
+
+    let node_fill = match style.node.background {
+        Some(Background::Color(color)) => color,
+        _ => Color::TRANSPARENT,
+    };
+    let text_color = style
+        .node
+        .text_color
+        .or(style.connection.color)
+        .unwrap_or(Color::BLACK);
+
+    for node in &graph.nodes {
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" {} {} stroke-width="{}"/>"#,
+            node.bounds.x,
+            node.bounds.y,
+            node.bounds.width,
+            node.bounds.height,
+            style.node.border_radius,
+            paint("fill", node_fill),
+            paint("stroke", style.node.border_color),
+            style.node.border_width
+        );
+        let center = node.bounds.center();
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="{}" text-anchor="middle" dominant-baseline="middle" {}>{}</text>"#,
+            center.x,
+            center.y,
+            style.text_size,
+            paint("fill", text_color),
+            escape(&node.title)
+        );
+    }
+
+    for socket in &graph.sockets {
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{}" cy="{}" r="{}" {}/>"#,
+            socket.center.x,
+            socket.center.y,
+            style.socket_radius,
+            paint("fill", style.socket_color)
+        );
+    }
+
+    let connection_color = style.connection.color.unwrap_or(Color::BLACK);
+    for link in &graph.links {
+        let (Some((from, from_direction)), Some((to, to_direction))) =
+            (resolve(graph, link.start()), resolve(graph, link.end()))
+        else {
+            continue;
+        };
+
+        // Like in the editor, the curve leaves the start socket away from its node and enters
+        // the end socket from the outside. Other endpoints are left and entered to the right.
+        let right = Vector::new(1.0, 0.0);
+        let spline = generate_spline(
+            Vector::new(from.x, from.y),
+            from_direction.unwrap_or(right),
+            1.0,
+            Vector::new(to.x, to.y),
+            to_direction.map_or(right, |direction| direction * -1.0),
+            20,
+            1.0,
+        );
+
+        let mut path = String::new();
+        for (index, point) in spline.iter().enumerate() {
+            let command = if index == 0 { 'M' } else { 'L' };
+            let _ = write!(path, "{}{} {} ", command, point.x, point.y);
+        }
+        let _ = writeln!(
+            svg,
+            r#"<path d="{}" fill="none" {} stroke-width="{}"/>"#,
+            path.trim_end(),
+            paint("stroke", connection_color),
+            style.connection_width
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// The position of an endpoint, along with the direction away from its node if it is a socket
+fn resolve(graph: &GraphDescription, endpoint: &Endpoint) -> Option<(Point, Option<Vector>)> {
+    match endpoint {
+        Endpoint::Absolute(point) => Some((*point, None)),
+        Endpoint::NodeAnchor(node_index, anchor) => graph.nodes.get(*node_index).map(|node| {
+            let point = Point::new(
+                node.bounds.x + node.bounds.width * anchor.x,
+                node.bounds.y + node.bounds.height * anchor.y,
+            );
+            (point, None)
+        }),
+        Endpoint::Socket(logical) => graph
+            .sockets
+            .iter()
+            .find(|socket| socket.endpoint == *logical)
+            .map(|socket| (socket.center, Some(socket.side.outward_direction()))),
+        Endpoint::NamedSocket(node_index, name) => {
+            let logical = named_socket(&graph.socket_names, *node_index, name)?;
+            resolve(graph, &Endpoint::Socket(logical))
+        }
+    }
+}
+
+// An SVG paint attribute, with the alpha channel as a separate opacity attribute
+fn paint(attribute: &str, color: Color) -> String {
+    let [r, g, b, a] = color.into_rgba8();
+    format!(
+        r##"{attribute}="#{r:02x}{g:02x}{b:02x}" {attribute}-opacity="{}""##,
+        a as f32 / 255.0
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
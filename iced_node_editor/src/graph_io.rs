@@ -0,0 +1,713 @@
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use iced::{Point, Rectangle, Size, Vector};
+
+use crate::graph_export::{GraphDescription, NodeDescription};
+use crate::layout::layered::layered;
+use crate::node::DEFAULT_BLOB_RADIUS;
+use crate::{
+    Endpoint, Link, LogicalEndpoint, RelativeAnchor, SocketPosition, SocketRole, SocketSide,
+};
+
+// Conversions between `GraphDescription` and the Graphviz DOT language. Graph units are written
+// as DOT points (1/72 inch), with the y axis flipped as DOT positions grow upwards. Sockets are
+// represented as node ports named `in<n>` and `out<n>`; links between node anchors become edges
+// without ports, and links with absolute endpoints cannot be represented and are left out.
+
+// Size of imported nodes that do not specify `width` and `height`
+const DEFAULT_NODE_SIZE: Size = Size::new(150.0, 75.0);
+// Gaps between columns and rows when imported nodes have to be laid out
+const LAYOUT_SPACING: Vector = Vector::new(100.0, 50.0);
+const POINTS_PER_INCH: f32 = 72.0;
+// Ports with a higher socket index are rejected, as every socket up to the index is created
+const MAX_SOCKETS_PER_NODE: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotError {
+    // 1-based line in the DOT source where the error was found
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for DotError {}
+
+// Writes the graph as a DOT digraph. Node `i` is called `n<i>`, and is pinned to its position in
+// the editor, so that Graphviz tools (`neato -n`) reproduce the layout.
+pub fn to_dot(graph: &GraphDescription) -> String {
+    let mut dot = String::from("digraph {\n    node [shape=box];\n");
+
+    // Writing to a `String` cannot fail
+    for (index, node) in graph.nodes.iter().enumerate() {
+        let center = node.bounds.center();
+        let _ = writeln!(
+            dot,
+            "    n{} [label={}, pos=\"{},{}!\", width={}, height={}];",
+            index,
+            quote(&node.title),
+            center.x,
+            -center.y,
+            node.bounds.width / POINTS_PER_INCH,
+            node.bounds.height / POINTS_PER_INCH
+        );
+    }
+
+    for link in &graph.links {
+        let (Some(tail), Some(head)) = (dot_endpoint(link.start()), dot_endpoint(link.end()))
+        else {
+            continue;
+        };
+
+        let _ = writeln!(dot, "    {} -> {};", tail, head);
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+// Reads a DOT graph. Node labels become titles (falling back to the node ID), and `pos`, `width`
+// and `height` attributes are used for the bounds. If any node has no position, all nodes are
+// auto-laid-out with `layout::layered`. Socket positions are generated for every port used by
+// the edges: inputs are spread over the left side of the node, outputs over the right side.
+//
+// Only the parts of the language that describe nodes and edges are interpreted; attribute
+// statements and subgraph boundaries are accepted but ignored.
+pub fn from_dot(source: &str) -> Result<GraphDescription, DotError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        nodes: Vec::new(),
+        node_ids: HashMap::new(),
+        edges: Vec::new(),
+    };
+    parser.graph()?;
+
+    let positioned = parser.nodes.iter().all(|node| node.center.is_some());
+    let mut bounds: Vec<Rectangle> = parser
+        .nodes
+        .iter()
+        .map(|node| {
+            let center = node.center.unwrap_or(Point::ORIGIN);
+            Rectangle::new(
+                Point::new(
+                    center.x - node.size.width / 2.0,
+                    center.y - node.size.height / 2.0,
+                ),
+                node.size,
+            )
+        })
+        .collect();
+
+    let mut links = Vec::new();
+    let mut socket_counts = vec![(0, 0); parser.nodes.len()];
+    for edge in &parser.edges {
+        let start = edge_endpoint(&edge.tail, SocketRole::Out, edge.line)?;
+        let end = edge_endpoint(&edge.head, SocketRole::In, edge.line)?;
+
+        for endpoint in [&start, &end] {
+            if let Endpoint::Socket(logical) = endpoint {
+                let count = logical
+                    .socket_index
+                    .checked_add(1)
+                    .filter(|count| *count <= MAX_SOCKETS_PER_NODE)
+                    .ok_or_else(|| socket_index_error(edge.line))?;
+                let counts = &mut socket_counts[logical.node_index];
+                match logical.role {
+                    SocketRole::In => counts.0 = counts.0.max(count),
+                    SocketRole::Out => counts.1 = counts.1.max(count),
+                }
+            }
+        }
+
+        links.push(Link::new(start, end));
+    }
+
+    if !positioned {
+        let edges: Vec<(usize, usize)> = parser
+            .edges
+            .iter()
+            .map(|edge| (edge.tail.0, edge.head.0))
+            .collect();
+        layered(&mut bounds, &edges, LAYOUT_SPACING);
+    }
+
+    let mut sockets = Vec::new();
+    for (node_index, (inputs, outputs)) in socket_counts.iter().enumerate() {
+        let node = bounds[node_index];
+        for (role, side, count, x) in [
+            (SocketRole::In, SocketSide::Left, *inputs, node.x),
+            (
+                SocketRole::Out,
+                SocketSide::Right,
+                *outputs,
+                node.x + node.width,
+            ),
+        ] {
+            for socket_index in 0..count {
+                let center = Point::new(
+                    x,
+                    node.y + node.height * (socket_index + 1) as f32 / (count + 1) as f32,
+                );
+                // Sockets get the size of the default blobs
+                let blob = Rectangle::new(
+                    center - Vector::new(DEFAULT_BLOB_RADIUS, DEFAULT_BLOB_RADIUS),
+                    Size::new(DEFAULT_BLOB_RADIUS * 2.0, DEFAULT_BLOB_RADIUS * 2.0),
+                );
+                sockets.push(SocketPosition {
+                    endpoint: LogicalEndpoint {
+                        node_index,
+                        role,
+                        socket_index,
+                    },
+                    center,
+                    side,
+                    blob,
+                    hit_area: blob,
+                });
+            }
+        }
+    }
+
+    Ok(GraphDescription {
+        nodes: parser
+            .nodes
+            .into_iter()
+            .zip(bounds)
+            .map(|(node, bounds)| NodeDescription {
+                bounds,
+                title: node.title,
+            })
+            .collect(),
+        sockets,
+        socket_names: Vec::new(),
+        links,
+    })
+}
+
+fn dot_endpoint(endpoint: &Endpoint) -> Option<String> {
+    match endpoint {
+        Endpoint::Absolute(_) => None,
+        Endpoint::NodeAnchor(node_index, _) => Some(format!("n{}", node_index)),
+        Endpoint::Socket(logical) => Some(format!(
+            "n{}:{}{}",
+            logical.node_index,
+            match logical.role {
+                SocketRole::In => "in",
+                SocketRole::Out => "out",
+            },
+            logical.socket_index
+        )),
+        // Any port other than `in<n>` and `out<n>` reads back as a node anchor
+        Endpoint::NamedSocket(node_index, name) => Some(format!("n{}:{}", node_index, quote(name))),
+    }
+}
+
+// Edges without a socket port attach to the side of the node facing the other end
+fn edge_endpoint(
+    (node_index, port): &(usize, Option<String>),
+    role: SocketRole,
+    line: usize,
+) -> Result<Endpoint, DotError> {
+    let anchor = match role {
+        SocketRole::In => RelativeAnchor::CENTER_LEFT,
+        SocketRole::Out => RelativeAnchor::CENTER_RIGHT,
+    };
+    let Some(port) = port else {
+        return Ok(Endpoint::NodeAnchor(*node_index, anchor));
+    };
+
+    let socket = [("in", SocketRole::In), ("out", SocketRole::Out)]
+        .into_iter()
+        .find_map(|(prefix, port_role)| {
+            let digits = port.strip_prefix(prefix)?;
+            (!digits.is_empty() && digits.bytes().all(|byte| byte.is_ascii_digit()))
+                .then(|| (port_role, digits.parse::<usize>().ok()))
+        });
+
+    match socket {
+        Some((port_role, socket_index)) if port_role == role => match socket_index {
+            Some(socket_index) if socket_index < MAX_SOCKETS_PER_NODE => {
+                Ok(Endpoint::socket(*node_index, role, socket_index))
+            }
+            _ => Err(socket_index_error(line)),
+        },
+        Some(_) => Err(DotError {
+            line,
+            message: format!(
+                "port `{}` cannot be used at this end of an edge, as edges go from outputs to \
+                 inputs",
+                port
+            ),
+        }),
+        // Any other port (such as a compass point) is not a socket
+        None => Ok(Endpoint::NodeAnchor(*node_index, anchor)),
+    }
+}
+
+fn socket_index_error(line: usize) -> DotError {
+    DotError {
+        line,
+        message: format!(
+            "socket index out of range, nodes can have at most {} sockets on each side",
+            MAX_SOCKETS_PER_NODE
+        ),
+    }
+}
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    // Identifiers, numerals, quoted and HTML strings
+    Id(String),
+    Punct(char),
+    Edge,
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, DotError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            // Preprocessor-style lines are treated as comments
+            '#' => while chars.next_if(|c| *c != '\n').is_some() {},
+            '/' if chars.next_if_eq(&'/').is_some() => {
+                while chars.next_if(|c| *c != '\n').is_some() {}
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                let mut previous = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if previous == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            previous = c;
+                        }
+                        None => {
+                            return Err(DotError {
+                                line,
+                                message: String::from("unterminated comment"),
+                            })
+                        }
+                    }
+                }
+            }
+            '-' if chars.next_if(|c| *c == '>' || *c == '-').is_some() => {
+                tokens.push((Token::Edge, line));
+            }
+            '"' => {
+                let start = line;
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') if chars.next_if_eq(&'"').is_some() => text.push('"'),
+                        Some('\\') if chars.next_if_eq(&'\\').is_some() => text.push('\\'),
+                        // Escaped line breaks continue the string
+                        Some('\\') if chars.next_if_eq(&'\n').is_some() => line += 1,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            text.push(c);
+                        }
+                        None => {
+                            return Err(DotError {
+                                line: start,
+                                message: String::from("unterminated string"),
+                            })
+                        }
+                    }
+                }
+
+                // Quoted strings can be concatenated with `+`
+                match tokens.last_mut() {
+                    Some((Token::Punct('+'), _)) => {
+                        tokens.pop();
+                        match tokens.last_mut() {
+                            Some((Token::Id(previous), _)) => previous.push_str(&text),
+                            _ => {
+                                return Err(DotError {
+                                    line,
+                                    message: String::from("`+` must follow a string"),
+                                })
+                            }
+                        }
+                    }
+                    _ => tokens.push((Token::Id(text), start)),
+                }
+            }
+            '<' => {
+                let start = line;
+                let mut text = String::new();
+                let mut depth = 1;
+                while depth > 0 {
+                    match chars.next() {
+                        Some(c) => {
+                            match c {
+                                '<' => depth += 1,
+                                '>' => depth -= 1,
+                                '\n' => line += 1,
+                                _ => {}
+                            }
+                            if depth > 0 {
+                                text.push(c);
+                            }
+                        }
+                        None => {
+                            return Err(DotError {
+                                line: start,
+                                message: String::from("unterminated HTML string"),
+                            })
+                        }
+                    }
+                }
+                tokens.push((Token::Id(text), start));
+            }
+            '{' | '}' | '[' | ']' | ';' | ',' | '=' | ':' | '+' => {
+                tokens.push((Token::Punct(c), line));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut text = String::from(c);
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '.')
+                {
+                    text.push(c);
+                }
+                tokens.push((Token::Id(text), line));
+            }
+            c => {
+                return Err(DotError {
+                    line,
+                    message: format!("unexpected character `{}`", c),
+                })
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct ParsedNode {
+    title: String,
+    center: Option<Point>,
+    size: Size,
+}
+
+struct ParsedEdge {
+    tail: (usize, Option<String>),
+    head: (usize, Option<String>),
+    line: usize,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    nodes: Vec<ParsedNode>,
+    node_ids: HashMap<String, usize>,
+    edges: Vec<ParsedEdge>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .or(self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn error(&self, message: impl Into<String>) -> DotError {
+        DotError {
+            line: self.line(),
+            message: message.into(),
+        }
+    }
+
+    fn eat(&mut self, punct: char) -> bool {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: char) -> Result<(), DotError> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", punct)))
+        }
+    }
+
+    fn id(&mut self) -> Result<String, DotError> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.position += 1;
+                Ok(id)
+            }
+            _ => Err(self.error("expected an identifier")),
+        }
+    }
+
+    fn keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Id(id)) if id.eq_ignore_ascii_case(keyword))
+    }
+
+    fn graph(&mut self) -> Result<(), DotError> {
+        if self.keyword("strict") {
+            self.position += 1;
+        }
+        if !(self.keyword("graph") || self.keyword("digraph")) {
+            return Err(self.error("expected `graph` or `digraph`"));
+        }
+        self.position += 1;
+
+        if !self.eat('{') {
+            self.id()?;
+            self.expect('{')?;
+        }
+        self.statements()?;
+
+        if self.position < self.tokens.len() {
+            return Err(self.error("unexpected content after the graph"));
+        }
+
+        Ok(())
+    }
+
+    // Parses statements up to and including the closing brace
+    fn statements(&mut self) -> Result<(), DotError> {
+        loop {
+            match self.peek() {
+                None => return Err(self.error("expected `}`")),
+                Some(Token::Punct('}')) => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                Some(Token::Punct(';')) => self.position += 1,
+                _ => self.statement()?,
+            }
+        }
+    }
+
+    fn statement(&mut self) -> Result<(), DotError> {
+        if self.keyword("graph") || self.keyword("node") || self.keyword("edge") {
+            self.position += 1;
+            self.attributes()?;
+            return Ok(());
+        }
+
+        if self.keyword("subgraph") || self.peek() == Some(&Token::Punct('{')) {
+            if self.keyword("subgraph") {
+                self.position += 1;
+                if !self.eat('{') {
+                    self.id()?;
+                    self.expect('{')?;
+                }
+            } else {
+                self.position += 1;
+            }
+
+            if self.peek() == Some(&Token::Edge) {
+                return Err(self.error("edges to subgraphs are not supported"));
+            }
+            return self.statements();
+        }
+
+        let id = self.id()?;
+
+        // Graph attribute assignment (`rankdir = LR`)
+        if self.eat('=') {
+            self.id()?;
+            return Ok(());
+        }
+
+        let line = self.line();
+        let mut endpoints = vec![(self.node(&id), self.port()?)];
+        while self.peek() == Some(&Token::Edge) {
+            self.position += 1;
+            if self.keyword("subgraph") || self.peek() == Some(&Token::Punct('{')) {
+                return Err(self.error("edges to subgraphs are not supported"));
+            }
+
+            let id = self.id()?;
+            endpoints.push((self.node(&id), self.port()?));
+        }
+
+        let attributes = self.attributes()?;
+        if endpoints.len() == 1 {
+            let node = &mut self.nodes[endpoints[0].0];
+            for (key, value) in attributes {
+                apply_node_attribute(node, &key, &value);
+            }
+        } else {
+            for pair in endpoints.windows(2) {
+                self.edges.push(ParsedEdge {
+                    tail: pair[0].clone(),
+                    head: pair[1].clone(),
+                    line,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    // The node with the given ID, which is created on first use
+    fn node(&mut self, id: &str) -> usize {
+        if let Some(index) = self.node_ids.get(id) {
+            return *index;
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(ParsedNode {
+            title: id.to_string(),
+            center: None,
+            size: DEFAULT_NODE_SIZE,
+        });
+        self.node_ids.insert(id.to_string(), index);
+        index
+    }
+
+    // An optional `:port` or `:port:compass_point` suffix of a node ID
+    fn port(&mut self) -> Result<Option<String>, DotError> {
+        if !self.eat(':') {
+            return Ok(None);
+        }
+
+        let port = self.id()?;
+        if self.eat(':') {
+            self.id()?;
+        }
+        Ok(Some(port))
+    }
+
+    // Any number of `[key = value, ...]` lists
+    fn attributes(&mut self) -> Result<Vec<(String, String)>, DotError> {
+        let mut attributes = Vec::new();
+        while self.eat('[') {
+            while !self.eat(']') {
+                let key = self.id()?;
+                self.expect('=')?;
+                let value = self.id()?;
+                attributes.push((key, value));
+
+                if !self.eat(',') {
+                    self.eat(';');
+                }
+            }
+        }
+
+        Ok(attributes)
+    }
+}
+
+fn apply_node_attribute(node: &mut ParsedNode, key: &str, value: &str) {
+    match key {
+        "label" => node.title = value.to_string(),
+        "pos" => {
+            let mut coordinates = value
+                .trim_end_matches('!')
+                .split(',')
+                .map(|coordinate| coordinate.trim().parse::<f32>());
+            if let (Some(Ok(x)), Some(Ok(y))) = (coordinates.next(), coordinates.next()) {
+                node.center = Some(Point::new(x, -y));
+            }
+        }
+        "width" => {
+            if let Ok(width) = value.parse::<f32>() {
+                node.size.width = width * POINTS_PER_INCH;
+            }
+        }
+        "height" => {
+            if let Ok(height) = value.parse::<f32>() {
+                node.size.height = height * POINTS_PER_INCH;
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_keeps_escaped_titles() {
+        let bounds = |x| Rectangle::new(Point::new(x, 0.0), Size::new(144.0, 72.0));
+        let graph = GraphDescription {
+            nodes: vec![
+                NodeDescription {
+                    bounds: bounds(0.0),
+                    title: String::from(r#"C:\data "raw" \"#),
+                },
+                NodeDescription {
+                    bounds: bounds(300.0),
+                    title: String::from(r"a\\b"),
+                },
+            ],
+            sockets: Vec::new(),
+            socket_names: Vec::new(),
+            links: vec![Link::new(
+                Endpoint::Socket(LogicalEndpoint {
+                    node_index: 0,
+                    role: SocketRole::Out,
+                    socket_index: 0,
+                }),
+                Endpoint::Socket(LogicalEndpoint {
+                    node_index: 1,
+                    role: SocketRole::In,
+                    socket_index: 0,
+                }),
+            )],
+        };
+
+        let parsed = from_dot(&to_dot(&graph)).unwrap();
+
+        assert_eq!(parsed.nodes.len(), 2);
+        for (parsed, original) in parsed.nodes.iter().zip(&graph.nodes) {
+            assert_eq!(parsed.title, original.title);
+            assert_eq!(parsed.bounds, original.bounds);
+        }
+        assert_eq!(parsed.links.len(), 1);
+        assert_eq!(parsed.links[0].start(), graph.links[0].start());
+        assert_eq!(parsed.links[0].end(), graph.links[0].end());
+    }
+
+    #[test]
+    fn out_of_range_socket_indices_are_rejected() {
+        for port in ["out1024", "out99999999999999999999999"] {
+            let source = format!(
+                "digraph {{\n    n0 -> n1;\n    n0:{} -> n1:in0;\n}}\n",
+                port
+            );
+
+            let error = from_dot(&source).unwrap_err();
+
+            assert_eq!(error.line, 3);
+        }
+
+        let parsed = from_dot("digraph { n0:out1023 -> n1:in0; }").unwrap();
+        assert_eq!(parsed.sockets.len(), 1025);
+    }
+}
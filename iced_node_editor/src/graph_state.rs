@@ -0,0 +1,260 @@
+use iced::advanced::{renderer, text};
+use iced::Point;
+
+use crate::{
+    links,
+    mesh_renderer::MeshRenderer,
+    styles::{connection, node},
+    Connection, GraphNodeElement, Link, Matrix, Node,
+};
+
+// A retained-mode representation of a graph, for applications that would rather mutate a graph
+// than rebuild it from their own state in every `view()` call. Every node carries a version that
+// is bumped whenever the node changes; the generated node widgets use it to skip relayouting
+// nodes that did not change.
+//
+// Nodes can contain a nested graph of their own, with its own nodes, links and viewport. Which
+// of these graphs is shown is up to the application; `SubgraphPath` helps keeping track of it.
+#[derive(Debug, Clone)]
+pub struct GraphState<N> {
+    nodes: Vec<GraphStateNode<N>>,
+    links: Vec<Link>,
+    matrix: Matrix,
+    next_version: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct GraphStateNode<N> {
+    pub position: Point,
+    pub data: N,
+    version: u64,
+    subgraph: Option<GraphState<N>>,
+}
+
+impl<N> GraphStateNode<N> {
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn subgraph(&self) -> Option<&GraphState<N>> {
+        self.subgraph.as_ref()
+    }
+}
+
+// The node indices leading from the root graph to the graph that is currently shown, e.g. to
+// draw breadcrumbs with `GraphState::breadcrumbs`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SubgraphPath {
+    nodes: Vec<usize>,
+}
+
+impl SubgraphPath {
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // How many subgraphs deep the path goes; 0 for the root graph
+    pub fn depth(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn nodes(&self) -> &[usize] {
+        &self.nodes
+    }
+
+    // Enters the subgraph of a node of the current graph, e.g. from `on_enter_subgraph`
+    pub fn enter(&mut self, node_index: usize) {
+        self.nodes.push(node_index);
+    }
+
+    // Goes up one level, returning the index of the node that was left
+    pub fn exit(&mut self) -> Option<usize> {
+        self.nodes.pop()
+    }
+
+    // Goes up to the given depth, e.g. when a breadcrumb is clicked
+    pub fn exit_to(&mut self, depth: usize) {
+        self.nodes.truncate(depth);
+    }
+}
+
+impl<N> Default for GraphState<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> GraphState<N> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            links: Vec::new(),
+            matrix: Matrix::identity(),
+            next_version: 0,
+        }
+    }
+
+    pub fn nodes(&self) -> &[GraphStateNode<N>] {
+        &self.nodes
+    }
+
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    pub fn node(&self, index: usize) -> Option<&GraphStateNode<N>> {
+        self.nodes.get(index)
+    }
+
+    // The viewport of this graph. Every subgraph remembers its own, so that leaving and entering
+    // a subgraph again shows the same part of it.
+    pub fn matrix(&self) -> Matrix {
+        self.matrix
+    }
+
+    pub fn set_matrix(&mut self, matrix: Matrix) {
+        self.matrix = matrix;
+    }
+
+    // Adds a node and returns its index
+    pub fn add_node(&mut self, position: Point, data: N) -> usize {
+        self.push_node(position, data, None)
+    }
+
+    // Adds a node containing an empty nested graph and returns its index
+    pub fn add_subgraph_node(&mut self, position: Point, data: N) -> usize {
+        self.push_node(position, data, Some(GraphState::new()))
+    }
+
+    pub fn subgraph(&self, index: usize) -> Option<&GraphState<N>> {
+        self.nodes.get(index)?.subgraph.as_ref()
+    }
+
+    // Changing the nested graph does not change the version of the node, as it is not displayed
+    // together with its node
+    pub fn subgraph_mut(&mut self, index: usize) -> Option<&mut GraphState<N>> {
+        self.nodes.get_mut(index)?.subgraph.as_mut()
+    }
+
+    // The graph at the end of the path, or `None` if the path does not lead through nodes with
+    // subgraphs (e.g. because a node was removed)
+    pub fn graph_at(&self, path: &SubgraphPath) -> Option<&GraphState<N>> {
+        path.nodes
+            .iter()
+            .try_fold(self, |graph, index| graph.subgraph(*index))
+    }
+
+    pub fn graph_at_mut(&mut self, path: &SubgraphPath) -> Option<&mut GraphState<N>> {
+        path.nodes
+            .iter()
+            .try_fold(self, |graph, index| graph.subgraph_mut(*index))
+    }
+
+    // The nodes whose subgraphs the path enters, from the outermost to the innermost. Stops at
+    // the first index that does not lead to a subgraph.
+    pub fn breadcrumbs(&self, path: &SubgraphPath) -> Vec<&GraphStateNode<N>> {
+        let mut breadcrumbs = Vec::new();
+        let mut graph = self;
+        for index in &path.nodes {
+            let Some(node) = graph.nodes.get(*index) else {
+                break;
+            };
+            let Some(subgraph) = &node.subgraph else {
+                break;
+            };
+            breadcrumbs.push(node);
+            graph = subgraph;
+        }
+
+        breadcrumbs
+    }
+
+    // Moving a node does not change its version, as the position is not part of the cached layout
+    pub fn move_node(&mut self, index: usize, position: Point) {
+        self.nodes[index].position = position;
+    }
+
+    pub fn translate_node(&mut self, index: usize, x: f32, y: f32) {
+        let position = self.nodes[index].position;
+        self.move_node(index, Point::new(position.x + x, position.y + y));
+    }
+
+    // Gives mutable access to the node's data and marks it as changed
+    pub fn update_node(&mut self, index: usize, f: impl FnOnce(&mut N)) {
+        let version = self.bump_version();
+        let node = &mut self.nodes[index];
+        f(&mut node.data);
+        node.version = version;
+    }
+
+    // Removes a node along with all links attached to it. The indices of all following nodes
+    // shift down by one, and the remaining links are rewritten accordingly.
+    pub fn remove_node(&mut self, index: usize) -> N {
+        links::remove_node(&mut self.links, index);
+
+        self.nodes.remove(index).data
+    }
+
+    pub fn add_link(&mut self, link: Link) {
+        if !self.links.contains(&link) {
+            self.links.push(link);
+        }
+    }
+
+    pub fn remove_link(&mut self, link: &Link) {
+        self.links.retain(|other| other != link);
+    }
+
+    // Builds the graph content: one node widget per node, created by `view_node`, positioned and
+    // versioned automatically, followed by one connection per link.
+    pub fn elements<'a, Message, Theme, Renderer>(
+        &'a self,
+        view_node: impl Fn(usize, &'a N) -> Node<'a, Message, Theme, Renderer>,
+    ) -> Vec<GraphNodeElement<'a, Message, Theme, Renderer>>
+    where
+        Message: 'a,
+        Theme: node::StyleSheet + connection::StyleSheet + 'a,
+        Renderer: renderer::Renderer + text::Renderer + MeshRenderer + 'a,
+    {
+        let mut elements: Vec<GraphNodeElement<'a, Message, Theme, Renderer>> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                view_node(index, &node.data)
+                    .position(node.position)
+                    .version(node.version)
+                    .subgraph(node.subgraph.is_some())
+                    .into()
+            })
+            .collect();
+
+        elements.extend(
+            self.links
+                .iter()
+                .map(|link| Connection::new(link.clone()).into()),
+        );
+
+        elements
+    }
+
+    fn push_node(&mut self, position: Point, data: N, subgraph: Option<GraphState<N>>) -> usize {
+        let version = self.bump_version();
+        self.nodes.push(GraphStateNode {
+            position,
+            data,
+            version,
+            subgraph,
+        });
+        self.nodes.len() - 1
+    }
+
+    fn bump_version(&mut self) -> u64 {
+        self.next_version += 1;
+        self.next_version
+    }
+}
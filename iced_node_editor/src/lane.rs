@@ -0,0 +1,119 @@
+use iced::advanced::{renderer, text};
+use iced::{alignment, Background, Color, Point, Rectangle, Size};
+
+use crate::layout::align::Axis;
+use crate::matrix::Matrix;
+
+// A band across the graph, drawn below the grid, e.g. a track of a sequencer. A lane along
+// `Axis::Horizontal` spans the whole width of the graph and covers the graph-space rows from
+// `start` to `start + extent`; a lane along `Axis::Vertical` covers columns instead. Combined with
+// `Node::lock_axis`, nodes can be kept within their lane.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lane {
+    pub axis: Axis,
+    pub start: f32,
+    pub extent: f32,
+    // Drawn at the edge of the view where the lane begins, so that it stays visible while panning
+    pub label: Option<String>,
+    // If `None`, every other lane is tinted with the mid guidelines color of the style
+    pub background: Option<Color>,
+}
+
+impl Lane {
+    pub fn new(axis: Axis, start: f32, extent: f32) -> Self {
+        Lane {
+            axis,
+            start,
+            extent,
+            label: None,
+            background: None,
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn background(mut self, background: Color) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    // Whether the graph-space point lies within the lane
+    pub fn contains(&self, point: Point) -> bool {
+        let position = match self.axis {
+            Axis::Horizontal => point.y,
+            Axis::Vertical => point.x,
+        };
+        position >= self.start && position < self.start + self.extent
+    }
+
+    // The screen-space rectangle of the lane within the bounds of the graph container
+    fn screen_bounds(&self, matrix: &Matrix, bounds: Rectangle) -> Option<Rectangle> {
+        let scale = matrix.get_scale();
+        let (offset_x, offset_y) = matrix.get_translation();
+        let lane = match self.axis {
+            Axis::Horizontal => Rectangle::new(
+                Point::new(bounds.x, bounds.y + offset_y + self.start * scale),
+                Size::new(bounds.width, self.extent * scale),
+            ),
+            Axis::Vertical => Rectangle::new(
+                Point::new(bounds.x + offset_x + self.start * scale, bounds.y),
+                Size::new(self.extent * scale, bounds.height),
+            ),
+        };
+        lane.intersection(&bounds)
+    }
+}
+
+// Space between the edges of a lane and its label, in screen pixels
+const LABEL_PADDING: f32 = 6.0;
+
+pub(crate) fn draw_lanes<Renderer>(
+    renderer: &mut Renderer,
+    lanes: &[Lane],
+    matrix: &Matrix,
+    bounds: Rectangle,
+    default_background: Option<Color>,
+    text_color: Color,
+) where
+    Renderer: renderer::Renderer + text::Renderer,
+{
+    for (index, lane) in lanes.iter().enumerate() {
+        let Some(lane_bounds) = lane.screen_bounds(matrix, bounds) else {
+            continue;
+        };
+
+        let background = lane
+            .background
+            .or(default_background.filter(|_| index % 2 == 1));
+        if let Some(background) = background {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: lane_bounds,
+                    ..renderer::Quad::default()
+                },
+                Background::Color(background),
+            );
+        }
+
+        if let Some(label) = &lane.label {
+            renderer.fill_text(
+                text::Text {
+                    content: label,
+                    bounds: Size::INFINITY,
+                    size: renderer.default_size(),
+                    line_height: text::LineHeight::default(),
+                    font: renderer.default_font(),
+                    horizontal_alignment: alignment::Horizontal::Left,
+                    vertical_alignment: alignment::Vertical::Top,
+                    shaping: text::Shaping::Basic,
+                },
+                Point::new(lane_bounds.x + LABEL_PADDING, lane_bounds.y + LABEL_PADDING),
+                text_color,
+                lane_bounds,
+            );
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use iced::Rectangle;
+
+// Helpers for "Align" and "Distribute" actions on a selection of nodes. The nodes are given as
+// their graph-space bounds; only the positions of the rectangles are changed, so the application
+// can read them back into its own node positions.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    // Centers the nodes horizontally within their common bounding box
+    HorizontalCenter,
+    Right,
+    Top,
+    // Centers the nodes vertically within their common bounding box
+    VerticalCenter,
+    Bottom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+// Aligns the nodes to the respective edge (or center) of their common bounding box
+pub fn align(nodes: &mut [Rectangle], alignment: Alignment) {
+    let Some(bounds) = bounding_box(nodes) else {
+        return;
+    };
+
+    for node in nodes.iter_mut() {
+        match alignment {
+            Alignment::Left => node.x = bounds.x,
+            Alignment::HorizontalCenter => node.x = bounds.center_x() - node.width / 2.0,
+            Alignment::Right => node.x = bounds.x + bounds.width - node.width,
+            Alignment::Top => node.y = bounds.y,
+            Alignment::VerticalCenter => node.y = bounds.center_y() - node.height / 2.0,
+            Alignment::Bottom => node.y = bounds.y + bounds.height - node.height,
+        }
+    }
+}
+
+// Spaces the nodes such that the gaps between neighbouring nodes along the axis are all the
+// same. The first and the last node keep their positions, and the order of the nodes along the
+// axis is preserved.
+pub fn distribute(nodes: &mut [Rectangle], axis: Axis) {
+    if nodes.len() < 3 {
+        return;
+    }
+
+    let mut order: Vec<usize> = (0..nodes.len()).collect();
+    order.sort_by(|a, b| {
+        extent(&nodes[*a], axis)
+            .0
+            .total_cmp(&extent(&nodes[*b], axis).0)
+    });
+
+    let (span_start, _) = extent(&nodes[order[0]], axis);
+    let (last_start, last_size) = extent(&nodes[order[order.len() - 1]], axis);
+    let total_size: f32 = nodes.iter().map(|node| extent(node, axis).1).sum();
+    let gap = (last_start + last_size - span_start - total_size) / (nodes.len() - 1) as f32;
+
+    let mut position = span_start;
+    for index in order {
+        let node = &mut nodes[index];
+        match axis {
+            Axis::Horizontal => node.x = position,
+            Axis::Vertical => node.y = position,
+        }
+        position += extent(node, axis).1 + gap;
+    }
+}
+
+// The smallest rectangle containing all of the given nodes, or `None` if there are none
+pub fn bounding_box(nodes: &[Rectangle]) -> Option<Rectangle> {
+    nodes.iter().copied().reduce(|a, b| a.union(&b))
+}
+
+// The start and the size of the node along the axis
+fn extent(node: &Rectangle, axis: Axis) -> (f32, f32) {
+    match axis {
+        Axis::Horizontal => (node.x, node.width),
+        Axis::Vertical => (node.y, node.height),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::{Point, Size};
+
+    fn rectangle(x: f32, y: f32, width: f32, height: f32) -> Rectangle {
+        Rectangle::new(Point::new(x, y), Size::new(width, height))
+    }
+
+    #[test]
+    fn align_moves_the_nodes_to_the_common_bounds() {
+        let nodes = [
+            rectangle(0.0, 50.0, 100.0, 40.0),
+            rectangle(200.0, 0.0, 50.0, 20.0),
+        ];
+
+        let aligned = |alignment| {
+            let mut nodes = nodes;
+            align(&mut nodes, alignment);
+            nodes.map(|node| (node.x, node.y))
+        };
+        assert_eq!(aligned(Alignment::Left), [(0.0, 50.0), (0.0, 0.0)]);
+        assert_eq!(aligned(Alignment::Right), [(150.0, 50.0), (200.0, 0.0)]);
+        assert_eq!(
+            aligned(Alignment::HorizontalCenter),
+            [(75.0, 50.0), (100.0, 0.0)]
+        );
+        assert_eq!(aligned(Alignment::Top), [(0.0, 0.0), (200.0, 0.0)]);
+        assert_eq!(aligned(Alignment::Bottom), [(0.0, 50.0), (200.0, 70.0)]);
+        assert_eq!(
+            aligned(Alignment::VerticalCenter),
+            [(0.0, 25.0), (200.0, 35.0)]
+        );
+    }
+
+    #[test]
+    fn distribute_evens_out_the_gaps() {
+        // Given out of order, and with different sizes
+        let mut nodes = [
+            rectangle(300.0, 0.0, 100.0, 10.0),
+            rectangle(50.0, 0.0, 20.0, 10.0),
+            rectangle(0.0, 0.0, 40.0, 10.0),
+        ];
+
+        distribute(&mut nodes, Axis::Horizontal);
+
+        let xs = nodes.map(|node| node.x);
+        assert_eq!(xs, [300.0, 160.0, 0.0]);
+        // The other axis is left alone
+        assert!(nodes.iter().all(|node| node.y == 0.0));
+    }
+
+    #[test]
+    fn distribute_needs_three_nodes() {
+        let mut nodes = [
+            rectangle(0.0, 0.0, 10.0, 10.0),
+            rectangle(0.0, 100.0, 10.0, 10.0),
+        ];
+        let before = nodes;
+
+        distribute(&mut nodes, Axis::Vertical);
+
+        assert_eq!(nodes, before);
+        assert_eq!(bounding_box(&nodes), Some(rectangle(0.0, 0.0, 10.0, 110.0)));
+        assert_eq!(bounding_box(&[]), None);
+    }
+}
@@ -0,0 +1,76 @@
+use iced::{Rectangle, Vector};
+
+// A simple layered ("Sugiyama-style") auto-layout. Every node is put into the column after the
+// furthest of its predecessors, and within a column the nodes are ordered by the average row of
+// their predecessors to reduce crossings. Only the positions of the rectangles are changed; the
+// edges are pairs of node indices, from the source to the target.
+pub fn layered(nodes: &mut [Rectangle], edges: &[(usize, usize)], spacing: Vector) {
+    if nodes.is_empty() {
+        return;
+    }
+
+    let edges: Vec<(usize, usize)> = edges
+        .iter()
+        .copied()
+        .filter(|(from, to)| from != to && *from < nodes.len() && *to < nodes.len())
+        .collect();
+
+    // Longest-path layering. Cycles would make this diverge, so the layers are capped at the
+    // node count, which is enough for any acyclic graph.
+    let mut layers = vec![0; nodes.len()];
+    for _ in 0..nodes.len() {
+        let mut changed = false;
+        for (from, to) in &edges {
+            if layers[*to] <= layers[*from] && layers[*from] + 1 < nodes.len() {
+                layers[*to] = layers[*from] + 1;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    let layer_count = layers.iter().max().map_or(0, |max| max + 1);
+    let mut columns: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+    for (node, layer) in layers.iter().enumerate() {
+        columns[*layer].push(node);
+    }
+
+    let mut rows = vec![0.0; nodes.len()];
+    let mut x = 0.0;
+    for column in &mut columns {
+        column.sort_by(|a, b| {
+            barycenter(*a, &edges, &rows).total_cmp(&barycenter(*b, &edges, &rows))
+        });
+
+        let mut y = 0.0;
+        let mut width: f32 = 0.0;
+        for (row, node) in column.iter().enumerate() {
+            rows[*node] = row as f32;
+            nodes[*node].x = x;
+            nodes[*node].y = y;
+            y += nodes[*node].height + spacing.y;
+            width = width.max(nodes[*node].width);
+        }
+
+        x += width + spacing.x;
+    }
+}
+
+// The average row of the node's predecessors in earlier columns, or its index if there are none
+fn barycenter(node: usize, edges: &[(usize, usize)], rows: &[f32]) -> f32 {
+    let (sum, count) = edges
+        .iter()
+        .filter(|(_, to)| *to == node)
+        .fold((0.0, 0), |(sum, count), (from, _)| {
+            (sum + rows[*from], count + 1)
+        });
+
+    if count == 0 {
+        node as f32
+    } else {
+        sum / count as f32
+    }
+}
@@ -1,27 +1,86 @@
 #![allow(clippy::type_complexity)]
 
+mod bindings;
+mod clipboard;
 mod connection;
+mod editor_controller;
+pub mod graph;
 mod graph_container;
+pub mod graph_export;
+pub mod graph_io;
+mod graph_state;
+mod lane;
+pub mod layout;
+pub mod links;
 mod matrix;
 mod mesh_renderer;
 mod node;
 mod node_element;
+mod node_palette;
+mod node_template;
+mod operation;
+mod routing;
+mod selection;
+mod size_mode;
 pub mod styles;
 
+pub use matrix::Easing;
 pub use matrix::Matrix;
+pub use matrix::SyncedMatrix;
+pub use matrix::ViewportChange;
 
 pub use connection::connection;
 pub use graph_container::graph_container;
+pub use graph_container::graph_to_screen;
+pub use graph_container::grid_cell_of;
+pub use graph_container::grid_cell_origin;
+pub use graph_container::screen_to_graph;
+pub use graph_container::snap_point;
 pub use node::node;
+pub use node::nodes_from_iter;
+pub use node_palette::node_palette;
+pub use operation::focus_node;
 
+pub use bindings::Bindings;
+pub use bindings::WheelAction;
+pub use clipboard::{duplicate, SubgraphClipboard};
 pub use connection::Connection;
 pub use connection::Endpoint;
+pub use connection::FlowAnimation;
+pub use connection::FlowDirection;
 pub use connection::Link;
 pub use connection::LogicalEndpoint;
+pub use connection::Quality;
+pub use connection::RelativeAnchor;
+pub use connection::Routing;
+pub use connection::Tangent;
+pub use editor_controller::EditorController;
+pub use editor_controller::EditorEvent;
 pub use graph_container::GraphContainer;
+pub use graph_container::Interaction;
+pub use graph_container::RejectionReason;
+pub use graph_state::GraphState;
+pub use graph_state::GraphStateNode;
+pub use graph_state::SubgraphPath;
+pub use lane::Lane;
+pub use node::Anchor;
+pub use node::BadgePosition;
+pub use node::DragRegion;
 pub use node::Node;
+pub use node::NodeStatus;
 pub use node::Socket;
+pub use node::SocketLayout;
 pub use node::SocketRole;
+pub use node::SocketRowStyle;
 pub use node::SocketSide;
 pub use node_element::GraphNodeElement;
 pub use node_element::ScalableWidget;
+pub use node_element::SocketPosition;
+pub use node_palette::NodePalette;
+pub use node_palette::PaletteEntry;
+pub use node_template::NodeTemplate;
+pub use selection::Marquee;
+pub use selection::Selection;
+pub use size_mode::SizeMode;
+pub use styles::graph_container::GridStyle;
+pub use styles::preset::Preset;
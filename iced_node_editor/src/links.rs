@@ -0,0 +1,27 @@
+use crate::Link;
+
+// Keeping the links of a graph in sync with its nodes, for applications that store both
+// themselves. Nodes are referred to by their index, so removing a node shifts the indices of all
+// following nodes down, which every link attached to them has to follow.
+
+// Drops the links attached to the node with the given index and rewrites the others for the
+// removal of the node, i.e. decrements every node index greater than `node_index`
+pub fn remove_node(links: &mut Vec<Link>, node_index: usize) {
+    *links = links
+        .iter()
+        .filter_map(|link| link.after_node_removed(node_index))
+        .collect();
+}
+
+// Like `remove_node`, for removing several nodes at once. The indices refer to the nodes before
+// any of them is removed, and may be given in any order.
+pub fn remove_nodes(links: &mut Vec<Link>, node_indices: &[usize]) {
+    let mut removed = node_indices.to_vec();
+    removed.sort_unstable();
+    removed.dedup();
+
+    // Removing from the back keeps the indices of the nodes still to be removed intact
+    for node_index in removed.into_iter().rev() {
+        remove_node(links, node_index);
+    }
+}
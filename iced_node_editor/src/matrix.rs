@@ -1,4 +1,6 @@
-#[derive(Clone, Copy)]
+use iced::Point;
+
+#[derive(Debug, Clone, Copy)]
 pub struct Matrix {
     a11: f32,
     a12: f32,
@@ -62,6 +64,68 @@ impl Matrix {
         }
     }
 
+    pub fn scale_non_uniform(&self, x: f32, y: f32) -> Matrix {
+        self.pre_multiply(x, 0.0, 0.0, 0.0, y, 0.0)
+    }
+
+    pub fn rotate(&self, angle: f32) -> Matrix {
+        let (sin, cos) = angle.sin_cos();
+        self.pre_multiply(cos, -sin, 0.0, sin, cos, 0.0)
+    }
+
+    // Returns the inverse of the affine part of this matrix, or `None` if it is singular
+    // (e.g. after scaling by zero).
+    pub fn invert(&self) -> Option<Matrix> {
+        let det = self.a11 * self.a22 - self.a12 * self.a21;
+        if det.abs() <= f32::EPSILON {
+            return None;
+        }
+
+        let b11 = self.a22 / det;
+        let b12 = -self.a12 / det;
+        let b21 = -self.a21 / det;
+        let b22 = self.a11 / det;
+
+        Some(Matrix {
+            a11: b11,
+            a12: b12,
+            a13: -(b11 * self.a13 + b12 * self.a23),
+
+            a21: b21,
+            a22: b22,
+            a23: -(b21 * self.a13 + b22 * self.a23),
+
+            a31: 0.0,
+            a32: 0.0,
+            a33: 1.0,
+        })
+    }
+
+    pub fn transform_point(&self, point: Point) -> Point {
+        Point::new(
+            self.a11 * point.x + self.a12 * point.y + self.a13,
+            self.a21 * point.x + self.a22 * point.y + self.a23,
+        )
+    }
+
+    // Applies the affine transformation given by the 2x3 matrix [b11 b12 b13; b21 b22 b23]
+    // after this one.
+    fn pre_multiply(&self, b11: f32, b12: f32, b13: f32, b21: f32, b22: f32, b23: f32) -> Matrix {
+        Matrix {
+            a11: b11 * self.a11 + b12 * self.a21,
+            a12: b11 * self.a12 + b12 * self.a22,
+            a13: b11 * self.a13 + b12 * self.a23 + b13,
+
+            a21: b21 * self.a11 + b22 * self.a21,
+            a22: b21 * self.a12 + b22 * self.a22,
+            a23: b21 * self.a13 + b22 * self.a23 + b23,
+
+            a31: self.a31,
+            a32: self.a32,
+            a33: self.a33,
+        }
+    }
+
     pub fn get_translation(&self) -> (f32, f32) {
         (self.a13, self.a23)
     }
@@ -69,4 +133,119 @@ impl Matrix {
     pub fn get_scale(&self) -> f32 {
         (self.a11 * self.a11 + self.a12 * self.a12).sqrt()
     }
+
+    // Component-wise linear interpolation between `self` (t = 0) and `target` (t = 1).
+    // For the translate/scale matrices used by the graph container this interpolates the
+    // translation and scale independently, which is what a zoom or pan animation needs.
+    // Pass `t` through an `Easing` first for non-linear transitions.
+    pub fn interpolate(&self, target: &Matrix, t: f32) -> Matrix {
+        let t = t.clamp(0.0, 1.0);
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+
+        Matrix {
+            a11: lerp(self.a11, target.a11),
+            a12: lerp(self.a12, target.a12),
+            a13: lerp(self.a13, target.a13),
+
+            a21: lerp(self.a21, target.a21),
+            a22: lerp(self.a22, target.a22),
+            a23: lerp(self.a23, target.a23),
+
+            a31: lerp(self.a31, target.a31),
+            a32: lerp(self.a32, target.a32),
+            a33: lerp(self.a33, target.a33),
+        }
+    }
+}
+
+// A change of the viewport, as reported by the `on_translate` and `on_scale` callbacks of a
+// graph container
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ViewportChange {
+    Translate(f32, f32),
+    // The point to zoom around, relative to the container, and the scroll amount
+    Scale(f32, f32, f32),
+}
+
+// A viewport shared by several graph containers, e.g. a main view and a view for inspecting
+// data. Every container is connected with `GraphContainer::synced_matrix`, and the changes it
+// reports are passed to `update`, so that panning or zooming any of them moves all of them.
+#[derive(Debug, Clone, Copy)]
+pub struct SyncedMatrix {
+    matrix: Matrix,
+    zoom_factor: f32,
+}
+
+impl Default for SyncedMatrix {
+    fn default() -> Self {
+        Self::new(Matrix::identity())
+    }
+}
+
+impl SyncedMatrix {
+    pub fn new(matrix: Matrix) -> Self {
+        SyncedMatrix {
+            matrix,
+            zoom_factor: 1.2,
+        }
+    }
+
+    // How much a single step of the mouse wheel zooms in or out
+    pub fn zoom_factor(mut self, zoom_factor: f32) -> Self {
+        self.zoom_factor = zoom_factor;
+        self
+    }
+
+    pub fn matrix(&self) -> Matrix {
+        self.matrix
+    }
+
+    pub fn set_matrix(&mut self, matrix: Matrix) {
+        self.matrix = matrix;
+    }
+
+    pub fn update(&mut self, change: ViewportChange) {
+        match change {
+            ViewportChange::Translate(x, y) => self.matrix = self.matrix.translate(x, y),
+            ViewportChange::Scale(x, y, amount) => {
+                let factor = if amount > 0.0 {
+                    self.zoom_factor
+                } else if amount < 0.0 {
+                    1.0 / self.zoom_factor
+                } else {
+                    return;
+                };
+
+                self.matrix = self.matrix.translate(-x, -y).scale(factor).translate(x, y);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
 }
@@ -1,12 +1,13 @@
 use iced::advanced::graphics::mesh::{Indexed, SolidVertex2D};
-use iced::{Point, Size};
+use iced::advanced::Renderer;
+use iced::{Point, Size, Vector};
 
 pub trait MeshRenderer {
     fn draw_buffers(&mut self, buffers: Indexed<SolidVertex2D>);
 }
 
 impl MeshRenderer for iced::Renderer {
-    fn draw_buffers(&mut self, buffers: Indexed<SolidVertex2D>) {
+    fn draw_buffers(&mut self, mut buffers: Indexed<SolidVertex2D>) {
         let min = buffers
             .vertices
             .iter()
@@ -24,7 +25,16 @@ impl MeshRenderer for iced::Renderer {
         let size = Size::new(max.x - min.x, max.y - min.y);
 
         if size.width >= 1.0 && size.height >= 1.0 {
-            self.draw_mesh(iced::advanced::graphics::Mesh::Solid { buffers, size });
+            // Meshes are clipped to the rectangle from the origin to `size`, so move the
+            // vertices such that their bounding box starts at the origin
+            for vertex in &mut buffers.vertices {
+                vertex.position[0] -= min.x;
+                vertex.position[1] -= min.y;
+            }
+
+            self.with_translation(Vector::new(min.x, min.y), |renderer| {
+                renderer.draw_mesh(iced::advanced::graphics::Mesh::Solid { buffers, size });
+            });
         }
     }
 }
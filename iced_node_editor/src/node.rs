@@ -1,13 +1,22 @@
+use iced::advanced::graphics::color::pack;
+use iced::advanced::graphics::mesh::{Indexed, SolidVertex2D};
 use iced::advanced::widget::Tree;
-use iced::advanced::{renderer, widget, Clipboard, Layout, Shell, Widget};
+use iced::advanced::{overlay, renderer, text, widget, Clipboard, Layout, Shell, Widget};
 use iced::{
-    alignment, event, mouse, Alignment, Background, Border, Color, Element, Event, Length, Padding,
-    Pixels, Point, Rectangle, Size, Vector,
+    alignment, event, keyboard, mouse,
+    time::{Duration, Instant},
+    window, Alignment, Background, Border, Color, Element, Event, Length, Padding, Pixels, Point,
+    Rectangle, Size, Vector,
 };
+use std::collections::HashMap;
 
 use crate::{
-    node_element::{GraphNodeElement, ScalableWidget},
-    styles::node::StyleSheet,
+    connection::{generate_spline, line_to_polygon, LogicalEndpoint},
+    layout::align::Axis,
+    node_element::{snap_to_pixel, GraphNodeElement, ScalableWidget},
+    operation::FocusTarget,
+    size_mode::SizeMode,
+    styles::node::{dim, dimmed, Appearance, StyleSheet},
 };
 
 pub struct Node<'a, Message, Theme, Renderer>
@@ -21,59 +30,469 @@ where
     max_height: f32,
     padding: Padding,
     style: Theme::Style,
+    appearance: Option<Appearance>,
     content: Element<'a, Message, Theme, Renderer>,
+    header: Option<Element<'a, Message, Theme, Renderer>>,
+    error: Option<Element<'a, Message, Theme, Renderer>>,
+    drag_region: Option<DragRegion>,
+    id: Option<widget::Id>,
     sockets: Vec<Socket<'a, Message, Theme, Renderer>>,
     socket_spacing: f32,
+    min_socket_row_height: f32,
+    socket_layout: SocketLayout,
     position: Point,
+    anchor: Anchor,
     horizontal_alignment: alignment::Horizontal,
     vertical_alignment: alignment::Vertical,
     on_translate: Option<Box<dyn Fn((f32, f32)) -> Message + 'a>>,
+    on_move: Option<Box<dyn Fn(Point) -> Message + 'a>>,
+    on_duplicate: Option<Box<dyn Fn(usize, Point) -> Message + 'a>>,
+    duplicate_modifier: keyboard::Modifiers,
+    locked_axes: Vec<Axis>,
+    position_bounds: Option<Rectangle>,
+    on_add_pin: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+    on_add_input: Option<Box<dyn Fn() -> Message + 'a>>,
+    on_add_output: Option<Box<dyn Fn() -> Message + 'a>>,
+    locked: bool,
+    disabled: bool,
+    version: Option<u64>,
+    subgraph: bool,
+    badges: Vec<(BadgePosition, Element<'a, Message, Theme, Renderer>)>,
+    status: NodeStatus,
+    bypassed: bool,
+    on_bypass_toggle: Option<Box<dyn Fn(bool) -> Message + 'a>>,
+    bypass_shortcut: (keyboard::Key, keyboard::Modifiers),
 }
 
+// Settings added after the original public fields are only set through the builder methods, so
+// that more of them can be added without breaking applications
 pub struct Socket<'a, Message, Theme, Renderer> {
     pub role: SocketRole,
+    // Lets links refer to the socket with `Endpoint::NamedSocket` instead of its index. Names
+    // should be unique within a node.
+    name: Option<String>,
 
     pub min_height: f32,
     pub max_height: f32,
 
     pub blob_side: SocketSide,
-    pub blob_radius: f32,
+    pub blob_radius: SizeMode,
+    // In the same units as `blob_radius`
     pub blob_border_radius: f32,
     pub blob_color: Color,
     pub blob_border_color: Option<Color>,
+    // Radius of the area around the blob center that reacts to the mouse, in screen pixels
+    // regardless of the graph scale. It is never smaller than `blob_radius`.
+    hit_radius: Option<f32>,
+    // If set, the socket is an array of this many pins, each of which can be connected on its
+    // own. Every pin counts as a separate socket for `LogicalEndpoint::socket_index`.
+    pins: Option<usize>,
+    // Background and padding of the row around the socket content
+    row_style: SocketRowStyle,
+    // If set, this element (e.g. an icon) is drawn in place of the colored blob, centered on the
+    // blob position. It is laid out within the blob size, and the blob still acts as the grab
+    // point for connections, so the element does not receive any events.
+    blob: Option<Element<'a, Message, Theme, Renderer>>,
 
     pub content: Element<'a, Message, Theme, Renderer>,
     pub content_alignment: alignment::Horizontal,
 }
 
+// Lets socket rows look like list items. The row spans the column of the socket, the blob is
+// drawn on top of it.
+#[derive(Debug, Clone, Copy)]
+pub struct SocketRowStyle {
+    pub background: Option<Color>,
+    // Used instead of `background` while the cursor is over the row
+    pub hovered_background: Option<Color>,
+    pub border_radius: f32,
+    // Space between the edges of the row and the socket content
+    pub padding: Padding,
+}
+
+impl Default for SocketRowStyle {
+    fn default() -> Self {
+        Self {
+            background: None,
+            hovered_background: None,
+            border_radius: 0.0,
+            padding: Padding::ZERO,
+        }
+    }
+}
+
 impl<'a, Message, Theme, Renderer> Socket<'a, Message, Theme, Renderer> {
+    // A socket with a round gray blob. Input sockets are put on the left side of the node,
+    // output sockets on the right side, with the content aligned to the same side.
+    pub fn new(
+        role: SocketRole,
+        content: impl Into<Element<'a, Message, Theme, Renderer>>,
+    ) -> Self {
+        let (blob_side, content_alignment) = match role {
+            SocketRole::In => (SocketSide::Left, alignment::Horizontal::Left),
+            SocketRole::Out => (SocketSide::Right, alignment::Horizontal::Right),
+        };
+
+        Socket {
+            role,
+            name: None,
+            min_height: 0.0,
+            max_height: f32::INFINITY,
+            blob_side,
+            blob_radius: SizeMode::Screen(DEFAULT_BLOB_RADIUS),
+            blob_border_radius: DEFAULT_BLOB_RADIUS,
+            blob_color: Color::from_rgb(0.5, 0.5, 0.5),
+            blob_border_color: None,
+            hit_radius: None,
+            pins: None,
+            row_style: SocketRowStyle::default(),
+            blob: None,
+            content: content.into(),
+            content_alignment,
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn min_height(mut self, min_height: f32) -> Self {
+        self.min_height = min_height;
+        self
+    }
+
+    pub fn max_height(mut self, max_height: f32) -> Self {
+        self.max_height = max_height;
+        self
+    }
+
+    pub fn blob_side(mut self, blob_side: SocketSide) -> Self {
+        self.blob_side = blob_side;
+        self
+    }
+
+    // Also makes the blob round again; use `blob_border_radius` afterwards for other shapes
+    pub fn blob_radius(mut self, blob_radius: impl Into<SizeMode>) -> Self {
+        self.blob_radius = blob_radius.into();
+        self.blob_border_radius = self.blob_radius.value();
+        self
+    }
+
+    pub fn blob_border_radius(mut self, blob_border_radius: f32) -> Self {
+        self.blob_border_radius = blob_border_radius;
+        self
+    }
+
+    pub fn blob_color(mut self, blob_color: Color) -> Self {
+        self.blob_color = blob_color;
+        self
+    }
+
+    pub fn blob_border_color(mut self, blob_border_color: Color) -> Self {
+        self.blob_border_color = Some(blob_border_color);
+        self
+    }
+
+    pub fn hit_radius(mut self, hit_radius: f32) -> Self {
+        self.hit_radius = Some(hit_radius);
+        self
+    }
+
+    pub fn pins(mut self, pins: usize) -> Self {
+        self.pins = Some(pins);
+        self
+    }
+
+    pub fn row_style(mut self, row_style: SocketRowStyle) -> Self {
+        self.row_style = row_style;
+        self
+    }
+
+    pub fn blob(mut self, blob: impl Into<Element<'a, Message, Theme, Renderer>>) -> Self {
+        self.blob = Some(blob.into());
+        self
+    }
+
+    pub fn content_alignment(mut self, content_alignment: alignment::Horizontal) -> Self {
+        self.content_alignment = content_alignment;
+        self
+    }
+
+    // Blobs on the top or bottom side are placed at the middle of the edge, at `center_y`
     pub fn blob_rect(&self, node_left: f32, node_width: f32, center_y: f32) -> Rectangle {
-        let x = match self.blob_side {
-            SocketSide::Left => node_left,
-            SocketSide::Right => node_left + node_width,
+        let node_bounds =
+            Rectangle::new(Point::new(node_left, center_y), Size::new(node_width, 0.0));
+        self.blob_rect_on_edge(node_bounds, center_y, 0.5, 1.0)
+    }
+
+    // `row_center_y` is used for blobs on the left or right side, `edge_fraction` (the relative
+    // position along the edge) for blobs on the top or bottom side.
+    pub fn blob_rect_on_edge(
+        &self,
+        node_bounds: Rectangle,
+        row_center_y: f32,
+        edge_fraction: f32,
+        scale: f32,
+    ) -> Rectangle {
+        let radius = self.blob_radius.resolve(scale);
+        let center = match self.blob_side {
+            SocketSide::Left => Point::new(node_bounds.x, row_center_y),
+            SocketSide::Right => Point::new(node_bounds.x + node_bounds.width, row_center_y),
+            SocketSide::Top => Point::new(
+                node_bounds.x + node_bounds.width * edge_fraction,
+                node_bounds.y,
+            ),
+            SocketSide::Bottom => Point::new(
+                node_bounds.x + node_bounds.width * edge_fraction,
+                node_bounds.y + node_bounds.height,
+            ),
         };
         Rectangle::new(
-            Point::new(x - self.blob_radius, center_y - self.blob_radius),
-            Size::new(self.blob_radius * 2.0, self.blob_radius * 2.0),
+            Point::new(center.x - radius, center.y - radius),
+            Size::new(radius * 2.0, radius * 2.0),
+        )
+    }
+
+    pub fn pin_count(&self) -> usize {
+        self.pins.unwrap_or(1)
+    }
+
+    // The blob of the `pin`-th out of `slots` pins. The pins are lined up along the side of the
+    // node, centered on the position a single blob would have.
+    pub fn pin_rect(
+        &self,
+        node_bounds: Rectangle,
+        row_center_y: f32,
+        edge_fraction: f32,
+        pin: usize,
+        slots: usize,
+        scale: f32,
+    ) -> Rectangle {
+        let offset = (pin as f32 - (slots as f32 - 1.0) / 2.0)
+            * self.blob_radius.resolve(scale)
+            * PIN_SPACING;
+        let blob_rect = self.blob_rect_on_edge(node_bounds, row_center_y, edge_fraction, scale);
+        match self.blob_side {
+            SocketSide::Left | SocketSide::Right => blob_rect + Vector::new(0.0, offset),
+            SocketSide::Top | SocketSide::Bottom => blob_rect + Vector::new(offset, 0.0),
+        }
+    }
+
+    pub fn hit_rect(&self, blob_rect: Rectangle) -> Rectangle {
+        let blob_radius = blob_rect.width / 2.0;
+        let radius = self
+            .hit_radius
+            .map_or(blob_radius, |hit_radius| hit_radius.max(blob_radius));
+        let center = blob_rect.center();
+        Rectangle::new(
+            Point::new(center.x - radius, center.y - radius),
+            Size::new(radius * 2.0, radius * 2.0),
         )
     }
 }
 
+// Blob radius of sockets created with `Socket::new`
+pub(crate) const DEFAULT_BLOB_RADIUS: f32 = 5.0;
+// Distance between the centers of neighbouring pins of a pin array, in blob radii
+const PIN_SPACING: f32 = 3.0;
+// Side length of the "add socket" buttons in the footer of a node, in screen pixels
+const ADD_SOCKET_BUTTON_SIZE: f32 = 10.0;
+// How long the border of a node flashes after it was focused with `focus_node`
+const FLASH_DURATION: Duration = Duration::from_millis(600);
+// Minimum border width of a flashing node, in screen pixels
+const FLASH_BORDER_WIDTH: f32 = 3.0;
+// How long one pulse of the border of a running node takes
+const PULSE_PERIOD: f32 = 1.2;
+// How far the border of a running node fades at the low point of a pulse
+const PULSE_FADE: f32 = 0.6;
+// How far the stack behind a node with a subgraph is offset, in screen pixels
+const SUBGRAPH_STACK_OFFSET: f32 = 4.0;
+// Width of the pass-through line of a bypassed node, in graph units
+const BYPASS_LINE_WIDTH: f32 = 3.0;
+// Number of points the pass-through line of a bypassed node is made of
+const BYPASS_LINE_SEGMENTS: usize = 20;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SocketSide {
     Left,
     Right,
+    Top,
+    Bottom,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl SocketSide {
+    // The direction in which a connection leaves a socket on this side
+    pub(crate) fn outward_direction(&self) -> Vector {
+        match self {
+            SocketSide::Left => Vector::new(-1.0, 0.0),
+            SocketSide::Right => Vector::new(1.0, 0.0),
+            SocketSide::Top => Vector::new(0.0, -1.0),
+            SocketSide::Bottom => Vector::new(0.0, 1.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SocketRole {
     In,
     Out,
 }
 
+// Where a node can be grabbed to drag it around
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragRegion {
+    // Only the header; a node without a header cannot be dragged
+    Header,
+    // Anywhere on the node, as long as the content does not capture the click
+    Anywhere,
+    // The node cannot be dragged at all
+    None,
+}
+
+// The point of a node that its position refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Center,
+}
+
+// The execution state of a node, e.g. while a dataflow graph is evaluated. Every status other
+// than `Idle` has its own appearance in the style sheet, see `StyleSheet::status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NodeStatus {
+    #[default]
+    Idle,
+    // The border pulses while the node is running
+    Running,
+    Error,
+    Done,
+}
+
+// The corner of a node that a badge is centered on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadgePosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl BadgePosition {
+    fn corner(&self, size: Size) -> Point {
+        match self {
+            BadgePosition::TopLeft => Point::ORIGIN,
+            BadgePosition::TopRight => Point::new(size.width, 0.0),
+            BadgePosition::BottomLeft => Point::new(0.0, size.height),
+            BadgePosition::BottomRight => Point::new(size.width, size.height),
+        }
+    }
+}
+
+// How the socket rows below the node content are arranged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SocketLayout {
+    // Every socket gets its own row
+    #[default]
+    Rows,
+    // Input sockets are stacked in the left column and output sockets in the right one, such
+    // that the n-th input shares a row with the n-th output
+    Columns,
+    // Sockets fill a grid with the given number of columns, row by row
+    Grid {
+        columns: usize,
+    },
+}
+
+impl SocketLayout {
+    // Returns the (row, column) for each socket, and the total number of columns
+    fn placements(&self, roles: &[SocketRole]) -> (Vec<(usize, usize)>, usize) {
+        match self {
+            SocketLayout::Rows => ((0..roles.len()).map(|i| (i, 0)).collect(), 1),
+            SocketLayout::Columns => {
+                let mut in_count = 0;
+                let mut out_count = 0;
+                let placements = roles
+                    .iter()
+                    .map(|role| match role {
+                        SocketRole::In => {
+                            in_count += 1;
+                            (in_count - 1, 0)
+                        }
+                        SocketRole::Out => {
+                            out_count += 1;
+                            (out_count - 1, 1)
+                        }
+                    })
+                    .collect();
+                (placements, 2)
+            }
+            SocketLayout::Grid { columns } => {
+                let columns = (*columns).max(1);
+                (
+                    (0..roles.len())
+                        .map(|i| (i / columns, i % columns))
+                        .collect(),
+                    columns,
+                )
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 struct NodeState {
     drag_start_position: Option<Point>,
+    hovered: bool,
+    // Set by `focus_node`, the border is highlighted until then
+    flash_until: Option<Instant>,
+    layout_cache: Option<LayoutCache>,
+    modifiers: keyboard::Modifiers,
+    // While dragging a copy of the node, how far it has been moved, in screen space
+    duplicate_offset: Option<Vector>,
+    // Where the node was when the drag started, and how far it has been dragged since, in graph
+    // space. Messages from `on_move` are based on these rather than on `position`, which is not
+    // updated until the application has handled the previous message.
+    drag_origin: Point,
+    drag_offset: Vector,
+    // The scale of the graph at the last layout
+    scale: f32,
+    // The position of the node in the content of the graph container at the last layout
+    index: usize,
+    // The graph-space size of the node at the last layout
+    size: Size,
+    // Set by the graph container when zoomed out too far, see `GraphContainer::min_socket_scale`
+    blobs_hidden: bool,
+    // When the node started running, and how long ago that was at the last frame, in seconds
+    pulse_start: Option<Instant>,
+    pulse_time: f32,
+}
+
+#[derive(Debug)]
+struct LayoutCache {
+    key: LayoutCacheKey,
+    layout: NodeLayout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LayoutCacheKey {
+    version: u64,
+    scale: f32,
+    min: Size,
+    max: Size,
+}
+
+// The layout of a node positioned at the origin, along with its socket blobs
+#[derive(Debug, Clone)]
+struct NodeLayout {
+    node: iced::advanced::layout::Node,
+    in_sockets: Vec<Rectangle>,
+    out_sockets: Vec<Rectangle>,
+    in_hit_areas: Vec<Rectangle>,
+    out_hit_areas: Vec<Rectangle>,
+    in_sides: Vec<SocketSide>,
+    out_sides: Vec<SocketSide>,
 }
 
 impl<'a, Message, Theme, Renderer> Node<'a, Message, Theme, Renderer>
@@ -92,13 +511,41 @@ where
             max_height: f32::MAX,
             padding: Padding::ZERO,
             style: Default::default(),
+            appearance: None,
             content: content.into(),
+            header: None,
+            error: None,
+            drag_region: None,
+            id: None,
             sockets: vec![],
             socket_spacing: 0.0,
+            min_socket_row_height: 0.0,
+            socket_layout: SocketLayout::default(),
             position: Point::new(0.0, 0.0),
+            anchor: Anchor::TopLeft,
             horizontal_alignment: alignment::Horizontal::Left,
             vertical_alignment: alignment::Vertical::Top,
             on_translate: None,
+            on_move: None,
+            on_duplicate: None,
+            duplicate_modifier: keyboard::Modifiers::CTRL,
+            locked_axes: vec![],
+            position_bounds: None,
+            on_add_pin: None,
+            on_add_input: None,
+            on_add_output: None,
+            locked: false,
+            disabled: false,
+            version: None,
+            subgraph: false,
+            badges: vec![],
+            status: NodeStatus::Idle,
+            bypassed: false,
+            on_bypass_toggle: None,
+            bypass_shortcut: (
+                keyboard::Key::Character("m".into()),
+                keyboard::Modifiers::empty(),
+            ),
         }
     }
 
@@ -110,11 +557,155 @@ where
         self
     }
 
+    // Called while the node is dragged with the graph-space position it is dragged to, so that
+    // it can simply be stored. Unlike the screen-space deltas of `on_translate`, the positions do
+    // not need to be scaled or accumulated.
+    pub fn on_move<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(Point) -> Message,
+    {
+        self.on_move = Some(Box::new(f));
+        self
+    }
+
+    // Dragging the node while the duplicate modifier (Ctrl by default) is held leaves it in place
+    // and shows where a copy would go instead. On release, `f` is called with the graph-space
+    // position for the copy, along with the index of this node in the graph container.
+    pub fn on_duplicate<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(usize, Point) -> Message,
+    {
+        self.on_duplicate = Some(Box::new(f));
+        self
+    }
+
+    // The modifiers that have to be held when a drag starts to duplicate the node
+    pub fn duplicate_modifier(mut self, modifiers: keyboard::Modifiers) -> Self {
+        self.duplicate_modifier = modifiers;
+        self
+    }
+
+    // Keeps dragging from moving the node along the given axis, so that it can only slide along
+    // the other one. Locking both axes has the same effect as `DragRegion::None`.
+    pub fn lock_axis(mut self, axis: Axis) -> Self {
+        self.locked_axes.push(axis);
+        self
+    }
+
+    // Keeps the node within the given graph-space rectangle (e.g. a lane) while it is dragged.
+    // A node that is larger than the rectangle sticks to its top left corner.
+    pub fn position_bounds(mut self, bounds: Rectangle) -> Self {
+        self.position_bounds = Some(bounds);
+        self
+    }
+
+    // Shows a "+" button after the pins of every socket with `pins` set, which calls `f` with
+    // the index of the socket (in the list passed to `sockets`) when clicked
+    pub fn on_add_pin<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(usize) -> Message,
+    {
+        self.on_add_pin = Some(Box::new(f));
+        self
+    }
+
+    // Shows a "+" button in a footer below the sockets, on the side of the sockets with the given
+    // role, which publishes the message returned by `f` when clicked. Can be set for both roles.
+    pub fn on_add_socket<F>(mut self, role: SocketRole, f: F) -> Self
+    where
+        F: 'a + Fn() -> Message,
+    {
+        match role {
+            SocketRole::In => self.on_add_input = Some(Box::new(f)),
+            SocketRole::Out => self.on_add_output = Some(Box::new(f)),
+        }
+        self
+    }
+
+    // A locked node can neither be dragged, nor can connections be made to or from its sockets.
+    // Its content still receives events.
+    pub fn locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    // A disabled node is locked, drawn with the disabled appearance of its style, and ignores
+    // all events.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    // Enables caching of the node's layout between frames. As long as the version (and the
+    // scale of the graph) stays the same, the node is not laid out again, only moved; so the
+    // version must be changed whenever anything about the node apart from its position changes.
+    pub fn version(mut self, version: u64) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    // Marks the node as containing a nested graph. It is drawn as a stack, and double-clicking
+    // it produces the `on_enter_subgraph` message of the graph container.
+    pub fn subgraph(mut self, subgraph: bool) -> Self {
+        self.subgraph = subgraph;
+        self
+    }
+
+    // A small element (e.g. an error icon, a progress spinner or a breakpoint dot) centered on
+    // a corner of the node. Badges are drawn above everything else of the node and move with it,
+    // but are not scaled, just like the content. Several badges can share a corner.
+    pub fn badge(
+        mut self,
+        position: BadgePosition,
+        badge: impl Into<Element<'a, Message, Theme, Renderer>>,
+    ) -> Self {
+        self.badges.push((position, badge.into()));
+        self
+    }
+
+    // Reflects the execution state of the node in its appearance. A disabled node is drawn as
+    // disabled regardless of its status.
+    pub fn status(mut self, status: NodeStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    // A bypassed (muted) node is drawn dimmed, with a line from its first input to its first
+    // output that shows its inputs being passed through unchanged. It still receives events.
+    pub fn bypassed(mut self, bypassed: bool) -> Self {
+        self.bypassed = bypassed;
+        self
+    }
+
+    // Pressing the bypass shortcut (M by default) while the cursor is over the node calls `f`
+    // with whether the node should be bypassed from now on, unless the key press is captured by
+    // the content of the node
+    pub fn on_bypass_toggle<F>(mut self, f: F) -> Self
+    where
+        F: 'a + Fn(bool) -> Message,
+    {
+        self.on_bypass_toggle = Some(Box::new(f));
+        self
+    }
+
+    // The key, and the exact modifiers held along with it, that toggle bypassing the node
+    pub fn bypass_shortcut(mut self, key: keyboard::Key, modifiers: keyboard::Modifiers) -> Self {
+        self.bypass_shortcut = (key, modifiers);
+        self
+    }
+
     pub fn position(mut self, position: Point) -> Self {
         self.position = position;
         self
     }
 
+    // Which point of the node `position` refers to. With `Anchor::Center`, a node can be placed
+    // centered on a point without knowing its size in advance.
+    pub fn anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
     pub fn width(mut self, width: Length) -> Self {
         self.width = width;
         self
@@ -145,6 +736,14 @@ where
         self
     }
 
+    // Draws the node with the given appearance instead of the one from its style, e.g. to tint
+    // nodes by category. A disabled node still gets dimmed, and a node with a status still gets
+    // the status border of its style.
+    pub fn appearance(mut self, appearance: Appearance) -> Self {
+        self.appearance = Some(appearance);
+        self
+    }
+
     pub fn align_x(mut self, alignment: alignment::Horizontal) -> Self {
         self.horizontal_alignment = alignment;
         self
@@ -165,6 +764,27 @@ where
         self
     }
 
+    // A full-width header above the content, drawn with the header background of the style. If
+    // the node has a header, it can only be dragged by it unless a different `drag_region` is
+    // set. The header adds to the height of the node, like the socket rows do.
+    pub fn header(mut self, header: impl Into<Element<'a, Message, Theme, Renderer>>) -> Self {
+        self.header = Some(header.into());
+        self
+    }
+
+    // Defaults to `DragRegion::Header` for nodes with a header and `DragRegion::Anywhere` for
+    // nodes without one
+    pub fn drag_region(mut self, drag_region: DragRegion) -> Self {
+        self.drag_region = Some(drag_region);
+        self
+    }
+
+    // Identifies the node for operations like `focus_node`
+    pub fn id(mut self, id: widget::Id) -> Self {
+        self.id = Some(id);
+        self
+    }
+
     pub fn sockets(mut self, sockets: Vec<Socket<'a, Message, Theme, Renderer>>) -> Self {
         self.sockets = sockets;
         self
@@ -174,6 +794,333 @@ where
         self.socket_spacing = socket_spacing.into().0;
         self
     }
+
+    // Every socket row is at least this high, even if the content of its sockets is smaller
+    pub fn min_socket_row_height(mut self, min_socket_row_height: impl Into<Pixels>) -> Self {
+        self.min_socket_row_height = min_socket_row_height.into().0;
+        self
+    }
+
+    pub fn socket_layout(mut self, socket_layout: SocketLayout) -> Self {
+        self.socket_layout = socket_layout;
+        self
+    }
+
+    // The width (in graph units) the socket rows need to fit the content of all sockets
+    fn natural_sockets_width(
+        &self,
+        tree: &mut widget::Tree,
+        renderer: &Renderer,
+        max_width: f32,
+        scale: f32,
+    ) -> f32 {
+        let roles: Vec<SocketRole> = self.sockets.iter().map(|socket| socket.role).collect();
+        let (_, columns) = self.socket_layout.placements(&roles);
+
+        let column_width = self
+            .sockets
+            .iter()
+            .enumerate()
+            .map(|(socket_index, socket)| {
+                let socket_limits = iced::advanced::layout::Limits::new(
+                    Size::new(0.0, socket.min_height),
+                    Size::new(max_width, socket.max_height),
+                );
+                let socket_content = socket.content.as_widget().layout(
+                    &mut tree.children[socket_index + 1],
+                    renderer,
+                    &socket_limits,
+                );
+                socket_content.size().width + socket.row_style.padding.horizontal() / scale
+            })
+            .fold(0.0, f32::max);
+
+        // All columns are equally wide
+        column_width * columns as f32
+    }
+
+    // Moves the node by a screen-space drag, returning the messages for `on_translate` and
+    // `on_move`
+    fn drag(&self, state: &mut NodeState, delta: Vector) -> Vec<Message> {
+        let delta = self.constrain_drag(delta, state);
+        state.drag_offset = state.drag_offset + delta * (1.0 / state.scale);
+
+        let translate = self.on_translate.as_ref().map(|f| f((delta.x, delta.y)));
+        let move_to = self
+            .on_move
+            .as_ref()
+            .map(|f| f(state.drag_origin + state.drag_offset));
+        translate.into_iter().chain(move_to).collect()
+    }
+
+    // Restricts a screen-space drag of the node according to `lock_axis` and `position_bounds`
+    fn constrain_drag(&self, delta: Vector, state: &NodeState) -> Vector {
+        let mut delta = delta;
+        for axis in &self.locked_axes {
+            match axis {
+                Axis::Horizontal => delta.x = 0.0,
+                Axis::Vertical => delta.y = 0.0,
+            }
+        }
+
+        let Some(bounds) = self.position_bounds else {
+            return delta;
+        };
+        // A node that is outside the bounds already (e.g. because the application put it there)
+        // is not pulled in, but it cannot be dragged any further out either
+        let clamp = |position: f32, delta: f32, min: f32, extent: f32, size: f32| {
+            let max = (min + extent - size).max(min);
+            let target = position + delta / state.scale;
+            let clamped = target.clamp(min.min(position), max.max(position));
+            (clamped - position) * state.scale
+        };
+        // The node is not rebuilt between the events of a drag, so its position is the one the
+        // drag has reached so far rather than `self.position`
+        let top_left = self.top_left_at(state.drag_origin + state.drag_offset, state.size);
+        Vector::new(
+            clamp(
+                top_left.x,
+                delta.x,
+                bounds.x,
+                bounds.width,
+                state.size.width,
+            ),
+            clamp(
+                top_left.y,
+                delta.y,
+                bounds.y,
+                bounds.height,
+                state.size.height,
+            ),
+        )
+    }
+
+    // The graph-space top left corner of the node, given its graph-space size
+    fn top_left(&self, size: Size) -> Point {
+        self.top_left_at(self.position, size)
+    }
+
+    // The top left corner the node would have if it was at `position`
+    fn top_left_at(&self, position: Point, size: Size) -> Point {
+        match self.anchor {
+            Anchor::TopLeft => position,
+            Anchor::Center => Point::new(
+                position.x - size.width / 2.0,
+                position.y - size.height / 2.0,
+            ),
+        }
+    }
+
+    // Maps the names of the named sockets to their first pin
+    fn socket_names(&self, node_index: usize) -> HashMap<String, LogicalEndpoint> {
+        let (mut next_in, mut next_out) = (0, 0);
+        let mut names = HashMap::new();
+        for socket in &self.sockets {
+            let next_index = match socket.role {
+                SocketRole::In => &mut next_in,
+                SocketRole::Out => &mut next_out,
+            };
+            if let Some(name) = &socket.name {
+                let logical = LogicalEndpoint {
+                    node_index,
+                    role: socket.role,
+                    socket_index: *next_index,
+                };
+                names.insert(name.clone(), logical);
+            }
+            *next_index += socket.pin_count();
+        }
+        names
+    }
+
+    // The number of pins of the socket, plus the "add pin" button if it has one
+    fn pin_slots(&self, socket: &Socket<'a, Message, Theme, Renderer>) -> usize {
+        let add_pin = socket.pins.is_some() && self.on_add_pin.is_some();
+        socket.pin_count() + usize::from(add_pin)
+    }
+
+    // The header comes after the content and the sockets, both in the layout and the widget tree
+    fn header_layout<'b>(&self, layout: Layout<'b>) -> Option<Layout<'b>> {
+        self.header.as_ref()?;
+        layout.children().nth(self.sockets.len() + 1)
+    }
+
+    // The area from which the node can be dragged, if any
+    fn drag_handle(&self, layout: Layout<'_>) -> Option<Rectangle> {
+        let default = if self.header.is_some() {
+            DragRegion::Header
+        } else {
+            DragRegion::Anywhere
+        };
+
+        match self.drag_region.unwrap_or(default) {
+            DragRegion::Header => self
+                .header_layout(layout)
+                .map(|header_layout| header_layout.bounds()),
+            DragRegion::Anywhere => Some(layout.bounds()),
+            DragRegion::None => None,
+        }
+    }
+
+    // Blob elements come after the header in the widget tree, in the order of their sockets
+    fn blob_tree_indices(&self) -> Vec<Option<usize>> {
+        let mut next = self.sockets.len() + 1 + usize::from(self.header.is_some());
+        self.sockets
+            .iter()
+            .map(|socket| {
+                socket.blob.as_ref().map(|_| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect()
+    }
+
+    // The error strip comes after the header in the layout, and after the blobs in the widget
+    // tree
+    fn error_layout<'b>(&self, layout: Layout<'b>) -> Option<Layout<'b>> {
+        self.error.as_ref()?;
+        layout
+            .children()
+            .nth(self.sockets.len() + 1 + usize::from(self.header.is_some()))
+    }
+
+    fn error_tree_index(&self) -> usize {
+        let blobs = self.sockets.iter().filter(|socket| socket.blob.is_some());
+        self.sockets.len() + 1 + usize::from(self.header.is_some()) + blobs.count()
+    }
+
+    // Badges come last, both in the layout and the widget tree
+    fn badge_layouts<'b>(&self, layout: Layout<'b>) -> impl Iterator<Item = Layout<'b>> {
+        layout.children().skip(
+            self.sockets.len()
+                + 1
+                + usize::from(self.header.is_some())
+                + usize::from(self.error.is_some()),
+        )
+    }
+
+    fn first_badge_tree_index(&self) -> usize {
+        self.error_tree_index() + usize::from(self.error.is_some())
+    }
+
+    fn has_footer(&self) -> bool {
+        self.on_add_input.is_some() || self.on_add_output.is_some()
+    }
+
+    // The "add socket" buttons in the footer, input on the left and output on the right
+    fn add_socket_buttons(
+        &self,
+        layout: Layout<'_>,
+    ) -> Vec<(Rectangle, &(dyn Fn() -> Message + 'a))> {
+        // The buttons are placed above the error strip, if there is one
+        let node_bounds = layout.bounds();
+        let bottom = self
+            .error_layout(layout)
+            .map_or(node_bounds.y + node_bounds.height, |error_layout| {
+                error_layout.bounds().y
+            });
+        let y = bottom - self.padding.bottom - ADD_SOCKET_BUTTON_SIZE;
+        let size = Size::new(ADD_SOCKET_BUTTON_SIZE, ADD_SOCKET_BUTTON_SIZE);
+
+        let mut buttons = Vec::new();
+        if let Some(f) = &self.on_add_input {
+            let x = node_bounds.x + self.padding.left;
+            buttons.push((Rectangle::new(Point::new(x, y), size), f.as_ref()));
+        }
+        if let Some(f) = &self.on_add_output {
+            let x = node_bounds.x + node_bounds.width - self.padding.right - ADD_SOCKET_BUTTON_SIZE;
+            buttons.push((Rectangle::new(Point::new(x, y), size), f.as_ref()));
+        }
+        buttons
+    }
+
+    // The message of the "add pin" or "add socket" button at the given position, if any
+    fn add_button_message(
+        &self,
+        layout: Layout<'_>,
+        position: Point,
+        scale: f32,
+    ) -> Option<Message> {
+        if let Some(message) = self
+            .add_socket_buttons(layout)
+            .into_iter()
+            .find(|(button, _)| button.contains(position))
+            .map(|(_, f)| f())
+        {
+            return Some(message);
+        }
+
+        let f = self.on_add_pin.as_ref()?;
+        self.add_pin_at(layout, position, scale).map(f)
+    }
+
+    // The index of the socket whose "add pin" button is at the given position
+    fn add_pin_at(&self, layout: Layout<'_>, position: Point, scale: f32) -> Option<usize> {
+        let edge_fractions = self.edge_fractions();
+        self.sockets
+            .iter()
+            .zip(layout.children().skip(1))
+            .enumerate()
+            .find(|(socket_index, (socket, socket_layout))| {
+                let slots = self.pin_slots(socket);
+                slots > socket.pin_count()
+                    && socket
+                        .hit_rect(socket.pin_rect(
+                            layout.bounds(),
+                            socket_layout.bounds().center_y(),
+                            edge_fractions[*socket_index],
+                            slots - 1,
+                            slots,
+                            scale,
+                        ))
+                        .contains(position)
+            })
+            .map(|(socket_index, _)| socket_index)
+    }
+
+    // Sockets on the top and bottom edges are distributed evenly along their edge
+    fn edge_fractions(&self) -> Vec<f32> {
+        let count_on = |side: SocketSide| {
+            self.sockets
+                .iter()
+                .filter(|socket| socket.blob_side == side)
+                .count()
+        };
+        let top_count = count_on(SocketSide::Top);
+        let bottom_count = count_on(SocketSide::Bottom);
+
+        let mut top_index = 0;
+        let mut bottom_index = 0;
+        self.sockets
+            .iter()
+            .map(|socket| match socket.blob_side {
+                SocketSide::Top => {
+                    top_index += 1;
+                    top_index as f32 / (top_count + 1) as f32
+                }
+                SocketSide::Bottom => {
+                    bottom_index += 1;
+                    bottom_index as f32 / (bottom_count + 1) as f32
+                }
+                SocketSide::Left | SocketSide::Right => 0.0,
+            })
+            .collect()
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Node<'a, Message, Theme, Renderer>
+where
+    Message: 'a,
+    Theme: StyleSheet + iced::widget::text::StyleSheet + 'a,
+    Renderer: renderer::Renderer + text::Renderer + 'a,
+{
+    // A strip at the bottom of the node that shows the message, e.g. a validation error of the
+    // node. It is drawn with the error colors of the style and adds to the height of the node.
+    pub fn error(mut self, error: Option<String>) -> Self {
+        self.error = error.map(|error| iced::widget::text(error).into());
+        self
+    }
 }
 
 pub fn node<'a, Message, Theme, Renderer>(
@@ -186,7 +1133,25 @@ where
     Node::new(content)
 }
 
-impl<'a, Message, Theme, Renderer> ScalableWidget<Message, Renderer>
+// Builds the nodes at the start of the graph content from the application's own list of nodes.
+// The closure gets the index every node will have in the graph (as in `LogicalEndpoint`), which
+// is also the position of its item in `items`, so the two cannot get out of step. The closure is
+// free to capture the index by `move` for messages like `on_translate`.
+pub fn nodes_from_iter<'a, Message, Theme, Renderer, T, N>(
+    items: impl IntoIterator<Item = T>,
+    mut node: impl FnMut(usize, T) -> N,
+) -> Vec<GraphNodeElement<'a, Message, Theme, Renderer>>
+where
+    N: Into<GraphNodeElement<'a, Message, Theme, Renderer>>,
+{
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| node(index, item).into())
+        .collect()
+}
+
+impl<'a, Message, Theme, Renderer> ScalableWidget<Message, Renderer, Theme>
     for Node<'a, Message, Theme, Renderer>
 where
     Theme: StyleSheet,
@@ -200,10 +1165,203 @@ where
         scale: f32,
         socket_state: &mut super::node_element::SocketLayoutState,
     ) -> iced::advanced::layout::Node {
-        if socket_state.done {
-            panic!("the graph content must consist of nodes, then connections; it is not allowed to have (more) nodes after the connections");
+        // The graph content should consist of nodes, then connections. A node after the
+        // connections is still laid out, but the connections before it cannot reach its sockets
+        // and dangle instead.
+
+        let key = self.version.map(|version| LayoutCacheKey {
+            version,
+            scale,
+            min: limits.min(),
+            max: limits.max(),
+        });
+
+        let state = tree.state.downcast_mut::<NodeState>();
+        state.scale = scale;
+        state.index = socket_state.nodes.len();
+        state.blobs_hidden = socket_state.blobs_hidden;
+        let cached = match (&state.layout_cache, key) {
+            (Some(cache), Some(key)) if cache.key == key => Some(cache.layout.clone()),
+            _ => None,
+        };
+
+        let node_layout = match cached {
+            Some(node_layout) => node_layout,
+            None => {
+                let node_layout = self.compute_layout(tree, renderer, limits, scale);
+                if let Some(key) = key {
+                    tree.state.downcast_mut::<NodeState>().layout_cache = Some(LayoutCache {
+                        key,
+                        layout: node_layout.clone(),
+                    });
+                }
+                node_layout
+            }
+        };
+
+        let size = node_layout.node.size();
+        tree.state.downcast_mut::<NodeState>().size =
+            Size::new(size.width / scale, size.height / scale);
+
+        // The position only affects the final translation, so it is not part of the cache key
+        // Nodes are aligned to physical pixels, so that their borders stay crisp while zooming
+        let scale_factor = socket_state.scale_factor;
+        let top_left = self.top_left(tree.state.downcast_ref::<NodeState>().size);
+        let offset = Vector::new(
+            snap_to_pixel(top_left.x * scale, scale_factor),
+            snap_to_pixel(top_left.y * scale, scale_factor),
+        );
+
+        let translate_all =
+            |rects: &[Rectangle]| rects.iter().map(|rect| *rect + offset).collect::<Vec<_>>();
+        socket_state
+            .inputs
+            .push(translate_all(&node_layout.in_sockets));
+        socket_state
+            .outputs
+            .push(translate_all(&node_layout.out_sockets));
+        socket_state
+            .input_hit_areas
+            .push(translate_all(&node_layout.in_hit_areas));
+        socket_state
+            .output_hit_areas
+            .push(translate_all(&node_layout.out_hit_areas));
+        socket_state.input_sides.push(node_layout.in_sides);
+        socket_state.output_sides.push(node_layout.out_sides);
+        socket_state
+            .socket_names
+            .push(self.socket_names(socket_state.nodes.len()));
+
+        socket_state.nodes.push(Rectangle::new(
+            Point::ORIGIN + offset,
+            node_layout.node.size(),
+        ));
+        socket_state.locked.push(self.locked || self.disabled);
+
+        let size = node_layout.node.size();
+        let snapped_size = Size::new(
+            snap_to_pixel(size.width, scale_factor),
+            snap_to_pixel(size.height, scale_factor),
+        );
+        iced::advanced::layout::Node::with_children(
+            snapped_size,
+            node_layout.node.children().to_vec(),
+        )
+        .translate(offset)
+    }
+
+    fn is_dragged(&self, tree: &widget::Tree) -> bool {
+        tree.state
+            .downcast_ref::<NodeState>()
+            .drag_start_position
+            .is_some()
+    }
+
+    fn has_subgraph(&self) -> bool {
+        self.subgraph
+    }
+
+    fn animate(&self, tree: &mut widget::Tree, now: Instant) -> bool {
+        let state = tree.state.downcast_mut::<NodeState>();
+        if self.status != NodeStatus::Running || self.disabled {
+            state.pulse_start = None;
+            return false;
+        }
+
+        let start = *state.pulse_start.get_or_insert(now);
+        state.pulse_time = now.saturating_duration_since(start).as_secs_f32();
+        true
+    }
+
+    fn drag_messages(&self, tree: &mut widget::Tree, delta: Vector) -> Vec<Message> {
+        let state = tree.state.downcast_mut::<NodeState>();
+        if state.drag_start_position.is_none() || state.duplicate_offset.is_some() {
+            return Vec::new();
+        }
+        self.drag(state, delta)
+    }
+
+    fn overlay_mesh(
+        &self,
+        tree: &widget::Tree,
+        theme: &Theme,
+        layout: Layout<'_>,
+    ) -> Option<Indexed<SolidVertex2D>> {
+        if !self.bypassed || self.disabled {
+            return None;
         }
 
+        let state = tree.state.downcast_ref::<NodeState>();
+        let bounds = layout.bounds();
+        let edge_fractions = self.edge_fractions();
+        let socket_layouts: Vec<Layout<'_>> =
+            layout.children().skip(1).take(self.sockets.len()).collect();
+        // The center of the first pin of the first socket with the given role, and the direction
+        // away from the node there
+        let first_blob = |role: SocketRole| {
+            let index = self
+                .sockets
+                .iter()
+                .position(|socket| socket.role == role && socket.pin_count() > 0)?;
+            let socket = &self.sockets[index];
+            let blob = socket.pin_rect(
+                bounds,
+                socket_layouts[index].bounds().center_y(),
+                edge_fractions[index],
+                0,
+                self.pin_slots(socket),
+                state.scale,
+            );
+            let center = blob.center();
+            Some((
+                Vector::new(center.x, center.y),
+                socket.blob_side.outward_direction(),
+            ))
+        };
+        let ((from, from_outward), (to, to_outward)) =
+            (first_blob(SocketRole::In)?, first_blob(SocketRole::Out)?);
+
+        let style = self
+            .appearance
+            .unwrap_or_else(|| theme.appearance(&self.style));
+        let color = style.bypass_color.unwrap_or(style.border_color);
+        let spline = generate_spline(
+            from,
+            from_outward * -1.0,
+            bounds.width.max(bounds.height),
+            to,
+            to_outward,
+            BYPASS_LINE_SEGMENTS,
+            1.0,
+        );
+        let (vertices, indices) = line_to_polygon(&spline, BYPASS_LINE_WIDTH * state.scale / 2.0);
+        let color = pack(color);
+        Some(Indexed {
+            vertices: vertices
+                .into_iter()
+                .map(|vertex| SolidVertex2D {
+                    position: [vertex.x, vertex.y],
+                    color,
+                })
+                .collect(),
+            indices,
+        })
+    }
+}
+
+impl<'a, Message, Theme, Renderer> Node<'a, Message, Theme, Renderer>
+where
+    Theme: StyleSheet,
+    Renderer: renderer::Renderer,
+{
+    // Lays out the node as if it were positioned at the origin
+    fn compute_layout(
+        &self,
+        tree: &mut widget::Tree,
+        renderer: &Renderer,
+        limits: &iced::advanced::layout::Limits,
+        scale: f32,
+    ) -> NodeLayout {
         let limits = limits
             .loose()
             .max_width(self.max_width)
@@ -220,7 +1378,17 @@ where
         let content_intrinsic_size = content.size();
         let padding = self.padding.fit(content_intrinsic_size, limits.max());
 
-        let content_frame_size = limits.resolve(self.width, self.height, content.size());
+        // A node that shrinks to its content is made wide enough for its socket rows as well.
+        // The padding is in screen space, so it has to be scaled down to graph units.
+        let sockets_width = match self.width {
+            Length::Shrink => self.natural_sockets_width(tree, renderer, limits.max().width, scale),
+            _ => 0.0,
+        };
+        let natural_size = Size::new(
+            content_intrinsic_size.width.max(sockets_width) + padding.horizontal() / scale,
+            content_intrinsic_size.height + padding.vertical() / scale,
+        );
+        let content_frame_size = limits.resolve(self.width, self.height, natural_size);
 
         let content_available_width =
             content_frame_size.width * scale - padding.left - padding.right;
@@ -228,7 +1396,30 @@ where
             content_frame_size.height * scale - padding.top - padding.bottom;
         let content_available_size = Size::new(content_available_width, content_available_height);
 
-        content.move_to_mut(Point::new(padding.left, padding.top));
+        let header = self.header.as_ref().map(|header| {
+            let header_limits = iced::advanced::layout::Limits::new(
+                Size::ZERO,
+                Size::new(
+                    content_frame_size.width - padding.left - padding.right,
+                    f32::INFINITY,
+                ),
+            );
+            let mut header_content = header.as_widget().layout(
+                &mut tree.children[self.sockets.len() + 1],
+                renderer,
+                &header_limits,
+            );
+            header_content.move_to_mut(Point::new(padding.left, padding.top));
+
+            let header_size = Size::new(
+                content_frame_size.width * scale,
+                header_content.size().height * scale + padding.top + padding.bottom,
+            );
+            iced::advanced::layout::Node::with_children(header_size, vec![header_content])
+        });
+        let header_height = header.as_ref().map_or(0.0, |header| header.size().height);
+
+        content.move_to_mut(Point::new(padding.left, header_height + padding.top));
         content.align_mut(
             Alignment::from(self.horizontal_alignment),
             Alignment::from(self.vertical_alignment),
@@ -237,77 +1428,228 @@ where
 
         let mut children = vec![content];
 
-        let mut in_sockets: Vec<Rectangle> = vec![];
-        let mut out_sockets: Vec<Rectangle> = vec![];
+        let roles: Vec<SocketRole> = self.sockets.iter().map(|socket| socket.role).collect();
+        let (placements, columns) = self.socket_layout.placements(&roles);
+        let row_count = placements.iter().map(|(row, _)| row + 1).max().unwrap_or(0);
+        let column_width_scaled = content_available_size.width / columns as f32;
 
-        let mut socket_top: f32 = content_available_size.height;
-        for (socket_index, socket) in self.sockets.iter().enumerate() {
+        let mut socket_nodes: Vec<Option<iced::advanced::layout::Node>> =
+            (0..self.sockets.len()).map(|_| None).collect();
+        let mut row_centers: Vec<f32> = vec![0.0; self.sockets.len()];
+
+        let mut socket_top: f32 = header_height + content_available_size.height;
+        for row in 0..row_count {
             socket_top += self.socket_spacing * scale;
 
             let socket_content_available_width =
-                content_frame_size.width - padding.left - padding.right;
+                (content_frame_size.width - padding.left - padding.right) / columns as f32;
+
+            // Lay out all socket contents in this row first, to find the height of the row
+            let mut row_contents = vec![];
+            let mut row_height_scaled: f32 = self.min_socket_row_height * scale;
+            for (socket_index, socket) in self.sockets.iter().enumerate() {
+                let (socket_row, column) = placements[socket_index];
+                if socket_row != row {
+                    continue;
+                }
 
-            let socket_limits = iced::advanced::layout::Limits::new(
-                Size {
-                    width: 0.0,
-                    height: socket.min_height,
-                },
-                Size {
-                    width: socket_content_available_width,
-                    height: socket.max_height,
-                },
-            );
+                let row_padding = socket.row_style.padding;
+                let socket_limits = iced::advanced::layout::Limits::new(
+                    Size {
+                        width: 0.0,
+                        height: socket.min_height,
+                    },
+                    Size {
+                        width: socket_content_available_width - row_padding.horizontal(),
+                        height: socket.max_height,
+                    },
+                );
 
-            let mut socket_content = socket.content.as_widget().layout(
-                &mut tree.children[socket_index + 1],
-                renderer,
-                &socket_limits,
-            );
+                let socket_content = socket.content.as_widget().layout(
+                    &mut tree.children[socket_index + 1],
+                    renderer,
+                    &socket_limits,
+                );
 
-            let socket_content_size_scaled = Size::new(
-                socket_content.size().width * scale,
-                socket_content.size().height * scale,
-            );
-            let socket_area_size_scaled = Size::new(
-                content_available_size.width,
-                socket_content_size_scaled.height,
+                // Not every widget respects the minimum height of its limits
+                let content_height = socket_content.size().height.max(socket.min_height);
+                row_height_scaled =
+                    row_height_scaled.max(content_height * scale + row_padding.vertical());
+                if socket.pins.is_some()
+                    && matches!(socket.blob_side, SocketSide::Left | SocketSide::Right)
+                {
+                    // Leave enough room for all pins
+                    let pins_height = self.pin_slots(socket) as f32
+                        * socket.blob_radius.resolve(scale)
+                        * PIN_SPACING;
+                    row_height_scaled = row_height_scaled.max(pins_height);
+                }
+                row_contents.push((socket_index, column, socket_content));
+            }
+
+            for (socket_index, column, mut socket_content) in row_contents {
+                let socket = &self.sockets[socket_index];
+
+                let socket_area_size_scaled = Size::new(column_width_scaled, row_height_scaled);
+                let row_padding = socket.row_style.padding;
+                socket_content.move_to_mut(Point::new(row_padding.left, row_padding.top));
+                socket_content.align_mut(
+                    Alignment::from(socket.content_alignment),
+                    Alignment::Center,
+                    Size::new(
+                        socket_area_size_scaled.width - row_padding.horizontal(),
+                        socket_area_size_scaled.height - row_padding.vertical(),
+                    ),
+                );
+
+                let mut socket_node = iced::advanced::layout::Node::with_children(
+                    socket_area_size_scaled,
+                    vec![socket_content],
+                );
+                socket_node.move_to_mut(Point::new(
+                    self.padding.left + column as f32 * column_width_scaled,
+                    padding.top + socket_top,
+                ));
+                socket_nodes[socket_index] = Some(socket_node);
+
+                row_centers[socket_index] = padding.top + socket_top + row_height_scaled / 2.0;
+            }
+
+            socket_top += row_height_scaled;
+        }
+
+        if self.has_footer() {
+            socket_top += self.socket_spacing * scale + ADD_SOCKET_BUTTON_SIZE;
+        }
+
+        // The error strip is laid out like the header, but below everything else
+        let error_top = padding.top + socket_top + padding.bottom;
+        let error = self.error.as_ref().map(|error| {
+            let error_limits = iced::advanced::layout::Limits::new(
+                Size::ZERO,
+                Size::new(
+                    content_frame_size.width - padding.left - padding.right,
+                    f32::INFINITY,
+                ),
             );
-            socket_content.align_mut(
-                Alignment::from(socket.content_alignment),
-                Alignment::Center,
-                socket_area_size_scaled,
+            let mut error_content = error.as_widget().layout(
+                &mut tree.children[self.error_tree_index()],
+                renderer,
+                &error_limits,
             );
+            error_content.move_to_mut(Point::new(padding.left, padding.top));
 
-            let mut socket_node = iced::advanced::layout::Node::with_children(
-                socket_area_size_scaled,
-                vec![socket_content],
+            let error_size = Size::new(
+                content_frame_size.width * scale,
+                error_content.size().height * scale + padding.top + padding.bottom,
             );
-            socket_node.move_to_mut(Point::new(self.padding.left, padding.top + socket_top));
-            children.push(socket_node);
+            iced::advanced::layout::Node::with_children(error_size, vec![error_content])
+                .move_to(Point::new(0.0, error_top))
+        });
+        let error_height = error.as_ref().map_or(0.0, |error| error.size().height);
 
-            let blob_rect = socket.blob_rect(
-                0.0,
-                content_frame_size.width * scale,
-                padding.top + socket_top + socket_area_size_scaled.height / 2.0,
-            ) + (Vector::new(self.position.x, self.position.y) * scale);
-            match socket.role {
-                SocketRole::In => in_sockets.push(blob_rect),
-                SocketRole::Out => out_sockets.push(blob_rect),
+        let total_size = Size::new(content_frame_size.width * scale, error_top + error_height);
+
+        // Blob rectangles can only be determined now, as blobs on the bottom edge
+        // depend on the total height
+        let mut in_sockets: Vec<Rectangle> = vec![];
+        let mut out_sockets: Vec<Rectangle> = vec![];
+        let mut in_hit_areas: Vec<Rectangle> = vec![];
+        let mut out_hit_areas: Vec<Rectangle> = vec![];
+        let mut in_sides: Vec<SocketSide> = vec![];
+        let mut out_sides: Vec<SocketSide> = vec![];
+        let edge_fractions = self.edge_fractions();
+
+        let blob_tree_indices = self.blob_tree_indices();
+
+        for (socket_index, socket_node) in socket_nodes.into_iter().enumerate() {
+            let mut socket_node =
+                socket_node.expect("every socket should have been placed in a row");
+            let socket_position = socket_node.bounds().position();
+            let mut blob_nodes = vec![];
+
+            let socket = &self.sockets[socket_index];
+            let slots = self.pin_slots(socket);
+            for pin in 0..socket.pin_count() {
+                let blob_rect = socket.pin_rect(
+                    Rectangle::new(Point::ORIGIN, total_size),
+                    row_centers[socket_index],
+                    edge_fractions[socket_index],
+                    pin,
+                    slots,
+                    scale,
+                );
+
+                // Every pin gets its own layout node for the blob element, relative to the row
+                if let (Some(blob), Some(tree_index)) =
+                    (&socket.blob, blob_tree_indices[socket_index])
+                {
+                    let mut blob_node = blob.as_widget().layout(
+                        &mut tree.children[tree_index],
+                        renderer,
+                        &iced::advanced::layout::Limits::new(Size::ZERO, blob_rect.size()),
+                    );
+                    let center = blob_rect.center();
+                    blob_node.move_to_mut(Point::new(
+                        center.x - blob_node.size().width / 2.0 - socket_position.x,
+                        center.y - blob_node.size().height / 2.0 - socket_position.y,
+                    ));
+                    blob_nodes.push(blob_node);
+                }
+
+                match socket.role {
+                    SocketRole::In => {
+                        in_sockets.push(blob_rect);
+                        in_hit_areas.push(socket.hit_rect(blob_rect));
+                        in_sides.push(socket.blob_side);
+                    }
+                    SocketRole::Out => {
+                        out_sockets.push(blob_rect);
+                        out_hit_areas.push(socket.hit_rect(blob_rect));
+                        out_sides.push(socket.blob_side);
+                    }
+                }
             }
 
-            socket_top += socket_content_size_scaled.height;
+            if !blob_nodes.is_empty() {
+                let mut socket_children = socket_node.children().to_vec();
+                socket_children.extend(blob_nodes);
+                socket_node = iced::advanced::layout::Node::with_children(
+                    socket_node.size(),
+                    socket_children,
+                )
+                .move_to(socket_position);
+            }
+            children.push(socket_node);
         }
 
-        socket_state.inputs.push(in_sockets);
-        socket_state.outputs.push(out_sockets);
+        children.extend(header);
+        children.extend(error);
 
-        let total_size = Size::new(
-            content_frame_size.width * scale,
-            padding.top + socket_top + padding.bottom,
-        );
-        let node = iced::advanced::layout::Node::with_children(total_size, children);
+        let first_badge = self.first_badge_tree_index();
+        for (index, (position, badge)) in self.badges.iter().enumerate() {
+            let mut badge_node = badge.as_widget().layout(
+                &mut tree.children[first_badge + index],
+                renderer,
+                &iced::advanced::layout::Limits::new(Size::ZERO, Size::INFINITY),
+            );
+            let corner = position.corner(total_size);
+            badge_node.move_to_mut(Point::new(
+                corner.x - badge_node.size().width / 2.0,
+                corner.y - badge_node.size().height / 2.0,
+            ));
+            children.push(badge_node);
+        }
 
-        node.translate(Vector::new(self.position.x, self.position.y) * scale)
+        NodeLayout {
+            node: iced::advanced::layout::Node::with_children(total_size, children),
+            in_sockets,
+            out_sockets,
+            in_hit_areas,
+            out_hit_areas,
+            in_sides,
+            out_sides,
+        }
     }
 }
 
@@ -322,6 +1664,22 @@ where
         for socket in &self.sockets {
             res.push(widget::Tree::new(&socket.content));
         }
+        if let Some(header) = &self.header {
+            res.push(widget::Tree::new(header));
+        }
+        for blob in self
+            .sockets
+            .iter()
+            .filter_map(|socket| socket.blob.as_ref())
+        {
+            res.push(widget::Tree::new(blob));
+        }
+        if let Some(error) = &self.error {
+            res.push(widget::Tree::new(error));
+        }
+        for (_, badge) in &self.badges {
+            res.push(widget::Tree::new(badge));
+        }
         res
     }
 
@@ -331,16 +1689,45 @@ where
         for socket in &self.sockets {
             new_children.push(socket.content.as_widget());
         }
+        if let Some(header) = &self.header {
+            new_children.push(header.as_widget());
+        }
+        for blob in self
+            .sockets
+            .iter()
+            .filter_map(|socket| socket.blob.as_ref())
+        {
+            new_children.push(blob.as_widget());
+        }
+        if let Some(error) = &self.error {
+            new_children.push(error.as_widget());
+        }
+        for (_, badge) in &self.badges {
+            new_children.push(badge.as_widget());
+        }
         tree.diff_children(new_children.as_slice())
     }
 
     fn tag(&self) -> widget::tree::Tag {
-        widget::tree::Tag::of::<widget::tree::State>()
+        widget::tree::Tag::of::<NodeState>()
     }
 
     fn state(&self) -> widget::tree::State {
         widget::tree::State::new(NodeState {
             drag_start_position: None,
+            drag_origin: Point::ORIGIN,
+            drag_offset: Vector::ZERO,
+            hovered: false,
+            flash_until: None,
+            layout_cache: None,
+            modifiers: keyboard::Modifiers::default(),
+            duplicate_offset: None,
+            scale: 1.0,
+            index: 0,
+            size: Size::ZERO,
+            blobs_hidden: false,
+            pulse_start: None,
+            pulse_time: 0.0,
         })
     }
 
@@ -363,13 +1750,52 @@ where
         cursor: mouse::Cursor,
         viewport: &Rectangle,
     ) {
-        let style = theme.appearance(&self.style);
+        let state = tree.state.downcast_ref::<NodeState>();
+        let mut style = match (self.appearance, self.disabled, self.status) {
+            (Some(appearance), false, NodeStatus::Idle) => appearance,
+            // The status border of the style sheet goes on top of the explicit appearance
+            (Some(appearance), false, status) => {
+                let status = theme.status(&self.style, status);
+                Appearance {
+                    border_color: status.border_color,
+                    border_width: appearance.border_width.max(status.border_width),
+                    ..appearance
+                }
+            }
+            (Some(appearance), true, _) => dimmed(appearance),
+            (None, false, NodeStatus::Idle) if state.hovered => theme.hovered(&self.style),
+            (None, false, NodeStatus::Idle) => theme.appearance(&self.style),
+            (None, false, status) if state.hovered => Appearance {
+                shadow: theme.hovered(&self.style).shadow,
+                ..theme.status(&self.style, status)
+            },
+            (None, false, status) => theme.status(&self.style, status),
+            (None, true, _) => theme.disabled(&self.style),
+        };
+        if self.bypassed && !self.disabled {
+            style = Appearance {
+                bypass_color: style.bypass_color,
+                ..dimmed(style)
+            };
+        }
+        if self.status == NodeStatus::Running && !self.disabled {
+            let phase = state.pulse_time / PULSE_PERIOD * std::f32::consts::TAU;
+            style.border_color.a *= 1.0 - PULSE_FADE * (0.5 - 0.5 * phase.cos());
+        }
+        if state
+            .flash_until
+            .is_some_and(|until| Instant::now() < until)
+        {
+            style.border_color = style.text_color.unwrap_or(renderer_style.text_color);
+            style.border_width = style.border_width.max(FLASH_BORDER_WIDTH);
+        }
         let bounds = layout.bounds();
 
-        if style.background.is_some() || style.border_width > 0.0 {
+        if self.subgraph {
+            // Another node peeking out from behind this one
             renderer.fill_quad(
                 renderer::Quad {
-                    bounds,
+                    bounds: bounds + Vector::new(SUBGRAPH_STACK_OFFSET, SUBGRAPH_STACK_OFFSET),
                     border: Border {
                         color: style.border_color,
                         width: style.border_width,
@@ -383,15 +1809,71 @@ where
             );
         }
 
+        if style.background.is_some() || style.border_width > 0.0 || style.shadow.is_some() {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds,
+                    border: Border {
+                        color: style.border_color,
+                        width: style.border_width,
+                        radius: style.border_radius.into(),
+                    },
+                    shadow: style.shadow.unwrap_or_default(),
+                },
+                style
+                    .background
+                    .unwrap_or(Background::Color(Color::TRANSPARENT)),
+            );
+        }
+
+        let text_color = style.text_color.unwrap_or(renderer_style.text_color);
+        if let (Some(header), Some(header_layout)) = (&self.header, self.header_layout(layout)) {
+            if let Some(background) = style.header_background {
+                // Inset by the border, so that the header does not cover it
+                let inset = style.border_width;
+                let radius = (style.border_radius - inset).max(0.0);
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: Rectangle {
+                            x: bounds.x + inset,
+                            y: bounds.y + inset,
+                            width: bounds.width - inset * 2.0,
+                            height: header_layout.bounds().height - inset,
+                        },
+                        border: Border {
+                            radius: [radius, radius, 0.0, 0.0].into(),
+                            ..Border::default()
+                        },
+                        ..renderer::Quad::default()
+                    },
+                    background,
+                );
+            }
+
+            let header_content_layout = header_layout
+                .children()
+                .next()
+                .expect("the header layout node should have one child");
+            if content_visible(header_layout, header_content_layout) {
+                header.as_widget().draw(
+                    &tree.children[self.sockets.len() + 1],
+                    renderer,
+                    theme,
+                    &renderer::Style { text_color },
+                    header_content_layout,
+                    cursor,
+                    viewport,
+                );
+            }
+        }
+
         let mut children_iter = layout.children();
         let content_layout = children_iter
             .next()
             .expect("there should be a layout node for the graph node content");
 
         // Only draw node content if it would be sufficiently big
-        if layout.bounds().width > content_layout.bounds().width
-            && layout.bounds().height > content_layout.bounds().height
-        {
+        if content_visible(layout, content_layout) {
             self.content.as_widget().draw(
                 &tree.children[0],
                 renderer,
@@ -405,7 +1887,9 @@ where
             );
         }
 
-        for (socket_index, socket_layout) in children_iter.enumerate() {
+        let edge_fractions = self.edge_fractions();
+        let blob_tree_indices = self.blob_tree_indices();
+        for (socket_index, socket_layout) in children_iter.take(self.sockets.len()).enumerate() {
             let socket = &self.sockets[socket_index];
 
             let child_layout = socket_layout
@@ -413,10 +1897,34 @@ where
                 .next()
                 .expect("the socket layout node should have one child");
 
+            let row_background = if cursor.is_over(socket_layout.bounds()) && !self.disabled {
+                socket
+                    .row_style
+                    .hovered_background
+                    .or(socket.row_style.background)
+            } else {
+                socket.row_style.background
+            };
+            if let Some(row_background) = row_background {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: socket_layout.bounds(),
+                        border: Border {
+                            radius: socket.row_style.border_radius.into(),
+                            ..Border::default()
+                        },
+                        ..renderer::Quad::default()
+                    },
+                    Background::Color(if self.disabled {
+                        dim(row_background)
+                    } else {
+                        row_background
+                    }),
+                );
+            }
+
             // Only draw socket content if it would be sufficiently big
-            if socket_layout.bounds().width > child_layout.bounds().width
-                && (socket_layout.bounds().height * 2.0) > child_layout.bounds().height
-            {
+            if socket_content_visible(socket_layout, child_layout) {
                 socket.content.as_widget().draw(
                     &tree.children[socket_index + 1],
                     renderer,
@@ -430,24 +1938,234 @@ where
                 );
             }
 
-            // Draw blob
-            let blob_rect =
-                socket.blob_rect(bounds.x, bounds.width, socket_layout.bounds().center_y());
+            // Draw blobs
+            let blob_color = if self.disabled {
+                dim(socket.blob_color)
+            } else {
+                socket.blob_color
+            };
+            let slots = self.pin_slots(socket);
+            let drawn_slots = if state.blobs_hidden { 0 } else { slots };
+            for pin in 0..drawn_slots {
+                let blob_rect = socket.pin_rect(
+                    bounds,
+                    socket_layout.bounds().center_y(),
+                    edge_fractions[socket_index],
+                    pin,
+                    slots,
+                    state.scale,
+                );
+
+                if let (true, Some(blob), Some(tree_index)) = (
+                    pin < socket.pin_count(),
+                    &socket.blob,
+                    blob_tree_indices[socket_index],
+                ) {
+                    let blob_layout = socket_layout
+                        .children()
+                        .nth(pin + 1)
+                        .expect("every pin should have a layout node for the blob element");
+                    blob.as_widget().draw(
+                        &tree.children[tree_index],
+                        renderer,
+                        theme,
+                        &renderer::Style {
+                            text_color: blob_color,
+                        },
+                        blob_layout,
+                        cursor,
+                        viewport,
+                    );
+                } else if pin < socket.pin_count() {
+                    renderer.fill_quad(
+                        renderer::Quad {
+                            bounds: blob_rect,
+                            border: Border {
+                                color: socket.blob_border_color.unwrap_or(style.border_color),
+                                width: style.border_width,
+                                radius: (socket.blob_border_radius
+                                    * socket.blob_radius.multiplier(state.scale))
+                                .into(),
+                            },
+                            ..renderer::Quad::default()
+                        },
+                        Background::Color(blob_color),
+                    );
+                } else {
+                    draw_plus(renderer, blob_rect, blob_color);
+                }
+            }
+        }
+
+        for (button, _) in self.add_socket_buttons(layout) {
+            draw_plus(
+                renderer,
+                button,
+                if self.disabled {
+                    dim(text_color)
+                } else {
+                    text_color
+                },
+            );
+        }
+
+        if let (Some(error), Some(error_layout)) = (&self.error, self.error_layout(layout)) {
+            if let Some(background) = style.error_background {
+                // Inset by the border, like the header
+                let inset = style.border_width;
+                let radius = (style.border_radius - inset).max(0.0);
+                let error_bounds = error_layout.bounds();
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: Rectangle {
+                            x: error_bounds.x + inset,
+                            y: error_bounds.y,
+                            width: error_bounds.width - inset * 2.0,
+                            height: error_bounds.height - inset,
+                        },
+                        border: Border {
+                            radius: [0.0, 0.0, radius, radius].into(),
+                            ..Border::default()
+                        },
+                        ..renderer::Quad::default()
+                    },
+                    background,
+                );
+            }
+
+            let error_content_layout = error_layout
+                .children()
+                .next()
+                .expect("the error layout node should have one child");
+            if content_visible(error_layout, error_content_layout) {
+                error.as_widget().draw(
+                    &tree.children[self.error_tree_index()],
+                    renderer,
+                    theme,
+                    &renderer::Style {
+                        text_color: style.error_text_color.unwrap_or(text_color),
+                    },
+                    error_content_layout,
+                    cursor,
+                    viewport,
+                );
+            }
+        }
+
+        let first_badge = self.first_badge_tree_index();
+        for (index, ((_, badge), badge_layout)) in self
+            .badges
+            .iter()
+            .zip(self.badge_layouts(layout))
+            .enumerate()
+        {
+            badge.as_widget().draw(
+                &tree.children[first_badge + index],
+                renderer,
+                theme,
+                &renderer::Style { text_color },
+                badge_layout,
+                cursor,
+                viewport,
+            );
+        }
+
+        // The outline of the copy being dragged
+        if let Some(offset) = tree.state.downcast_ref::<NodeState>().duplicate_offset {
             renderer.fill_quad(
                 renderer::Quad {
-                    bounds: blob_rect,
+                    bounds: bounds + offset,
                     border: Border {
-                        color: socket.blob_border_color.unwrap_or(style.border_color),
-                        width: style.border_width,
-                        radius: socket.blob_border_radius.into(),
+                        color: Color {
+                            a: 0.5,
+                            ..style.border_color
+                        },
+                        width: style.border_width.max(1.0),
+                        radius: style.border_radius.into(),
                     },
                     ..renderer::Quad::default()
                 },
-                Background::Color(socket.blob_color),
+                Background::Color(Color::TRANSPARENT),
             );
         }
     }
 
+    fn operate(
+        &self,
+        tree: &mut widget::Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn widget::Operation<Message>,
+    ) {
+        let mut target = FocusTarget {
+            bounds: layout.bounds(),
+            flash: false,
+        };
+        operation.custom(&mut target, self.id.as_ref());
+        if target.flash {
+            tree.state.downcast_mut::<NodeState>().flash_until =
+                Some(Instant::now() + FLASH_DURATION);
+        }
+
+        // Lets operations like `text_input::focus` reach widgets inside the node
+        operation.container(self.id.as_ref(), layout.bounds(), &mut |operation| {
+            let mut layout_children_iter = layout.children();
+            let content_layout = layout_children_iter
+                .next()
+                .expect("there should be a layout node for the graph node content");
+            self.content.as_widget().operate(
+                &mut tree.children[0],
+                content_layout,
+                renderer,
+                operation,
+            );
+
+            for ((socket, socket_tree), socket_layout) in self
+                .sockets
+                .iter()
+                .zip(&mut tree.children[1..])
+                .zip(layout_children_iter)
+            {
+                let child_layout = socket_layout
+                    .children()
+                    .next()
+                    .expect("the socket layout node should have one child");
+                socket
+                    .content
+                    .as_widget()
+                    .operate(socket_tree, child_layout, renderer, operation);
+            }
+
+            if let (Some(header), Some(header_layout)) = (&self.header, self.header_layout(layout))
+            {
+                header.as_widget().operate(
+                    &mut tree.children[self.sockets.len() + 1],
+                    header_layout
+                        .children()
+                        .next()
+                        .expect("the header layout node should have one child"),
+                    renderer,
+                    operation,
+                );
+            }
+
+            let first_badge = self.first_badge_tree_index();
+            for (index, ((_, badge), badge_layout)) in self
+                .badges
+                .iter()
+                .zip(self.badge_layouts(layout))
+                .enumerate()
+            {
+                badge.as_widget().operate(
+                    &mut tree.children[first_badge + index],
+                    badge_layout,
+                    renderer,
+                    operation,
+                );
+            }
+        });
+    }
+
     fn on_event(
         &mut self,
         tree: &mut widget::Tree,
@@ -462,49 +2180,122 @@ where
         let mut status = event::Status::Ignored;
         let state = tree.state.downcast_mut::<NodeState>();
 
-        if let Some(cursor_position) = cursor.position() {
-            if let Some(start) = state.drag_start_position {
+        if let (Event::Window(_, window::Event::RedrawRequested(now)), Some(until)) =
+            (&event, state.flash_until)
+        {
+            if *now >= until {
+                state.flash_until = None;
+            } else {
+                // Redraw once more when the flash is over, to remove it
+                shell.request_redraw(window::RedrawRequest::At(until));
+            }
+        }
+
+        if let Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) = event {
+            state.modifiers = modifiers;
+        }
+
+        if self.disabled {
+            state.drag_start_position = None;
+            state.duplicate_offset = None;
+            state.hovered = false;
+            return status;
+        }
+
+        if let Event::Mouse(mouse::Event::CursorMoved { .. } | mouse::Event::CursorLeft) = event {
+            let hovered = cursor.is_over(layout.bounds());
+            if hovered != state.hovered {
+                state.hovered = hovered;
+                shell.request_redraw(window::RedrawRequest::NextFrame);
+            }
+        }
+
+        if let Some(start) = state.drag_start_position {
+            if let Some(cursor_position) = cursor.position() {
                 match event {
                     Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
                         state.drag_start_position = None;
+                        if let (Some(offset), Some(f)) =
+                            (state.duplicate_offset.take(), &self.on_duplicate)
+                        {
+                            if offset != Vector::ZERO {
+                                shell.publish(f(
+                                    state.index,
+                                    self.position + offset * (1.0 / state.scale),
+                                ));
+                            }
+                        }
                     }
                     Event::Mouse(mouse::Event::CursorMoved { .. }) => {
                         let delta = cursor_position - start;
                         state.drag_start_position = Some(cursor_position);
-                        if let Some(f) = &self.on_translate {
-                            let message = f((delta.x, delta.y));
-                            shell.publish(message);
+                        if let Some(offset) = &mut state.duplicate_offset {
+                            *offset = *offset + delta;
+                            shell.request_redraw(window::RedrawRequest::NextFrame);
+                        } else {
+                            for message in self.drag(state, delta) {
+                                shell.publish(message);
+                            }
                         }
                         status = event::Status::Captured;
                     }
                     _ => {}
                 }
-            } else {
-                let mut layout_children_iter = layout.children();
-                let content_layout = layout_children_iter
-                    .next()
-                    .expect("there should be a layout node for the graph node content");
+            }
+        } else {
+            if let (
+                Some(cursor_position),
+                Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+            ) = (cursor.position(), &event)
+            {
+                if let Some(message) = self.add_button_message(layout, cursor_position, state.scale)
+                {
+                    shell.publish(message);
+                    return event::Status::Captured;
+                }
+            }
 
-                status = self.content.as_widget_mut().on_event(
-                    &mut tree.children[0],
+            // Children receive events even if the cursor is unavailable, so that e.g. a focused
+            // text input keeps receiving keyboard input
+            let mut layout_children_iter = layout.children();
+            let content_layout = layout_children_iter
+                .next()
+                .expect("there should be a layout node for the graph node content");
+
+            // Badges are drawn on top, so they get the first chance to capture the event
+            let first_badge = self.first_badge_tree_index();
+            let badge_layouts = self.badge_layouts(layout);
+            for (index, ((_, badge), badge_layout)) in
+                self.badges.iter_mut().zip(badge_layouts).enumerate()
+            {
+                if status == event::Status::Captured {
+                    break;
+                }
+                status = badge.as_widget_mut().on_event(
+                    &mut tree.children[first_badge + index],
                     event.clone(),
-                    content_layout,
+                    badge_layout,
                     cursor,
                     renderer,
                     clipboard,
                     shell,
                     viewport,
                 );
+            }
 
-                for (socket_index, socket_layout) in layout_children_iter.enumerate() {
-                    if status == event::Status::Captured {
-                        break;
-                    }
-
-                    status = self.sockets[socket_index].content.as_widget_mut().on_event(
-                        &mut tree.children[socket_index + 1],
+            let header_layout = self.header_layout(layout);
+            if let (Some(header), Some(header_layout)) = (&mut self.header, header_layout) {
+                let header_content_layout = header_layout
+                    .children()
+                    .next()
+                    .expect("the header layout node should have one child");
+                if status == event::Status::Ignored
+                    && content_visible(header_layout, header_content_layout)
+                {
+                    status = header.as_widget_mut().on_event(
+                        &mut tree.children[self.sockets.len() + 1],
                         event.clone(),
-                        socket_layout,
+                        header_content_layout,
                         cursor,
                         renderer,
                         clipboard,
@@ -513,12 +2304,77 @@ where
                     );
                 }
             }
+
+            // Content that is too small to be drawn must not react to clicks either
+            if status == event::Status::Ignored && content_visible(layout, content_layout) {
+                status = self.content.as_widget_mut().on_event(
+                    &mut tree.children[0],
+                    event.clone(),
+                    content_layout,
+                    cursor,
+                    renderer,
+                    clipboard,
+                    shell,
+                    viewport,
+                );
+            }
+
+            for (socket_index, socket_layout) in
+                layout_children_iter.take(self.sockets.len()).enumerate()
+            {
+                if status == event::Status::Captured {
+                    break;
+                }
+
+                let child_layout = socket_layout
+                    .children()
+                    .next()
+                    .expect("the socket layout node should have one child");
+                if !socket_content_visible(socket_layout, child_layout) {
+                    continue;
+                }
+
+                status = self.sockets[socket_index].content.as_widget_mut().on_event(
+                    &mut tree.children[socket_index + 1],
+                    event.clone(),
+                    child_layout,
+                    cursor,
+                    renderer,
+                    clipboard,
+                    shell,
+                    viewport,
+                );
+            }
+        }
+
+        // Keys captured by the children (e.g. typed into a focused text input) are left alone
+        if let (
+            Some(f),
+            event::Status::Ignored,
+            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }),
+        ) = (&self.on_bypass_toggle, status, &event)
+        {
+            let (shortcut_key, shortcut_modifiers) = &self.bypass_shortcut;
+            if state.hovered && key == shortcut_key && modifiers == shortcut_modifiers {
+                shell.publish(f(!self.bypassed));
+                return event::Status::Captured;
+            }
         }
 
         if let Some(cursor_position) = cursor.position() {
-            if status == event::Status::Ignored && layout.bounds().contains(cursor_position) {
+            if status == event::Status::Ignored
+                && !self.locked
+                && self
+                    .drag_handle(layout)
+                    .is_some_and(|handle| handle.contains(cursor_position))
+            {
                 if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event {
                     state.drag_start_position = Some(cursor_position);
+                    state.drag_origin = self.position;
+                    state.drag_offset = Vector::ZERO;
+                    state.duplicate_offset = (self.on_duplicate.is_some()
+                        && state.modifiers.contains(self.duplicate_modifier))
+                    .then_some(Vector::ZERO);
                     status = event::Status::Captured;
                 }
             }
@@ -535,13 +2391,158 @@ where
         viewport: &Rectangle,
         renderer: &Renderer,
     ) -> mouse::Interaction {
-        self.content.as_widget().mouse_interaction(
-            &tree.children[0],
-            layout,
-            cursor,
-            viewport,
+        if tree
+            .state
+            .downcast_ref::<NodeState>()
+            .drag_start_position
+            .is_some()
+        {
+            return mouse::Interaction::Grabbing;
+        }
+
+        let mut layout_children_iter = layout.children();
+        let content_layout = layout_children_iter
+            .next()
+            .expect("there should be a layout node for the graph node content");
+
+        let content_interaction = if content_visible(layout, content_layout) {
+            self.content.as_widget().mouse_interaction(
+                &tree.children[0],
+                content_layout,
+                cursor,
+                viewport,
+                renderer,
+            )
+        } else {
+            mouse::Interaction::default()
+        };
+
+        let header_interaction = self
+            .header
+            .as_ref()
+            .zip(self.header_layout(layout))
+            .and_then(|(header, header_layout)| {
+                let header_content_layout = header_layout
+                    .children()
+                    .next()
+                    .expect("the header layout node should have one child");
+                content_visible(header_layout, header_content_layout).then(|| {
+                    header.as_widget().mouse_interaction(
+                        &tree.children[self.sockets.len() + 1],
+                        header_content_layout,
+                        cursor,
+                        viewport,
+                        renderer,
+                    )
+                })
+            })
+            .unwrap_or_default();
+
+        let interaction = layout_children_iter
+            .take(self.sockets.len())
+            .enumerate()
+            .filter_map(|(socket_index, socket_layout)| {
+                let child_layout = socket_layout
+                    .children()
+                    .next()
+                    .expect("the socket layout node should have one child");
+                socket_content_visible(socket_layout, child_layout).then(|| {
+                    self.sockets[socket_index]
+                        .content
+                        .as_widget()
+                        .mouse_interaction(
+                            &tree.children[socket_index + 1],
+                            child_layout,
+                            cursor,
+                            viewport,
+                            renderer,
+                        )
+                })
+            })
+            .fold(content_interaction.max(header_interaction), std::cmp::max);
+
+        let first_badge = self.first_badge_tree_index();
+        self.badges
+            .iter()
+            .zip(self.badge_layouts(layout))
+            .enumerate()
+            .map(|(index, ((_, badge), badge_layout))| {
+                badge.as_widget().mouse_interaction(
+                    &tree.children[first_badge + index],
+                    badge_layout,
+                    cursor,
+                    viewport,
+                    renderer,
+                )
+            })
+            .fold(interaction, std::cmp::max)
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut widget::Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        // The layout of the children is already scaled, so overlays opened by them (e.g. the menu
+        // of a pick list) end up in the right place without any further transformation
+        let mut layout_children_iter = layout.children();
+        let content_layout = layout_children_iter
+            .next()
+            .expect("there should be a layout node for the graph node content");
+
+        let header_layout = self.header_layout(layout);
+        let (content_tree, other_trees) = tree
+            .children
+            .split_first_mut()
+            .expect("there should be a tree node for the graph node content");
+        let (socket_trees, header_tree) = other_trees.split_at_mut(self.sockets.len());
+
+        let mut children = Vec::new();
+        children.extend(self.content.as_widget_mut().overlay(
+            content_tree,
+            content_layout,
             renderer,
-        )
+            translation,
+        ));
+
+        for ((socket, socket_tree), socket_layout) in self
+            .sockets
+            .iter_mut()
+            .zip(socket_trees)
+            .zip(layout_children_iter)
+        {
+            let child_layout = socket_layout
+                .children()
+                .next()
+                .expect("the socket layout node should have one child");
+
+            children.extend(socket.content.as_widget_mut().overlay(
+                socket_tree,
+                child_layout,
+                renderer,
+                translation,
+            ));
+        }
+
+        if let (Some(header), Some(header_tree), Some(header_layout)) =
+            (&mut self.header, header_tree.first_mut(), header_layout)
+        {
+            children.extend(
+                header.as_widget_mut().overlay(
+                    header_tree,
+                    header_layout
+                        .children()
+                        .next()
+                        .expect("the header layout node should have one child"),
+                    renderer,
+                    translation,
+                ),
+            );
+        }
+
+        (!children.is_empty()).then(|| overlay::Group::with_children(children).overlay())
     }
 
     fn size(&self) -> Size<Length> {
@@ -552,6 +2553,43 @@ where
     }
 }
 
+// The "add pin" button of a pin array, or an "add socket" button
+fn draw_plus<Renderer>(renderer: &mut Renderer, bounds: Rectangle, color: Color)
+where
+    Renderer: renderer::Renderer,
+{
+    let thickness = (bounds.width / 4.0).max(1.0);
+    for bar in [
+        Rectangle::new(
+            Point::new(bounds.x, bounds.center_y() - thickness / 2.0),
+            Size::new(bounds.width, thickness),
+        ),
+        Rectangle::new(
+            Point::new(bounds.center_x() - thickness / 2.0, bounds.y),
+            Size::new(thickness, bounds.height),
+        ),
+    ] {
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: bar,
+                ..renderer::Quad::default()
+            },
+            Background::Color(color),
+        );
+    }
+}
+
+// Whether the node is big enough (at the current scale) to show its content
+fn content_visible(node_layout: Layout<'_>, content_layout: Layout<'_>) -> bool {
+    node_layout.bounds().width > content_layout.bounds().width
+        && node_layout.bounds().height > content_layout.bounds().height
+}
+
+fn socket_content_visible(socket_layout: Layout<'_>, child_layout: Layout<'_>) -> bool {
+    socket_layout.bounds().width > child_layout.bounds().width
+        && (socket_layout.bounds().height * 2.0) > child_layout.bounds().height
+}
+
 impl<'a, Message, Theme, Renderer> From<Node<'a, Message, Theme, Renderer>>
     for GraphNodeElement<'a, Message, Theme, Renderer>
 where
@@ -1,24 +1,35 @@
+use iced::advanced::graphics::mesh::{Indexed, SolidVertex2D};
 use iced::advanced::widget::Tree;
-use iced::advanced::{layout, renderer, Widget};
-use iced::Rectangle;
+use iced::advanced::{layout, renderer, Layout, Widget};
+use iced::time::Instant;
+use iced::{Color, Point, Rectangle, Size, Vector};
+
+use crate::graph_container::graph_to_screen;
+use crate::layout::align::bounding_box;
+use crate::routing::Channels;
+use crate::{Endpoint, Link, LogicalEndpoint, Matrix, SocketRole, SocketSide};
 use std::borrow::Borrow;
+use std::collections::HashMap;
+
+// Side length of the cells of the socket hit-testing grid, in (scaled) layout units
+const SOCKET_GRID_CELL_SIZE: f32 = 64.0;
 
 pub struct GraphNodeElement<'a, Message, Theme, Renderer> {
     widget: Box<dyn GraphWidget<'a, Message, Theme, Renderer> + 'a>,
 }
 
 pub trait GraphWidget<'a, Message, Theme, Renderer: renderer::Renderer>:
-    Widget<Message, Theme, Renderer> + ScalableWidget<Message, Renderer>
+    Widget<Message, Theme, Renderer> + ScalableWidget<Message, Renderer, Theme>
 {
     fn as_widget(&self) -> &(dyn Widget<Message, Theme, Renderer> + 'a);
     fn as_widget_mut(&mut self) -> &mut (dyn Widget<Message, Theme, Renderer> + 'a);
-    fn as_scalable_widget(&self) -> &(dyn ScalableWidget<Message, Renderer> + 'a);
+    fn as_scalable_widget(&self) -> &(dyn ScalableWidget<Message, Renderer, Theme> + 'a);
 }
 
 impl<'a, T, Message, Theme, Renderer: renderer::Renderer> GraphWidget<'a, Message, Theme, Renderer>
     for T
 where
-    T: Widget<Message, Theme, Renderer> + ScalableWidget<Message, Renderer> + 'a,
+    T: Widget<Message, Theme, Renderer> + ScalableWidget<Message, Renderer, Theme> + 'a,
 {
     fn as_widget(&self) -> &(dyn Widget<Message, Theme, Renderer> + 'a) {
         self
@@ -28,12 +39,14 @@ where
         self
     }
 
-    fn as_scalable_widget(&self) -> &(dyn ScalableWidget<Message, Renderer> + 'a) {
+    fn as_scalable_widget(&self) -> &(dyn ScalableWidget<Message, Renderer, Theme> + 'a) {
         self
     }
 }
 
-pub trait ScalableWidget<Message, Renderer>
+// `Theme` comes last and defaults to the built-in theme, so that implementations written
+// against `ScalableWidget<Message, Renderer>` keep working
+pub trait ScalableWidget<Message, Renderer, Theme = iced::Theme>
 where
     Renderer: renderer::Renderer,
 {
@@ -45,20 +58,383 @@ where
         scale: f32,
         socket_state: &mut SocketLayoutState,
     ) -> layout::Node;
+
+    // The link represented by this element, if it is a connection
+    fn link(&self) -> Option<&Link> {
+        None
+    }
+
+    // If this element can be drawn as part of a single mesh together with other elements,
+    // returns its geometry translated by `translation`. Elements returning `None` are drawn
+    // individually.
+    fn batched_mesh(
+        &self,
+        _tree: &Tree,
+        _theme: &Theme,
+        _translation: Vector,
+    ) -> Option<Indexed<SolidVertex2D>> {
+        None
+    }
+
+    // If this element is currently being dragged, moves it by `delta` (in screen space) and
+    // returns the messages that tell the application about it. Used to keep it under the cursor
+    // while the viewport is auto-panned.
+    fn drag_messages(&self, _tree: &mut Tree, _delta: Vector) -> Vec<Message> {
+        Vec::new()
+    }
+
+    // Whether any part of this element lies within the rectangle, which is relative to the top
+    // left corner of its layout bounds. Only asked about elements whose bounds intersect the
+    // visible area, so elements that fill their bounds do not need to override this.
+    fn is_visible(&self, _tree: &Tree, _rectangle: Rectangle) -> bool {
+        true
+    }
+
+    // Whether this element is currently being dragged by the user
+    fn is_dragged(&self, _tree: &Tree) -> bool {
+        false
+    }
+
+    // Whether this element contains a nested graph, which is entered by double-clicking it
+    fn has_subgraph(&self) -> bool {
+        false
+    }
+
+    // Advances the animations of this element to the given point in time, returning whether it
+    // has any that keep running and thus need another frame
+    fn animate(&self, _tree: &mut Tree, _now: Instant) -> bool {
+        false
+    }
+
+    // If this element is a connection, returns a band around its curve that is wider than the
+    // curve by `extra` on both sides, translated by `translation`. Used to highlight it.
+    fn halo_mesh(
+        &self,
+        _tree: &Tree,
+        _extra: f32,
+        _color: Color,
+        _translation: Vector,
+    ) -> Option<Indexed<SolidVertex2D>> {
+        None
+    }
+
+    // Geometry drawn on top of this element, in the same coordinates as its layout bounds, e.g.
+    // the pass-through line of a bypassed node
+    fn overlay_mesh(
+        &self,
+        _tree: &Tree,
+        _theme: &Theme,
+        _layout: Layout<'_>,
+    ) -> Option<Indexed<SolidVertex2D>> {
+        None
+    }
+
+    // If a point, relative to the top left corner of the element's layout bounds, lies on the
+    // breakpoint glyph of this element, returns whether the breakpoint is set
+    fn breakpoint_at(&self, _tree: &Tree, _point: Vector) -> Option<bool> {
+        None
+    }
+
+    // The distance from a point, relative to the top left corner of the element's layout bounds,
+    // to the curve of this element if it is a connection
+    fn distance_to(&self, _tree: &Tree, _point: Vector) -> Option<f32> {
+        None
+    }
+}
+
+// Rounds a screen-space coordinate to the nearest physical pixel
+pub(crate) fn snap_to_pixel(value: f32, scale_factor: f32) -> f32 {
+    (value * scale_factor).round() / scale_factor
 }
 
 #[derive(Debug)]
 pub struct SocketLayoutState {
+    pub(crate) nodes: Vec<Rectangle>,
+    pub(crate) locked: Vec<bool>,
     pub(crate) inputs: Vec<Vec<Rectangle>>,
     pub(crate) outputs: Vec<Vec<Rectangle>>,
+    // The areas around the blobs that react to the mouse, which may be larger than the blobs
+    pub(crate) input_hit_areas: Vec<Vec<Rectangle>>,
+    pub(crate) output_hit_areas: Vec<Vec<Rectangle>>,
+    pub(crate) input_sides: Vec<Vec<SocketSide>>,
+    pub(crate) output_sides: Vec<Vec<SocketSide>>,
+    // For every node, the sockets that have a name, pointing at their first pin
+    pub(crate) socket_names: Vec<HashMap<String, LogicalEndpoint>>,
     pub(crate) done: bool,
+    // The scale factor of the window, see `GraphContainer::scale_factor`
+    pub(crate) scale_factor: f32,
+    // Whether nodes should leave out their blobs, see `GraphContainer::min_socket_scale`
+    pub(crate) blobs_hidden: bool,
+    // Spatial hash of all blobs, mapping grid cells to the sockets whose blobs overlap them
+    pub(crate) socket_grid: HashMap<(i32, i32), Vec<LogicalEndpoint>>,
+    // See `GraphContainer::edge_bundling`
+    pub(crate) bundle_strength: f32,
+    // For every socket with several links, the average position of the other ends of its links
+    pub(crate) bundle_targets: HashMap<LogicalEndpoint, Vector>,
+    // The channels taken by orthogonal connections, see `Routing::Orthogonal`
+    pub(crate) channels: Channels,
+    // A hash of the bounds of all nodes, which connections routed around them depend on; set by
+    // the first connection
+    pub(crate) obstacles_hash: u64,
+}
+
+// Looks up a socket name in the names of every node, as collected by `SocketLayoutState`
+pub(crate) fn named_socket(
+    socket_names: &[HashMap<String, LogicalEndpoint>],
+    node_index: usize,
+    name: &str,
+) -> Option<LogicalEndpoint> {
+    socket_names.get(node_index)?.get(name).copied()
 }
 
 impl SocketLayoutState {
     pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.locked.clear();
         self.inputs.clear();
         self.outputs.clear();
+        self.input_hit_areas.clear();
+        self.output_hit_areas.clear();
+        self.input_sides.clear();
+        self.output_sides.clear();
+        self.socket_names.clear();
         self.done = false;
+        self.socket_grid.clear();
+        self.bundle_targets.clear();
+        self.channels.clear();
+    }
+
+    // The socket with the given `Socket::name` on the given node
+    pub(crate) fn named_socket(&self, node_index: usize, name: &str) -> Option<LogicalEndpoint> {
+        named_socket(&self.socket_names, node_index, name)
+    }
+
+    // The socket an endpoint refers to, by index or by name
+    pub(crate) fn logical_endpoint(&self, endpoint: &Endpoint) -> Option<LogicalEndpoint> {
+        match endpoint {
+            Endpoint::Socket(logical) => Some(*logical),
+            Endpoint::NamedSocket(node_index, name) => self.named_socket(*node_index, name),
+            Endpoint::Absolute(_) | Endpoint::NodeAnchor(..) => None,
+        }
+    }
+
+    // Rebuilds the spatial hash used by `socket_at`; to be called once all nodes are laid out
+    pub(crate) fn build_socket_grid(&mut self) {
+        self.socket_grid.clear();
+
+        for (role, node_sockets) in [
+            (SocketRole::In, &self.input_hit_areas),
+            (SocketRole::Out, &self.output_hit_areas),
+        ] {
+            for (node_index, sockets) in node_sockets.iter().enumerate() {
+                for (socket_index, hit_area) in sockets.iter().enumerate() {
+                    let (min_x, min_y) = grid_cell(Point::new(hit_area.x, hit_area.y));
+                    let (max_x, max_y) = grid_cell(Point::new(
+                        hit_area.x + hit_area.width,
+                        hit_area.y + hit_area.height,
+                    ));
+
+                    for x in min_x..=max_x {
+                        for y in min_y..=max_y {
+                            self.socket_grid
+                                .entry((x, y))
+                                .or_default()
+                                .push(LogicalEndpoint {
+                                    node_index,
+                                    role,
+                                    socket_index,
+                                });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn hit_area(&self, endpoint: &LogicalEndpoint) -> Option<Rectangle> {
+        let node_sockets = match endpoint.role {
+            SocketRole::In => &self.input_hit_areas,
+            SocketRole::Out => &self.output_hit_areas,
+        };
+
+        node_sockets
+            .get(endpoint.node_index)
+            .and_then(|sockets| sockets.get(endpoint.socket_index))
+            .copied()
+    }
+
+    pub(crate) fn blob(&self, endpoint: &LogicalEndpoint) -> Option<Rectangle> {
+        let node_sockets = match endpoint.role {
+            SocketRole::In => &self.inputs,
+            SocketRole::Out => &self.outputs,
+        };
+
+        node_sockets
+            .get(endpoint.node_index)
+            .and_then(|sockets| sockets.get(endpoint.socket_index))
+            .copied()
+    }
+
+    // All sockets of unlocked nodes in keyboard navigation order: node by node, inputs first
+    pub(crate) fn navigation_order(&self) -> Vec<LogicalEndpoint> {
+        let mut order = Vec::new();
+        for (node_index, locked) in self.locked.iter().enumerate() {
+            if *locked {
+                continue;
+            }
+
+            for (role, node_sockets) in [
+                (SocketRole::In, &self.inputs),
+                (SocketRole::Out, &self.outputs),
+            ] {
+                let count = node_sockets.get(node_index).map_or(0, Vec::len);
+                order.extend((0..count).map(|socket_index| LogicalEndpoint {
+                    node_index,
+                    role,
+                    socket_index,
+                }));
+            }
+        }
+        order
+    }
+
+    // Finds the socket whose hit area contains the given (scaled, untranslated) point, ignoring
+    // the sockets of locked nodes. If several hit areas overlap, the last one in layout order
+    // wins.
+    pub(crate) fn socket_at(&self, point: Point) -> Option<LogicalEndpoint> {
+        self.socket_grid
+            .get(&grid_cell(point))?
+            .iter()
+            .rev()
+            .filter(|endpoint| {
+                !self
+                    .locked
+                    .get(endpoint.node_index)
+                    .copied()
+                    .unwrap_or(false)
+            })
+            .find(|endpoint| {
+                self.hit_area(endpoint)
+                    .is_some_and(|hit_area| hit_area.contains(point))
+            })
+            .copied()
+    }
+
+    // The union of the bounds of all nodes in graph space, or `None` if there are no nodes
+    pub(crate) fn graph_bounds(&self, scale: f32) -> Option<Rectangle> {
+        bounding_box(&self.nodes).map(|bounds| {
+            Rectangle::new(
+                Point::new(bounds.x / scale, bounds.y / scale),
+                Size::new(bounds.width / scale, bounds.height / scale),
+            )
+        })
+    }
+
+    // The sizes of the nodes in graph space
+    pub(crate) fn node_sizes(&self, scale: f32) -> Vec<Size> {
+        self.nodes
+            .iter()
+            .map(|node| Size::new(node.width / scale, node.height / scale))
+            .collect()
+    }
+
+    // The stored blob rectangles are scaled, but not translated; undo the scaling to get
+    // graph-space positions.
+    pub(crate) fn positions(&self, scale: f32) -> Vec<SocketPosition> {
+        let unscale = |rectangle: &Rectangle| {
+            Rectangle::new(
+                Point::new(rectangle.x / scale, rectangle.y / scale),
+                Size::new(rectangle.width / scale, rectangle.height / scale),
+            )
+        };
+
+        let mut positions = Vec::new();
+        for (role, blobs, hit_areas, sides) in [
+            (
+                SocketRole::In,
+                &self.inputs,
+                &self.input_hit_areas,
+                &self.input_sides,
+            ),
+            (
+                SocketRole::Out,
+                &self.outputs,
+                &self.output_hit_areas,
+                &self.output_sides,
+            ),
+        ] {
+            for (node_index, sockets) in blobs.iter().enumerate() {
+                for (socket_index, blob) in sockets.iter().enumerate() {
+                    let hit_area = hit_areas
+                        .get(node_index)
+                        .and_then(|hit_areas| hit_areas.get(socket_index))
+                        .unwrap_or(blob);
+                    let side = sides
+                        .get(node_index)
+                        .and_then(|sides| sides.get(socket_index))
+                        .copied()
+                        .unwrap_or(match role {
+                            SocketRole::In => SocketSide::Left,
+                            SocketRole::Out => SocketSide::Right,
+                        });
+
+                    positions.push(SocketPosition {
+                        endpoint: LogicalEndpoint {
+                            node_index,
+                            role,
+                            socket_index,
+                        },
+                        center: Point::new(blob.center_x() / scale, blob.center_y() / scale),
+                        side,
+                        blob: unscale(blob),
+                        hit_area: unscale(hit_area),
+                    });
+                }
+            }
+        }
+
+        positions
+    }
+}
+
+fn grid_cell(point: Point) -> (i32, i32) {
+    (
+        (point.x / SOCKET_GRID_CELL_SIZE).floor() as i32,
+        (point.y / SOCKET_GRID_CELL_SIZE).floor() as i32,
+    )
+}
+
+// The computed geometry of a socket in graph space, e.g. for widgets that are drawn on top of the
+// graph, see `GraphContainer::on_socket_layout`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SocketPosition {
+    pub endpoint: LogicalEndpoint,
+    pub center: Point,
+    pub side: SocketSide,
+    // The blob that is drawn for the socket
+    pub blob: Rectangle,
+    // The area that reacts to the mouse, which may be larger than the blob
+    pub hit_area: Rectangle,
+}
+
+impl SocketPosition {
+    // Converts the rectangles into absolute (window) coordinates, given the matrix and the bounds
+    // of the graph container, like `graph_to_screen`
+    pub fn to_screen(self, matrix: &Matrix, bounds: Rectangle) -> Self {
+        let scale = matrix.get_scale();
+        let convert = |rectangle: Rectangle| {
+            Rectangle::new(
+                graph_to_screen(matrix, bounds, rectangle.position()),
+                Size::new(rectangle.width * scale, rectangle.height * scale),
+            )
+        };
+
+        Self {
+            center: graph_to_screen(matrix, bounds, self.center),
+            blob: convert(self.blob),
+            hit_area: convert(self.hit_area),
+            ..self
+        }
     }
 }
 
@@ -80,7 +456,7 @@ where
         self.widget.as_widget_mut()
     }
 
-    pub fn as_scalable_widget(&self) -> &dyn ScalableWidget<Message, Renderer> {
+    pub fn as_scalable_widget(&self) -> &dyn ScalableWidget<Message, Renderer, Theme> {
         self.widget.as_scalable_widget()
     }
 }
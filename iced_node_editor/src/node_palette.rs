@@ -0,0 +1,580 @@
+use iced::advanced::text::{self, Paragraph as _};
+use iced::advanced::{layout, overlay, renderer, Clipboard, Layout, Shell};
+use iced::{
+    alignment, event, keyboard, mouse, Background, Border, Color, Event, Pixels, Point, Rectangle,
+    Size,
+};
+
+use crate::styles::graph_container::StyleSheet;
+
+// A kind of node that can be created from the node palette
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteEntry {
+    // Passed to `on_create`, so that the application knows which node to create
+    pub id: String,
+    // Shown in the palette and matched against the search
+    pub name: String,
+    // Additional words that are matched against the search, e.g. a category or synonyms
+    pub keywords: Vec<String>,
+}
+
+impl PaletteEntry {
+    pub fn new(id: impl Into<String>, name: impl Into<String>) -> Self {
+        PaletteEntry {
+            id: id.into(),
+            name: name.into(),
+            keywords: vec![],
+        }
+    }
+
+    pub fn keywords(mut self, keywords: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.keywords = keywords.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+// A searchable list of node kinds, shown on top of the graph container. It is opened by
+// double-clicking the canvas, or by pressing Tab while the cursor is over the container (unless
+// Tab is used for keyboard navigation). Picking an entry produces `on_create` with the ID of
+// the entry and the graph position the palette was opened at.
+pub struct NodePalette<'a, Message> {
+    entries: Vec<PaletteEntry>,
+    on_create: Box<dyn Fn(String, Point) -> Message + 'a>,
+    placeholder: String,
+    width: f32,
+    max_results: usize,
+    text_size: Option<Pixels>,
+}
+
+impl<'a, Message> NodePalette<'a, Message> {
+    pub fn new<F>(entries: Vec<PaletteEntry>, on_create: F) -> Self
+    where
+        F: 'a + Fn(String, Point) -> Message,
+    {
+        NodePalette {
+            entries,
+            on_create: Box::new(on_create),
+            placeholder: String::from("Add node..."),
+            width: 240.0,
+            max_results: 8,
+            text_size: None,
+        }
+    }
+
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    // How many results are visible at once. The list scrolls to keep the selected one in view.
+    pub fn max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results.max(1);
+        self
+    }
+
+    pub fn text_size(mut self, text_size: impl Into<Pixels>) -> Self {
+        self.text_size = Some(text_size.into());
+        self
+    }
+
+    // The indices of the entries that match the query, best match first
+    fn matches(&self, query: &str) -> Vec<usize> {
+        let mut scored: Vec<(i32, usize)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let keyword_score = entry
+                    .keywords
+                    .iter()
+                    .filter_map(|keyword| fuzzy_score(query, keyword))
+                    .max()
+                    // Prefer matches in the name
+                    .map(|score| score - KEYWORD_PENALTY);
+                fuzzy_score(query, &entry.name)
+                    .max(keyword_score)
+                    .map(|score| (score, index))
+            })
+            .collect();
+
+        // Stable, so entries with the same score keep their order
+        scored.sort_by_key(|(score, _)| -score);
+        scored.into_iter().map(|(_, index)| index).collect()
+    }
+}
+
+pub fn node_palette<'a, Message, F>(
+    entries: Vec<PaletteEntry>,
+    on_create: F,
+) -> NodePalette<'a, Message>
+where
+    F: 'a + Fn(String, Point) -> Message,
+{
+    NodePalette::new(entries, on_create)
+}
+
+// Scores how well the query matches the text. All characters of the query have to appear in
+// the text in order, ignoring case. Runs of consecutive characters and characters at the start
+// of a word score higher.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+
+    for c in text.chars() {
+        let Some(&wanted) = query.peek() else {
+            break;
+        };
+
+        if c.to_lowercase().eq(std::iter::once(wanted)) {
+            score += 1;
+            if previous_matched {
+                score += 4;
+            }
+
+            let word_start = previous.is_none_or(|previous| {
+                !previous.is_alphanumeric() || (previous.is_lowercase() && c.is_uppercase())
+            });
+            if word_start {
+                score += 3;
+            }
+
+            query.next();
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+
+        previous = Some(c);
+    }
+
+    query.peek().is_none().then_some(score)
+}
+
+// The state of an open palette, kept by the graph container
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PaletteState {
+    // Where the palette was opened, in absolute and in graph coordinates
+    pub position: Point,
+    pub graph_position: Point,
+    query: String,
+    // Index into the matches
+    selected: usize,
+}
+
+impl PaletteState {
+    pub fn new(position: Point, graph_position: Point) -> Self {
+        PaletteState {
+            position,
+            graph_position,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+}
+
+pub(crate) struct PaletteOverlay<'a, 'b, Message, Theme>
+where
+    Theme: StyleSheet,
+{
+    pub palette: &'b NodePalette<'a, Message>,
+    // Set to `None` to close the palette
+    pub state: &'b mut Option<PaletteState>,
+    pub style: &'b Theme::Style,
+    // Added to the position of the palette state
+    pub translation: iced::Vector,
+}
+
+impl<'a, 'b, Message, Theme> PaletteOverlay<'a, 'b, Message, Theme>
+where
+    Theme: StyleSheet,
+{
+    fn row_height<Renderer: text::Renderer>(&self, renderer: &Renderer) -> f32 {
+        self.text_size(renderer).0 * ROW_HEIGHT
+    }
+
+    fn text_size<Renderer: text::Renderer>(&self, renderer: &Renderer) -> Pixels {
+        self.palette
+            .text_size
+            .unwrap_or_else(|| renderer.default_size())
+    }
+
+    fn create(&mut self, shell: &mut Shell<'_, Message>, entry: usize) {
+        if let Some(state) = self.state.take() {
+            let id = self.palette.entries[entry].id.clone();
+            shell.publish((self.palette.on_create)(id, state.graph_position));
+        }
+    }
+}
+
+impl<'a, 'b, Message, Theme, Renderer> overlay::Overlay<Message, Theme, Renderer>
+    for PaletteOverlay<'a, 'b, Message, Theme>
+where
+    Theme: StyleSheet,
+    Renderer: renderer::Renderer + text::Renderer,
+{
+    fn layout(&mut self, renderer: &Renderer, bounds: Size) -> layout::Node {
+        let Some(state) = &self.state else {
+            return layout::Node::new(Size::ZERO);
+        };
+
+        let rows = self
+            .palette
+            .matches(&state.query)
+            .len()
+            .clamp(1, self.palette.max_results);
+        let size = Size::new(
+            self.palette.width,
+            self.row_height(renderer) * (rows + 1) as f32 + PADDING * 3.0,
+        );
+
+        // Keep the palette inside the window
+        let position = state.position + self.translation;
+        layout::Node::new(size).move_to(Point::new(
+            position.x.min(bounds.width - size.width).max(0.0),
+            position.y.min(bounds.height - size.height).max(0.0),
+        ))
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let row_height = self.row_height(renderer);
+        let max_results = self.palette.max_results;
+        let Some(state) = self.state.as_mut() else {
+            return event::Status::Ignored;
+        };
+
+        let bounds = layout.bounds();
+        let matches = self.palette.matches(&state.query);
+
+        match event {
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key,
+                modifiers,
+                text,
+                ..
+            }) => {
+                use keyboard::key::Named;
+
+                let step = |selected: usize, forward: bool| match (matches.len(), forward) {
+                    (0, _) => 0,
+                    (len, true) => (selected + 1) % len,
+                    (len, false) => (selected + len - 1) % len,
+                };
+
+                match key {
+                    keyboard::Key::Named(Named::Escape) => {
+                        *self.state = None;
+                    }
+                    keyboard::Key::Named(Named::Enter) => {
+                        if let Some(&entry) = matches.get(state.selected) {
+                            self.create(shell, entry);
+                        }
+                    }
+                    keyboard::Key::Named(Named::ArrowDown) => {
+                        state.selected = step(state.selected, true);
+                    }
+                    keyboard::Key::Named(Named::ArrowUp) => {
+                        state.selected = step(state.selected, false);
+                    }
+                    keyboard::Key::Named(Named::Tab) => {
+                        state.selected = step(state.selected, !modifiers.shift());
+                    }
+                    keyboard::Key::Named(Named::Backspace) => {
+                        state.query.pop();
+                        state.selected = 0;
+                    }
+                    _ => {
+                        let text = text.filter(|_| !modifiers.command());
+                        if let Some(text) = text {
+                            let typed: String = text.chars().filter(|c| !c.is_control()).collect();
+                            if !typed.is_empty() {
+                                state.query.push_str(&typed);
+                                state.selected = 0;
+                            }
+                        }
+                    }
+                }
+
+                // The graph must not react to keys while the palette is open
+                event::Status::Captured
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                if let Some(cursor_position) = cursor.position() {
+                    let hovered = result_rows(
+                        bounds,
+                        row_height,
+                        max_results,
+                        state.selected,
+                        matches.len(),
+                    )
+                    .find(|(_, row)| row.contains(cursor_position));
+                    if let Some((index, _)) = hovered {
+                        state.selected = index;
+                    }
+                }
+
+                event::Status::Ignored
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(_)) => {
+                match cursor.position() {
+                    Some(cursor_position) if bounds.contains(cursor_position) => {
+                        let clicked = result_rows(
+                            bounds,
+                            row_height,
+                            max_results,
+                            state.selected,
+                            matches.len(),
+                        )
+                        .find(|(_, row)| row.contains(cursor_position));
+                        if let Some((index, _)) = clicked {
+                            self.create(shell, matches[index]);
+                        }
+                    }
+                    // Clicking anywhere else dismisses the palette
+                    _ => *self.state = None,
+                }
+
+                event::Status::Captured
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { .. }) if cursor.is_over(bounds) => {
+                event::Status::Captured
+            }
+            _ => event::Status::Ignored,
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let (Some(state), Some(cursor_position)) = (&self.state, cursor.position()) else {
+            return mouse::Interaction::default();
+        };
+
+        let row_height = self.row_height(renderer);
+        let match_count = self.palette.matches(&state.query).len();
+        if result_rows(
+            layout.bounds(),
+            row_height,
+            self.palette.max_results,
+            state.selected,
+            match_count,
+        )
+        .any(|(_, row)| row.contains(cursor_position))
+        {
+            mouse::Interaction::Pointer
+        } else if layout.bounds().contains(cursor_position) {
+            mouse::Interaction::Text
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        renderer_style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+    ) {
+        let Some(state) = &self.state else {
+            return;
+        };
+
+        let style = theme.node_palette(self.style);
+        let bounds = layout.bounds();
+        let text_color = style.text_color.unwrap_or(renderer_style.text_color);
+        let placeholder_color = style.placeholder_color.unwrap_or(Color {
+            a: 0.5,
+            ..text_color
+        });
+        let text_size = self.text_size(renderer);
+        let row_height = self.row_height(renderer);
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds,
+                border: Border {
+                    color: style.border_color,
+                    width: style.border_width,
+                    radius: style.border_radius.into(),
+                },
+                shadow: style.shadow.unwrap_or_default(),
+            },
+            style
+                .background
+                .unwrap_or(Background::Color(Color::TRANSPARENT)),
+        );
+
+        let label = text::Text {
+            content: "",
+            bounds: Size::new(bounds.width - PADDING * 4.0, row_height),
+            size: text_size,
+            line_height: text::LineHeight::default(),
+            font: renderer.default_font(),
+            horizontal_alignment: alignment::Horizontal::Left,
+            vertical_alignment: alignment::Vertical::Center,
+            shaping: text::Shaping::Advanced,
+        };
+
+        // The search field, with a caret after the query
+        let field = Rectangle {
+            x: bounds.x + PADDING,
+            y: bounds.y + PADDING,
+            width: bounds.width - PADDING * 2.0,
+            height: row_height,
+        };
+        let text_position = Point::new(field.x + PADDING, field.center_y());
+        let caret_x = if state.query.is_empty() {
+            renderer.fill_text(
+                text::Text {
+                    content: &self.palette.placeholder,
+                    ..label
+                },
+                text_position,
+                placeholder_color,
+                bounds,
+            );
+            text_position.x
+        } else {
+            let width = Renderer::Paragraph::with_text(text::Text {
+                content: &state.query,
+                ..label
+            })
+            .min_width();
+            renderer.fill_text(
+                text::Text {
+                    content: &state.query,
+                    ..label
+                },
+                text_position,
+                text_color,
+                bounds,
+            );
+            text_position.x + width
+        };
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: Rectangle {
+                    x: caret_x.min(field.x + field.width - PADDING),
+                    y: field.center_y() - text_size.0 / 2.0,
+                    width: 1.0,
+                    height: text_size.0,
+                },
+                ..renderer::Quad::default()
+            },
+            Background::Color(text_color),
+        );
+
+        // Separates the search field from the results
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: Rectangle {
+                    x: bounds.x,
+                    y: field.y + field.height + PADDING / 2.0,
+                    width: bounds.width,
+                    height: style.border_width,
+                },
+                ..renderer::Quad::default()
+            },
+            Background::Color(style.border_color),
+        );
+
+        let matches = self.palette.matches(&state.query);
+        if matches.is_empty() {
+            renderer.fill_text(
+                text::Text {
+                    content: "No matches",
+                    ..label
+                },
+                Point::new(text_position.x, field.center_y() + row_height + PADDING),
+                placeholder_color,
+                bounds,
+            );
+            return;
+        }
+
+        let rows = result_rows(
+            bounds,
+            row_height,
+            self.palette.max_results,
+            state.selected,
+            matches.len(),
+        );
+        for (index, row) in rows {
+            if let (true, Some(background)) = (index == state.selected, style.selected_background) {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: row,
+                        border: Border {
+                            radius: (style.border_radius - PADDING).max(0.0).into(),
+                            ..Border::default()
+                        },
+                        ..renderer::Quad::default()
+                    },
+                    background,
+                );
+            }
+
+            renderer.fill_text(
+                text::Text {
+                    content: &self.palette.entries[matches[index]].name,
+                    ..label
+                },
+                Point::new(row.x + PADDING, row.center_y()),
+                text_color,
+                bounds,
+            );
+        }
+    }
+}
+
+// The bounds of the visible results, along with their index into the matches. The results
+// scroll such that the selected one is in view.
+fn result_rows(
+    bounds: Rectangle,
+    row_height: f32,
+    max_results: usize,
+    selected: usize,
+    match_count: usize,
+) -> impl Iterator<Item = (usize, Rectangle)> {
+    let first = selected.saturating_sub(max_results.saturating_sub(1));
+    let last = match_count.min(first + max_results);
+
+    (first..last).map(move |index| {
+        (
+            index,
+            Rectangle {
+                x: bounds.x + PADDING,
+                y: bounds.y + PADDING + row_height * (1 + index - first) as f32 + PADDING,
+                width: bounds.width - PADDING * 2.0,
+                height: row_height,
+            },
+        )
+    })
+}
+
+// Space around the search field and the results, in pixels
+const PADDING: f32 = 4.0;
+// Height of a row, relative to the text size
+const ROW_HEIGHT: f32 = 1.6;
+// Subtracted from the score of a match in the keywords of an entry
+const KEYWORD_PENALTY: i32 = 2;
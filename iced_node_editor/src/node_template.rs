@@ -0,0 +1,44 @@
+use iced::advanced::renderer;
+use iced::{Element, Point};
+
+use crate::node::Node;
+use crate::styles::node::StyleSheet;
+
+// Holds the settings that many nodes of one kind share (sockets, size, padding, style, drag
+// behaviour), so that they are only written down once. They are given as a function that
+// applies them to a node with the regular `Node` builder methods, e.g.
+// `NodeTemplate::new(|node| node.padding(10.0).width(Length::Fixed(200.0)))`. Every call to
+// `instantiate` builds a node at the given position with them; the result is a regular `Node`,
+// so anything that differs per node (e.g. `on_translate`) can still be set on it.
+pub struct NodeTemplate<'a, Message, Theme, Renderer>
+where
+    Theme: StyleSheet,
+    Renderer: renderer::Renderer,
+{
+    // Called once per node, as nodes (and the elements in their sockets) cannot be cloned
+    build:
+        Box<dyn Fn(Node<'a, Message, Theme, Renderer>) -> Node<'a, Message, Theme, Renderer> + 'a>,
+}
+
+impl<'a, Message, Theme, Renderer> NodeTemplate<'a, Message, Theme, Renderer>
+where
+    Theme: StyleSheet,
+    Renderer: renderer::Renderer,
+{
+    pub fn new<F>(build: F) -> Self
+    where
+        F: Fn(Node<'a, Message, Theme, Renderer>) -> Node<'a, Message, Theme, Renderer> + 'a,
+    {
+        NodeTemplate {
+            build: Box::new(build),
+        }
+    }
+
+    pub fn instantiate(
+        &self,
+        position: Point,
+        content: impl Into<Element<'a, Message, Theme, Renderer>>,
+    ) -> Node<'a, Message, Theme, Renderer> {
+        (self.build)(Node::new(content).position(position))
+    }
+}
@@ -0,0 +1,80 @@
+use std::any::Any;
+
+use iced::advanced::widget::operation::Outcome;
+use iced::advanced::widget::{Id, Operation};
+use iced::{Command, Rectangle, Vector};
+
+// Passed to `Operation::custom` by the graph container, before its nodes are operated on
+pub(crate) struct GraphViewport {
+    pub bounds: Rectangle,
+}
+
+// Passed to `Operation::custom` by every node, with the node's ID
+pub(crate) struct FocusTarget {
+    pub bounds: Rectangle,
+    // Set by the operation if the node should flash its border
+    pub flash: bool,
+}
+
+// Centers the viewport of the graph container on the node with the given ID (set with
+// `Node::id`). The translation is produced as a message, with the same signature as
+// `GraphContainer::on_translate`. If `flash` is set, the border of the node flashes briefly, so
+// that it can be spotted more easily.
+pub fn focus_node<Message: 'static>(
+    id: Id,
+    flash: bool,
+    on_translate: impl Fn((f32, f32)) -> Message + 'static,
+) -> Command<Message> {
+    Command::widget(FocusNode {
+        id,
+        flash,
+        viewport: None,
+        translation: None,
+        on_translate: Box::new(on_translate),
+    })
+}
+
+struct FocusNode<Message> {
+    id: Id,
+    flash: bool,
+    // The bounds of the graph container that is currently being traversed
+    viewport: Option<Rectangle>,
+    translation: Option<Vector>,
+    on_translate: Box<dyn Fn((f32, f32)) -> Message>,
+}
+
+impl<Message> Operation<Message> for FocusNode<Message> {
+    fn container(
+        &mut self,
+        _id: Option<&Id>,
+        _bounds: Rectangle,
+        operate_on_children: &mut dyn FnMut(&mut dyn Operation<Message>),
+    ) {
+        operate_on_children(self);
+    }
+
+    fn custom(&mut self, state: &mut dyn Any, id: Option<&Id>) {
+        if let Some(viewport) = state.downcast_ref::<GraphViewport>() {
+            self.viewport = Some(viewport.bounds);
+            return;
+        }
+
+        if id != Some(&self.id) {
+            return;
+        }
+
+        if let Some(target) = state.downcast_mut::<FocusTarget>() {
+            target.flash = self.flash;
+            if let Some(viewport) = self.viewport {
+                self.translation = Some(viewport.center() - target.bounds.center());
+            }
+        }
+    }
+
+    fn finish(&self) -> Outcome<Message> {
+        match self.translation {
+            Some(translation) => Outcome::Some((self.on_translate)((translation.x, translation.y))),
+            None => Outcome::None,
+        }
+    }
+}
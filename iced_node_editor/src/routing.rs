@@ -0,0 +1,321 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use iced::{Point, Rectangle, Vector};
+
+// Searches larger than this many grid cells are given up on, to keep layout fast
+const MAX_CELLS: usize = 40_000;
+// Smaller cells would make even short searches too big, and are of no use on screen anyway
+const MIN_CELL_SIZE: f32 = 0.5;
+// Obstacles this many cells away from the direct line between the endpoints are still considered
+const SEARCH_MARGIN: f32 = 4.0;
+const STEP_COST: u32 = 10;
+// Extra cost for changing direction, so that paths prefer long straight runs
+const TURN_COST: u32 = 5;
+
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+// Channels are searched for this many steps of the spacing to either side
+const MAX_CHANNEL_OFFSET: i32 = 16;
+
+// The middle segments of the orthogonal connections laid out so far, so that parallel segments
+// of later connections can be moved beside them instead of on top of them
+#[derive(Debug, Default)]
+pub(crate) struct Channels {
+    segments: Vec<ChannelSegment>,
+}
+
+#[derive(Debug)]
+struct ChannelSegment {
+    vertical: bool,
+    // The x coordinate of a vertical segment, or the y coordinate of a horizontal one
+    position: f32,
+    start: f32,
+    end: f32,
+}
+
+impl Channels {
+    pub(crate) fn clear(&mut self) {
+        self.segments.clear();
+    }
+
+    // Reserves a channel for a segment spanning `start..end`, as near to `position` as possible
+    // in steps of `spacing`, such that it keeps at least half the spacing away from the
+    // overlapping parallel segments reserved before. Returns the position of the channel.
+    pub(crate) fn claim(
+        &mut self,
+        vertical: bool,
+        position: f32,
+        (start, end): (f32, f32),
+        spacing: f32,
+    ) -> f32 {
+        let free = |candidate: f32| {
+            !self.segments.iter().any(|segment| {
+                segment.vertical == vertical
+                    && (segment.position - candidate).abs() < spacing / 2.0
+                    && segment.start < end
+                    && start < segment.end
+            })
+        };
+
+        let position = if spacing > 0.0 {
+            (0..=MAX_CHANNEL_OFFSET)
+                .flat_map(|step| [step, -step])
+                .map(|step| position + step as f32 * spacing)
+                .find(|candidate| free(*candidate))
+                .unwrap_or(position)
+        } else {
+            position
+        };
+
+        self.segments.push(ChannelSegment {
+            vertical,
+            position,
+            start,
+            end,
+        });
+        position
+    }
+}
+
+// Finds an orthogonal path from `from` to `to` that does not pass through any of the obstacles,
+// using A* on a grid with the given cell size. Returns the corners of the path between the
+// endpoints (not including them), or `None` if there is no path, the search area is too big or
+// the cell size is too small, in which case the connection is drawn without avoiding the nodes.
+pub(crate) fn find_path(
+    from: Vector,
+    to: Vector,
+    obstacles: &[Rectangle],
+    cell_size: f32,
+) -> Option<Vec<Vector>> {
+    let finite = |point: Vector| point.x.is_finite() && point.y.is_finite();
+    if !(cell_size.is_finite() && cell_size >= MIN_CELL_SIZE && finite(from) && finite(to)) {
+        return None;
+    }
+
+    let endpoints = Rectangle::new(Point::new(from.x, from.y), iced::Size::ZERO)
+        .union(&Rectangle::new(Point::new(to.x, to.y), iced::Size::ZERO))
+        .expand(cell_size * SEARCH_MARGIN);
+    let obstacles: Vec<Rectangle> = obstacles
+        .iter()
+        .filter(|obstacle| obstacle.intersects(&endpoints))
+        .copied()
+        .collect();
+    let area = obstacles
+        .iter()
+        .fold(endpoints, |area, obstacle| area.union(obstacle))
+        .expand(cell_size * 2.0);
+
+    let grid = Grid {
+        origin: Vector::new(area.x, area.y),
+        cell_size,
+        columns: (area.width / cell_size).ceil() as usize,
+        rows: (area.height / cell_size).ceil() as usize,
+    };
+    let cell_count = grid.columns.checked_mul(grid.rows)?;
+    if cell_count > MAX_CELLS {
+        return None;
+    }
+
+    let start = grid.cell_at(from);
+    let goal = grid.cell_at(to);
+    let blocked: Vec<bool> = (0..cell_count)
+        .map(|index| {
+            let center = grid.center(index);
+            let center = Point::new(center.x, center.y);
+            index != start
+                && index != goal
+                && obstacles.iter().any(|obstacle| obstacle.contains(center))
+        })
+        .collect();
+
+    let cells = grid.path(start, goal, &blocked)?;
+
+    // Only keep the cells where the path changes direction
+    let corners = cells
+        .windows(3)
+        .filter(|window| grid.step(window[0], window[1]) != grid.step(window[1], window[2]))
+        .map(|window| grid.center(window[1]))
+        .collect();
+
+    Some(corners)
+}
+
+struct Grid {
+    origin: Vector,
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+}
+
+impl Grid {
+    fn cell_at(&self, point: Vector) -> usize {
+        let column = ((point.x - self.origin.x) / self.cell_size) as usize;
+        let row = ((point.y - self.origin.y) / self.cell_size) as usize;
+        row.min(self.rows - 1) * self.columns + column.min(self.columns - 1)
+    }
+
+    fn center(&self, index: usize) -> Vector {
+        let (column, row) = (index % self.columns, index / self.columns);
+        self.origin
+            + Vector::new(
+                (column as f32 + 0.5) * self.cell_size,
+                (row as f32 + 0.5) * self.cell_size,
+            )
+    }
+
+    fn neighbour(&self, index: usize, (dx, dy): (i32, i32)) -> Option<usize> {
+        let column = (index % self.columns) as i32 + dx;
+        let row = (index / self.columns) as i32 + dy;
+        (column >= 0 && row >= 0 && (column as usize) < self.columns && (row as usize) < self.rows)
+            .then(|| row as usize * self.columns + column as usize)
+    }
+
+    fn step(&self, from: usize, to: usize) -> (i32, i32) {
+        let (from_column, from_row) = ((from % self.columns) as i32, (from / self.columns) as i32);
+        let (to_column, to_row) = ((to % self.columns) as i32, (to / self.columns) as i32);
+        (to_column - from_column, to_row - from_row)
+    }
+
+    fn heuristic(&self, index: usize, goal: usize) -> u32 {
+        let (dx, dy) = self.step(index, goal);
+        (dx.unsigned_abs() + dy.unsigned_abs()) * STEP_COST
+    }
+
+    // A* over (cell, direction of arrival) states, so that turns can be penalized
+    fn path(&self, start: usize, goal: usize, blocked: &[bool]) -> Option<Vec<usize>> {
+        let state_count = self.columns * self.rows * DIRECTIONS.len();
+        let mut costs = vec![u32::MAX; state_count];
+        let mut previous = vec![usize::MAX; state_count];
+        let mut queue = BinaryHeap::new();
+
+        for direction in 0..DIRECTIONS.len() {
+            let state = start * DIRECTIONS.len() + direction;
+            costs[state] = 0;
+            queue.push(Reverse((self.heuristic(start, goal), 0, state)));
+        }
+
+        while let Some(Reverse((_, cost, state))) = queue.pop() {
+            if cost > costs[state] {
+                continue;
+            }
+
+            let (cell, direction) = (state / DIRECTIONS.len(), state % DIRECTIONS.len());
+            if cell == goal {
+                let mut cells = vec![cell];
+                let mut state = state;
+                while previous[state] != usize::MAX {
+                    state = previous[state];
+                    cells.push(state / DIRECTIONS.len());
+                }
+                cells.reverse();
+                return Some(cells);
+            }
+
+            for (next_direction, step) in DIRECTIONS.iter().enumerate() {
+                let Some(next_cell) = self.neighbour(cell, *step) else {
+                    continue;
+                };
+                if blocked[next_cell] {
+                    continue;
+                }
+
+                let turn = if next_direction == direction || cell == start {
+                    0
+                } else {
+                    TURN_COST
+                };
+                let next_cost = cost + STEP_COST + turn;
+                let next_state = next_cell * DIRECTIONS.len() + next_direction;
+                if next_cost < costs[next_state] {
+                    costs[next_state] = next_cost;
+                    previous[next_state] = state;
+                    queue.push(Reverse((
+                        next_cost + self.heuristic(next_cell, goal),
+                        next_cost,
+                        next_state,
+                    )));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::Size;
+
+    #[test]
+    fn path_goes_around_an_obstacle() {
+        let obstacle = Rectangle::new(Point::new(80.0, 0.0), Size::new(40.0, 100.0));
+
+        let corners = find_path(
+            Vector::new(0.0, 50.0),
+            Vector::new(200.0, 50.0),
+            &[obstacle],
+            5.0,
+        )
+        .unwrap();
+
+        assert!(!corners.is_empty());
+        for corner in corners {
+            assert!(!obstacle.contains(Point::new(corner.x, corner.y)));
+        }
+    }
+
+    #[test]
+    fn enclosed_target_is_unreachable() {
+        let walls = [
+            Rectangle::new(Point::new(85.0, 85.0), Size::new(30.0, 10.0)),
+            Rectangle::new(Point::new(85.0, 105.0), Size::new(30.0, 10.0)),
+            Rectangle::new(Point::new(85.0, 85.0), Size::new(10.0, 30.0)),
+            Rectangle::new(Point::new(105.0, 85.0), Size::new(10.0, 30.0)),
+        ];
+
+        let path = find_path(
+            Vector::new(0.0, 100.0),
+            Vector::new(100.0, 100.0),
+            &walls,
+            5.0,
+        );
+
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    fn degenerate_cell_sizes_are_rejected() {
+        let (from, to) = (Vector::new(0.0, 0.0), Vector::new(100.0, 0.0));
+
+        for cell_size in [0.0, -5.0, 0.1, f32::NAN, f32::INFINITY] {
+            assert_eq!(find_path(from, to, &[], cell_size), None);
+        }
+        assert_eq!(find_path(Vector::new(f32::NAN, 0.0), to, &[], 5.0), None);
+    }
+
+    #[test]
+    fn claimed_channels_keep_apart() {
+        let mut channels = Channels::default();
+
+        assert_eq!(channels.claim(true, 100.0, (0.0, 50.0), 10.0), 100.0);
+        assert_eq!(channels.claim(true, 100.0, (20.0, 80.0), 10.0), 110.0);
+        assert_eq!(channels.claim(true, 100.0, (30.0, 40.0), 10.0), 90.0);
+
+        // Segments that do not overlap, or run the other way, may share the position
+        assert_eq!(channels.claim(true, 100.0, (60.0, 90.0), 10.0), 100.0);
+        assert_eq!(channels.claim(false, 100.0, (0.0, 50.0), 10.0), 100.0);
+
+        channels.clear();
+        assert_eq!(channels.claim(true, 100.0, (0.0, 50.0), 10.0), 100.0);
+    }
+
+    #[test]
+    fn claiming_without_spacing_keeps_the_position() {
+        let mut channels = Channels::default();
+
+        assert_eq!(channels.claim(true, 100.0, (0.0, 50.0), 0.0), 100.0);
+        assert_eq!(channels.claim(true, 100.0, (0.0, 50.0), 0.0), 100.0);
+    }
+}
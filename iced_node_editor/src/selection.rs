@@ -0,0 +1,246 @@
+use iced::{Point, Rectangle};
+
+use crate::graph::Adjacency;
+use crate::Link;
+
+// The selected nodes (by index) and links of a graph, see `GraphContainer::selection`. Both are
+// kept in the order they were selected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    nodes: Vec<usize>,
+    links: Vec<Link>,
+}
+
+// The shape that is drawn by dragging the empty canvas to select the nodes within it, see
+// `GraphContainer::marquee`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Marquee {
+    // The rectangle spanned by the press and the cursor
+    Box,
+    // The freeform path of the cursor, closed by a straight line back to the press
+    Lasso,
+}
+
+impl Marquee {
+    // Whether the marquee drawn along the path (in the same coordinates) contains the point
+    pub(crate) fn contains(&self, path: &[Point], point: Point) -> bool {
+        let (Some(first), Some(last)) = (path.first(), path.last()) else {
+            return false;
+        };
+
+        match self {
+            Marquee::Box => {
+                let top_left = Point::new(first.x.min(last.x), first.y.min(last.y));
+                let bottom_right = Point::new(first.x.max(last.x), first.y.max(last.y));
+                Rectangle::new(top_left, (bottom_right - top_left).into()).contains(point)
+            }
+            // Even-odd rule: count the edges that a ray to the right of the point crosses
+            Marquee::Lasso => {
+                let mut inside = false;
+                let mut previous = *last;
+                for current in path {
+                    if (current.y > point.y) != (previous.y > point.y) {
+                        let x = current.x
+                            + (point.y - current.y) / (previous.y - current.y)
+                                * (previous.x - current.x);
+                        if point.x < x {
+                            inside = !inside;
+                        }
+                    }
+                    previous = *current;
+                }
+                inside
+            }
+        }
+    }
+}
+
+// Something that can be selected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Selectable {
+    Node(usize),
+    Link(Link),
+}
+
+impl Selection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn nodes(&self) -> &[usize] {
+        &self.nodes
+    }
+
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.links.is_empty()
+    }
+
+    pub fn contains_node(&self, node: usize) -> bool {
+        self.nodes.contains(&node)
+    }
+
+    pub fn contains_link(&self, link: &Link) -> bool {
+        self.links.contains(link)
+    }
+
+    pub fn select_node(&mut self, node: usize) {
+        if !self.contains_node(node) {
+            self.nodes.push(node);
+        }
+    }
+
+    pub fn select_link(&mut self, link: Link) {
+        if !self.contains_link(&link) {
+            self.links.push(link);
+        }
+    }
+
+    pub fn deselect_node(&mut self, node: usize) {
+        self.nodes.retain(|selected| *selected != node);
+    }
+
+    pub fn deselect_link(&mut self, link: &Link) {
+        self.links.retain(|selected| selected != link);
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.links.clear();
+    }
+
+    pub fn with_node(mut self, node: usize) -> Self {
+        self.select_node(node);
+        self
+    }
+
+    pub fn with_link(mut self, link: Link) -> Self {
+        self.select_link(link);
+        self
+    }
+
+    // Adds every node that is reachable from a selected node by following the links, e.g. for
+    // "select downstream" in a dataflow graph
+    pub fn with_downstream(self, links: &[Link]) -> Self {
+        self.with_reachable(links, Adjacency::downstream)
+    }
+
+    // Adds every node from which a selected node is reachable by following the links
+    pub fn with_upstream(self, links: &[Link]) -> Self {
+        self.with_reachable(links, Adjacency::upstream)
+    }
+
+    fn with_reachable(
+        mut self,
+        links: &[Link],
+        reachable: impl Fn(&Adjacency, usize) -> Vec<usize>,
+    ) -> Self {
+        let adjacency = Adjacency::new(links);
+        let reached: Vec<usize> = self
+            .nodes
+            .iter()
+            .flat_map(|node| reachable(&adjacency, *node))
+            .collect();
+        for node in reached {
+            self.select_node(node);
+        }
+        self
+    }
+
+    pub(crate) fn contains(&self, item: &Selectable) -> bool {
+        match item {
+            Selectable::Node(node) => self.contains_node(*node),
+            Selectable::Link(link) => self.contains_link(link),
+        }
+    }
+
+    // The selection after clicking the item: with `add`, the item is toggled and the rest stays
+    // selected. Otherwise, the item becomes the only selected one, unless it is already selected,
+    // so that pressing on one of several selected nodes can drag all of them.
+    pub(crate) fn clicked(&self, item: Selectable, add: bool) -> Selection {
+        if !add && self.contains(&item) {
+            return self.clone();
+        }
+
+        let mut selection = if add { self.clone() } else { Selection::new() };
+        match item {
+            Selectable::Node(node) if selection.contains_node(node) => {
+                selection.deselect_node(node)
+            }
+            Selectable::Node(node) => selection.select_node(node),
+            Selectable::Link(link) if selection.contains_link(&link) => {
+                selection.deselect_link(&link)
+            }
+            Selectable::Link(link) => selection.select_link(link),
+        }
+        selection
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clicking_replaces_the_selection() {
+        let selection = Selection::new().with_node(0).with_node(1);
+
+        assert_eq!(
+            selection.clicked(Selectable::Node(2), false),
+            Selection::new().with_node(2)
+        );
+        // Pressing on a selected node keeps the others, so that all of them can be dragged
+        assert_eq!(selection.clicked(Selectable::Node(1), false), selection);
+    }
+
+    #[test]
+    fn clicking_with_add_toggles_the_item() {
+        let selection = Selection::new().with_node(0).with_node(1);
+
+        assert_eq!(
+            selection.clicked(Selectable::Node(2), true),
+            selection.clone().with_node(2)
+        );
+        assert_eq!(
+            selection.clicked(Selectable::Node(0), true),
+            Selection::new().with_node(1)
+        );
+    }
+
+    #[test]
+    fn box_marquee_spans_the_first_and_last_point() {
+        // Dragged up and to the left, with the points in between not mattering
+        let path = [
+            Point::new(100.0, 100.0),
+            Point::new(500.0, 0.0),
+            Point::new(0.0, 20.0),
+        ];
+
+        assert!(Marquee::Box.contains(&path, Point::new(50.0, 50.0)));
+        assert!(!Marquee::Box.contains(&path, Point::new(150.0, 50.0)));
+        assert!(!Marquee::Box.contains(&path, Point::new(50.0, 10.0)));
+        assert!(!Marquee::Box.contains(&[], Point::ORIGIN));
+    }
+
+    #[test]
+    fn lasso_marquee_follows_the_path() {
+        // An L shape, closed back to the first point
+        let path = [
+            Point::new(0.0, 0.0),
+            Point::new(20.0, 0.0),
+            Point::new(20.0, 80.0),
+            Point::new(100.0, 80.0),
+            Point::new(100.0, 100.0),
+            Point::new(0.0, 100.0),
+        ];
+
+        assert!(Marquee::Lasso.contains(&path, Point::new(10.0, 50.0)));
+        assert!(Marquee::Lasso.contains(&path, Point::new(90.0, 90.0)));
+        assert!(!Marquee::Lasso.contains(&path, Point::new(60.0, 40.0)));
+        assert!(!Marquee::Lasso.contains(&path, Point::new(150.0, 90.0)));
+        assert!(!Marquee::Lasso.contains(&path[..1], path[0]));
+    }
+}
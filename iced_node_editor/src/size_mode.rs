@@ -0,0 +1,38 @@
+// A length that either stays the same on screen, or is part of the graph and grows and shrinks
+// with the zoom level
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeMode {
+    // In screen pixels, regardless of the scale of the graph
+    Screen(f32),
+    // In graph units
+    Graph(f32),
+}
+
+impl SizeMode {
+    // The length in screen pixels at the given scale of the graph
+    pub fn resolve(self, scale: f32) -> f32 {
+        self.value() * self.multiplier(scale)
+    }
+
+    pub fn value(self) -> f32 {
+        match self {
+            SizeMode::Screen(value) | SizeMode::Graph(value) => value,
+        }
+    }
+
+    // What lengths that are given in the same units as this one are multiplied with to get
+    // screen pixels
+    pub(crate) fn multiplier(self, scale: f32) -> f32 {
+        match self {
+            SizeMode::Screen(_) => 1.0,
+            SizeMode::Graph(_) => scale,
+        }
+    }
+}
+
+// Plain numbers are screen pixels, as they have always been
+impl From<f32> for SizeMode {
+    fn from(value: f32) -> Self {
+        SizeMode::Screen(value)
+    }
+}
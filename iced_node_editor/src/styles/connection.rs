@@ -1,8 +1,13 @@
 use iced::{Color, Theme};
 
+use super::preset::Preset;
+
 #[derive(Debug, Clone, Copy)]
 pub struct Appearance {
     pub color: Option<Color>,
+    // Color of the breakpoint glyph, see `Connection::breakpoint`. The color of the connection
+    // is used if `None`.
+    pub breakpoint_color: Option<Color>,
 }
 
 pub trait StyleSheet {
@@ -26,8 +31,30 @@ impl StyleSheet for Theme {
         match style {
             Node::Default => Appearance {
                 color: Some(palette.primary.base.color),
+                breakpoint_color: Some(palette.danger.base.color),
             },
             Node::Custom(custom) => custom.appearance(self),
         }
     }
 }
+
+// Allows styling with a closure, e.g. `.style(|theme: &Theme| Appearance { .. })`
+impl<T: Fn(&Theme) -> Appearance> StyleSheet for T {
+    type Style = Theme;
+
+    fn appearance(&self, style: &Self::Style) -> Appearance {
+        (self)(style)
+    }
+}
+
+impl<T: Fn(&Theme) -> Appearance + 'static> From<T> for Node {
+    fn from(f: T) -> Self {
+        Node::Custom(Box::new(f))
+    }
+}
+
+impl From<Preset> for Node {
+    fn from(preset: Preset) -> Self {
+        Node::Custom(Box::new(preset))
+    }
+}
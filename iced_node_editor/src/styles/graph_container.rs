@@ -1,6 +1,8 @@
-use iced::{Background, Color, Theme};
+use iced::{Background, Color, Shadow, Theme, Vector};
 use palette::{Darken, FromColor, Hsl, Lighten, Srgb};
 
+use super::{node_palette, preset::Preset};
+
 #[derive(Debug, Clone, Copy)]
 pub struct Appearance {
     pub background: Option<Background>,
@@ -10,11 +12,63 @@ pub struct Appearance {
     pub minor_guidelines_spacing: Option<f32>,
     pub mid_guidelines_spacing: Option<f32>,
     pub major_guidelines_spacing: Option<f32>,
+    // The kind of grid drawn behind the graph, unless the container sets its own with
+    // `GraphContainer::grid_style`, which takes precedence
+    pub grid_kind: Option<GridStyle>,
+    // Color of the horizontal line through the graph origin (y = 0)
+    pub x_axis_color: Option<Color>,
+    // Color of the vertical line through the graph origin (x = 0)
+    pub y_axis_color: Option<Color>,
+    // Color of the ring around the socket focused with the keyboard
+    pub focus_color: Option<Color>,
+    // Color of the ring around a socket that the connection being made may not be attached to
+    pub rejected_color: Option<Color>,
+    // Color of the outlines around the sockets and nodes at the ends of the hovered connection,
+    // see `GraphContainer::trace_connections`
+    pub traced_color: Option<Color>,
+    // Color of the outlines around selected nodes and the halos around selected connections,
+    // see `GraphContainer::selection`
+    pub selection_color: Option<Color>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GridStyle {
+    #[default]
+    Lines,
+    // A dot at every intersection of guidelines
+    Dots,
+    // A small cross at every intersection of guidelines
+    Cross,
+    None,
 }
 
 pub trait StyleSheet {
     type Style: Default;
     fn appearance(&self, style: &Self::Style) -> Appearance;
+
+    // The look of the node palette, derived from the appearance of the container by default
+    fn node_palette(&self, style: &Self::Style) -> node_palette::Appearance {
+        let appearance = self.appearance(style);
+
+        node_palette::Appearance {
+            background: appearance.background,
+            border_color: appearance
+                .major_guidelines_color
+                .unwrap_or(Color::from_rgb(0.5, 0.5, 0.5)),
+            border_width: 1.0,
+            border_radius: 4.0,
+            shadow: Some(Shadow {
+                color: Color::from_rgba(0.0, 0.0, 0.0, 0.3),
+                offset: Vector::new(0.0, 3.0),
+                blur_radius: 10.0,
+            }),
+            text_color: None,
+            placeholder_color: None,
+            selected_background: appearance
+                .focus_color
+                .map(|color| Background::Color(Color { a: 0.3, ..color })),
+        }
+    }
 }
 
 #[derive(Default)]
@@ -60,8 +114,36 @@ impl StyleSheet for Theme {
                 minor_guidelines_spacing: Some(10.0),
                 mid_guidelines_spacing: Some(50.0),
                 major_guidelines_spacing: Some(100.0),
+                grid_kind: Some(GridStyle::Lines),
+                x_axis_color: None,
+                y_axis_color: None,
+                focus_color: Some(palette.primary.strong.color),
+                rejected_color: Some(palette.danger.base.color),
+                traced_color: Some(palette.primary.base.color),
+                selection_color: Some(palette.primary.strong.color),
             },
             GraphContainer::Custom(custom) => custom.appearance(self),
         }
     }
 }
+
+// Allows styling with a closure, e.g. `.style(|theme: &Theme| Appearance { .. })`
+impl<T: Fn(&Theme) -> Appearance> StyleSheet for T {
+    type Style = Theme;
+
+    fn appearance(&self, style: &Self::Style) -> Appearance {
+        (self)(style)
+    }
+}
+
+impl<T: Fn(&Theme) -> Appearance + 'static> From<T> for GraphContainer {
+    fn from(f: T) -> Self {
+        GraphContainer::Custom(Box::new(f))
+    }
+}
+
+impl From<Preset> for GraphContainer {
+    fn from(preset: Preset) -> Self {
+        GraphContainer::Custom(Box::new(preset))
+    }
+}
@@ -1,3 +1,5 @@
+pub mod connection;
 pub mod graph_container;
 pub mod node;
-pub mod connection;
\ No newline at end of file
+pub mod node_palette;
+pub mod preset;
@@ -1,4 +1,10 @@
-use iced::{Background, Color, Theme};
+use iced::{Background, Color, Shadow, Theme, Vector};
+
+use super::preset::Preset;
+use crate::node::NodeStatus;
+
+// Minimum border width of a node with a status other than `NodeStatus::Idle`
+const STATUS_BORDER_WIDTH: f32 = 2.0;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Appearance {
@@ -7,6 +13,14 @@ pub struct Appearance {
     pub border_radius: f32,
     pub border_width: f32,
     pub border_color: Color,
+    // Background of the header, if the node has one
+    pub header_background: Option<Background>,
+    pub shadow: Option<Shadow>,
+    // Background and text color of the error strip, if the node has one
+    pub error_background: Option<Background>,
+    pub error_text_color: Option<Color>,
+    // Color of the pass-through line of a bypassed node; the border color if `None`
+    pub bypass_color: Option<Color>,
 }
 
 impl Default for Appearance {
@@ -17,6 +31,11 @@ impl Default for Appearance {
             border_radius: 0.0,
             border_width: 0.0,
             border_color: Color::TRANSPARENT,
+            header_background: None,
+            shadow: None,
+            error_background: None,
+            error_text_color: None,
+            bypass_color: None,
         }
     }
 }
@@ -24,6 +43,90 @@ impl Default for Appearance {
 pub trait StyleSheet {
     type Style: Default;
     fn appearance(&self, style: &Self::Style) -> Appearance;
+
+    // Used while the cursor is over the node
+    fn hovered(&self, style: &Self::Style) -> Appearance {
+        self.appearance(style)
+    }
+
+    fn disabled(&self, style: &Self::Style) -> Appearance {
+        dimmed(self.appearance(style))
+    }
+
+    // Used for nodes with a status other than `NodeStatus::Idle`. The border of a running node
+    // additionally pulses.
+    fn status(&self, style: &Self::Style, status: NodeStatus) -> Appearance {
+        with_status(
+            self.appearance(style),
+            status,
+            Color::from_rgb8(0x4c, 0x9a, 0xff),
+            Color::from_rgb8(0xe0, 0x40, 0x40),
+            Color::from_rgb8(0x4c, 0xaf, 0x50),
+        )
+    }
+}
+
+// The appearance with the border in the color of the status
+pub(crate) fn with_status(
+    active: Appearance,
+    status: NodeStatus,
+    running: Color,
+    error: Color,
+    done: Color,
+) -> Appearance {
+    let border_color = match status {
+        NodeStatus::Idle => return active,
+        NodeStatus::Running => running,
+        NodeStatus::Error => error,
+        NodeStatus::Done => done,
+    };
+
+    Appearance {
+        border_color,
+        border_width: active.border_width.max(STATUS_BORDER_WIDTH),
+        ..active
+    }
+}
+
+// The disabled variant of an appearance
+pub(crate) fn dimmed(active: Appearance) -> Appearance {
+    Appearance {
+        text_color: active.text_color.map(dim),
+        background: active.background.map(|background| match background {
+            Background::Color(color) => Background::Color(dim(color)),
+            background => background,
+        }),
+        border_color: dim(active.border_color),
+        header_background: active.header_background.map(|background| match background {
+            Background::Color(color) => Background::Color(dim(color)),
+            background => background,
+        }),
+        error_background: active.error_background.map(|background| match background {
+            Background::Color(color) => Background::Color(dim(color)),
+            background => background,
+        }),
+        error_text_color: active.error_text_color.map(dim),
+        ..active
+    }
+}
+
+// The appearance with a shadow below it, so that the node seems to lift off the graph
+pub(crate) fn lifted(active: Appearance) -> Appearance {
+    Appearance {
+        shadow: Some(Shadow {
+            color: Color::from_rgba(0.0, 0.0, 0.0, 0.35),
+            offset: Vector::new(0.0, 3.0),
+            blur_radius: 10.0,
+        }),
+        ..active
+    }
+}
+
+pub(crate) fn dim(color: Color) -> Color {
+    Color {
+        a: color.a * 0.5,
+        ..color
+    }
 }
 
 #[derive(Default)]
@@ -46,8 +149,56 @@ impl StyleSheet for Theme {
                 border_radius: 5.0,
                 border_width: 1.0,
                 text_color: Some(palette.primary.base.color),
+                header_background: Some(Background::Color(palette.background.weak.color)),
+                shadow: None,
+                error_background: Some(Background::Color(palette.danger.weak.color)),
+                error_text_color: Some(palette.danger.weak.text),
+                bypass_color: Some(palette.danger.base.color),
             },
             Node::Custom(custom) => custom.appearance(self),
         }
     }
+
+    fn hovered(&self, style: &Self::Style) -> Appearance {
+        match style {
+            Node::Default => lifted(self.appearance(style)),
+            Node::Custom(custom) => custom.hovered(self),
+        }
+    }
+
+    fn status(&self, style: &Self::Style, status: NodeStatus) -> Appearance {
+        let palette = self.extended_palette();
+
+        match style {
+            Node::Default => with_status(
+                self.appearance(style),
+                status,
+                palette.primary.strong.color,
+                palette.danger.base.color,
+                palette.success.base.color,
+            ),
+            Node::Custom(custom) => custom.status(self, status),
+        }
+    }
+}
+
+// Allows styling with a closure, e.g. `.style(|theme: &Theme| Appearance { .. })`
+impl<T: Fn(&Theme) -> Appearance> StyleSheet for T {
+    type Style = Theme;
+
+    fn appearance(&self, style: &Self::Style) -> Appearance {
+        (self)(style)
+    }
+}
+
+impl<T: Fn(&Theme) -> Appearance + 'static> From<T> for Node {
+    fn from(f: T) -> Self {
+        Node::Custom(Box::new(f))
+    }
+}
+
+impl From<Preset> for Node {
+    fn from(preset: Preset) -> Self {
+        Node::Custom(Box::new(preset))
+    }
 }
@@ -0,0 +1,19 @@
+use iced::{Background, Color, Shadow};
+
+// The look of the node palette. It is part of the graph container's style sheet, see
+// `graph_container::StyleSheet::node_palette`.
+#[derive(Debug, Clone, Copy)]
+pub struct Appearance {
+    pub background: Option<Background>,
+    pub border_color: Color,
+    pub border_width: f32,
+    pub border_radius: f32,
+    pub shadow: Option<Shadow>,
+    // Defaults to the text color of the renderer
+    pub text_color: Option<Color>,
+    // Color of the placeholder and of the message shown when nothing matches. Defaults to the
+    // text color at half opacity.
+    pub placeholder_color: Option<Color>,
+    // Background of the result that is picked with Enter
+    pub selected_background: Option<Background>,
+}
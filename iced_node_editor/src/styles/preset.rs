@@ -0,0 +1,177 @@
+use iced::{Background, Color, Theme};
+
+use super::{connection, graph_container, node};
+use crate::node::NodeStatus;
+
+// Built-in looks that do not depend on the application theme. A preset can be passed to the
+// `style` method of the graph container, nodes and connections alike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    Dark,
+    Light,
+    // White lines on blue paper
+    Blueprint,
+    // Modelled after the node editors of Blender
+    Blender,
+}
+
+struct Colors {
+    background: Color,
+    minor_guidelines: Color,
+    mid_guidelines: Color,
+    major_guidelines: Color,
+    grid: graph_container::GridStyle,
+    node_background: Color,
+    node_header: Color,
+    node_border: Color,
+    node_border_radius: f32,
+    text: Color,
+    connection: Color,
+    focus: Color,
+    rejected: Color,
+    // Border of nodes that finished running
+    done: Color,
+}
+
+impl Preset {
+    fn colors(self) -> Colors {
+        match self {
+            Preset::Dark => Colors {
+                background: Color::from_rgb8(0x1e, 0x1f, 0x22),
+                minor_guidelines: Color::from_rgb8(0x23, 0x24, 0x27),
+                mid_guidelines: Color::from_rgb8(0x29, 0x2a, 0x2e),
+                major_guidelines: Color::from_rgb8(0x32, 0x34, 0x38),
+                grid: graph_container::GridStyle::Lines,
+                node_background: Color::from_rgb8(0x2b, 0x2d, 0x31),
+                node_header: Color::from_rgb8(0x38, 0x3a, 0x3f),
+                node_border: Color::from_rgb8(0x4e, 0x51, 0x57),
+                node_border_radius: 5.0,
+                text: Color::from_rgb8(0xdc, 0xde, 0xe1),
+                connection: Color::from_rgb8(0x9a, 0x9e, 0xa6),
+                focus: Color::from_rgb8(0x4c, 0x9a, 0xff),
+                rejected: Color::from_rgb8(0xf2, 0x5f, 0x5c),
+                done: Color::from_rgb8(0x5c, 0xb8, 0x5c),
+            },
+            Preset::Light => Colors {
+                background: Color::from_rgb8(0xf6, 0xf6, 0xf4),
+                minor_guidelines: Color::from_rgb8(0xee, 0xee, 0xec),
+                mid_guidelines: Color::from_rgb8(0xe4, 0xe4, 0xe1),
+                major_guidelines: Color::from_rgb8(0xd6, 0xd6, 0xd2),
+                grid: graph_container::GridStyle::Lines,
+                node_background: Color::WHITE,
+                node_header: Color::from_rgb8(0xea, 0xea, 0xe6),
+                node_border: Color::from_rgb8(0xb4, 0xb4, 0xb0),
+                node_border_radius: 5.0,
+                text: Color::from_rgb8(0x22, 0x22, 0x22),
+                connection: Color::from_rgb8(0x5c, 0x5c, 0x5c),
+                focus: Color::from_rgb8(0x1a, 0x6d, 0xe0),
+                rejected: Color::from_rgb8(0xc9, 0x2a, 0x2a),
+                done: Color::from_rgb8(0x2e, 0x8b, 0x3e),
+            },
+            Preset::Blueprint => Colors {
+                background: Color::from_rgb8(0x1b, 0x3f, 0x73),
+                minor_guidelines: Color::from_rgb8(0x22, 0x48, 0x7e),
+                mid_guidelines: Color::from_rgb8(0x2c, 0x55, 0x8c),
+                major_guidelines: Color::from_rgb8(0x3d, 0x68, 0x9e),
+                grid: graph_container::GridStyle::Lines,
+                node_background: Color::from_rgba8(0x1b, 0x3f, 0x73, 0.8),
+                node_header: Color::from_rgba8(0xff, 0xff, 0xff, 0.15),
+                node_border: Color::WHITE,
+                node_border_radius: 0.0,
+                text: Color::WHITE,
+                connection: Color::from_rgb8(0xe8, 0xf0, 0xfa),
+                focus: Color::from_rgb8(0xff, 0xd2, 0x4a),
+                rejected: Color::from_rgb8(0xff, 0x6b, 0x6b),
+                done: Color::from_rgb8(0x8b, 0xe9, 0x8b),
+            },
+            Preset::Blender => Colors {
+                background: Color::from_rgb8(0x1d, 0x1d, 0x1d),
+                minor_guidelines: Color::from_rgb8(0x1d, 0x1d, 0x1d),
+                mid_guidelines: Color::from_rgb8(0x28, 0x28, 0x28),
+                major_guidelines: Color::from_rgb8(0x33, 0x33, 0x33),
+                grid: graph_container::GridStyle::Dots,
+                node_background: Color::from_rgb8(0x30, 0x30, 0x30),
+                node_header: Color::from_rgb8(0x47, 0x47, 0x47),
+                node_border: Color::BLACK,
+                node_border_radius: 4.0,
+                text: Color::from_rgb8(0xe6, 0xe6, 0xe6),
+                connection: Color::from_rgb8(0xb4, 0xb4, 0xb4),
+                focus: Color::from_rgb8(0xff, 0xa5, 0x2e),
+                rejected: Color::from_rgb8(0xe0, 0x40, 0x40),
+                done: Color::from_rgb8(0x6a, 0xc2, 0x5a),
+            },
+        }
+    }
+}
+
+impl graph_container::StyleSheet for Preset {
+    type Style = Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> graph_container::Appearance {
+        let colors = self.colors();
+
+        graph_container::Appearance {
+            background: Some(Background::Color(colors.background)),
+            minor_guidelines_color: Some(colors.minor_guidelines),
+            mid_guidelines_color: Some(colors.mid_guidelines),
+            major_guidelines_color: Some(colors.major_guidelines),
+            minor_guidelines_spacing: Some(10.0),
+            mid_guidelines_spacing: Some(50.0),
+            major_guidelines_spacing: Some(100.0),
+            grid_kind: Some(colors.grid),
+            x_axis_color: None,
+            y_axis_color: None,
+            focus_color: Some(colors.focus),
+            rejected_color: Some(colors.rejected),
+            traced_color: Some(colors.focus),
+            selection_color: Some(colors.focus),
+        }
+    }
+}
+
+impl node::StyleSheet for Preset {
+    type Style = Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> node::Appearance {
+        let colors = self.colors();
+
+        node::Appearance {
+            text_color: Some(colors.text),
+            background: Some(Background::Color(colors.node_background)),
+            border_radius: colors.node_border_radius,
+            border_width: 1.0,
+            border_color: colors.node_border,
+            header_background: Some(Background::Color(colors.node_header)),
+            shadow: None,
+            error_background: Some(Background::Color(colors.rejected)),
+            error_text_color: Some(Color::WHITE),
+            bypass_color: Some(colors.rejected),
+        }
+    }
+
+    fn hovered(&self, style: &Self::Style) -> node::Appearance {
+        node::lifted(node::StyleSheet::appearance(self, style))
+    }
+
+    fn status(&self, style: &Self::Style, status: NodeStatus) -> node::Appearance {
+        let colors = self.colors();
+        node::with_status(
+            node::StyleSheet::appearance(self, style),
+            status,
+            colors.focus,
+            colors.rejected,
+            colors.done,
+        )
+    }
+}
+
+impl connection::StyleSheet for Preset {
+    type Style = Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> connection::Appearance {
+        connection::Appearance {
+            color: Some(self.colors().connection),
+            breakpoint_color: Some(self.colors().rejected),
+        }
+    }
+}